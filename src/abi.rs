@@ -0,0 +1,192 @@
+//! ABI encoding of parsed responses into the structs of the Solidity `QueryResponse`
+//! library, for contracts which take decoded responses rather than raw bytes.
+//!
+//! The output is `abi.encode(r)` of the struct: prefix it with a function selector to
+//! get calldata for a function taking the struct as its only argument.
+//!
+//! [`submission_calldata`] covers the other direction, passing raw response bytes and
+//! signatures to a contract which verifies them.
+
+use crate::structs::{
+    EthCallData, EthCallQueryRequest, EthCallQueryResponse, EthCallWithFinalityQueryRequest,
+    EthCallWithFinalityQueryResponse,
+};
+
+/// Encodes `EthCallQueryResponse { bytes requestBlockId; uint64 blockNum; uint64
+/// blockTime; bytes32 blockHash; EthCallData[] result; }`.
+pub fn encode_eth_call_response(
+    request: &EthCallQueryRequest,
+    response: &EthCallQueryResponse,
+) -> std::result::Result<Vec<u8>, std::io::Error> {
+    let results = call_results(response.zip(request)?);
+    Ok(encode(&Token::Tuple(vec![
+        Token::Bytes(request.block_tag.as_bytes().to_vec()),
+        Token::Uint(response.block_number),
+        Token::Uint(response.block_time),
+        Token::Bytes32(response.block_hash),
+        results,
+    ])))
+}
+
+/// Encodes `EthCallWithFinalityQueryResponse { bytes requestBlockId; bytes
+/// requestFinality; uint64 blockNum; uint64 blockTime; bytes32 blockHash; EthCallData[]
+/// result; }`.
+pub fn encode_eth_call_with_finality_response(
+    request: &EthCallWithFinalityQueryRequest,
+    response: &EthCallWithFinalityQueryResponse,
+) -> std::result::Result<Vec<u8>, std::io::Error> {
+    let results = call_results(response.zip(request)?);
+    Ok(encode(&Token::Tuple(vec![
+        Token::Bytes(request.block_tag.as_bytes().to_vec()),
+        Token::Bytes(request.finality.as_bytes().to_vec()),
+        Token::Uint(response.block_number),
+        Token::Uint(response.block_time),
+        Token::Bytes32(response.block_hash),
+        results,
+    ])))
+}
+
+/// Encodes `(bytes response, IWormhole.Signature[] signatures)`, the arguments of
+/// `verifyQueryResponseSignatures` and of entry points which pass them on to it.
+///
+/// Signatures are in the 66 byte guardian format; the recovery id is converted to the
+/// 27/28 `v` expected on chain.
+pub fn encode_signed_response(response: &[u8], signatures: &[[u8; 66]]) -> Vec<u8> {
+    encode_sequence(&[
+        Token::Bytes(response.to_vec()),
+        Token::Array(
+            signatures
+                .iter()
+                .map(|signature| {
+                    let mut r = [0u8; 32];
+                    let mut s = [0u8; 32];
+                    r.copy_from_slice(&signature[..32]);
+                    s.copy_from_slice(&signature[32..64]);
+                    let v = if signature[64] < 27 {
+                        signature[64] + 27
+                    } else {
+                        signature[64]
+                    };
+                    // Signature { bytes32 r; bytes32 s; uint8 v; uint8 guardianIndex; }
+                    Token::Tuple(vec![
+                        Token::Bytes32(r),
+                        Token::Bytes32(s),
+                        Token::Uint(v.into()),
+                        Token::Uint(signature[65].into()),
+                    ])
+                })
+                .collect(),
+        ),
+    ])
+}
+
+/// Calldata for a function taking `(bytes, IWormhole.Signature[])`, given its four byte
+/// selector.
+pub fn submission_calldata(selector: [u8; 4], response: &[u8], signatures: &[[u8; 66]]) -> Vec<u8> {
+    let mut calldata = selector.to_vec();
+    calldata.extend(encode_signed_response(response, signatures));
+    calldata
+}
+
+/// `EthCallData { address contractAddress; bytes callData; bytes result; }[]`
+fn call_results(pairs: Vec<(&EthCallData, &[u8])>) -> Token {
+    Token::Array(
+        pairs
+            .into_iter()
+            .map(|(call, result)| {
+                Token::Tuple(vec![
+                    Token::Address(call.to),
+                    Token::Bytes(call.data.clone()),
+                    Token::Bytes(result.to_vec()),
+                ])
+            })
+            .collect(),
+    )
+}
+
+enum Token {
+    Uint(u64),
+    Address([u8; 20]),
+    Bytes32([u8; 32]),
+    Bytes(Vec<u8>),
+    Array(Vec<Token>),
+    Tuple(Vec<Token>),
+}
+
+impl Token {
+    fn is_dynamic(&self) -> bool {
+        match self {
+            Token::Bytes(_) | Token::Array(_) => true,
+            Token::Tuple(tokens) => tokens.iter().any(Token::is_dynamic),
+            _ => false,
+        }
+    }
+}
+
+/// `abi.encode` of a single value: dynamic values are preceded by their offset.
+fn encode(token: &Token) -> Vec<u8> {
+    encode_sequence(std::slice::from_ref(token))
+}
+
+/// Heads followed by tails, with offsets relative to the start of the sequence.
+fn encode_sequence(tokens: &[Token]) -> Vec<u8> {
+    let mut head = Vec::with_capacity(tokens.len() * 32);
+    let mut tail = Vec::new();
+    let head_len = tokens
+        .iter()
+        .map(|token| {
+            if token.is_dynamic() {
+                32
+            } else {
+                static_len(token)
+            }
+        })
+        .sum::<usize>();
+    for token in tokens {
+        if token.is_dynamic() {
+            head.extend_from_slice(&word((head_len + tail.len()) as u64));
+            tail.extend(encode_value(token));
+        } else {
+            head.extend(encode_value(token));
+        }
+    }
+    head.extend(tail);
+    head
+}
+
+fn static_len(token: &Token) -> usize {
+    match token {
+        Token::Tuple(tokens) => tokens.iter().map(static_len).sum(),
+        _ => 32,
+    }
+}
+
+fn encode_value(token: &Token) -> Vec<u8> {
+    match token {
+        Token::Uint(value) => word(*value).to_vec(),
+        Token::Address(address) => {
+            let mut out = [0u8; 32];
+            out[12..].copy_from_slice(address);
+            out.to_vec()
+        }
+        Token::Bytes32(value) => value.to_vec(),
+        Token::Bytes(data) => {
+            let mut out = word(data.len() as u64).to_vec();
+            out.extend_from_slice(data);
+            out.resize(32 + data.len().div_ceil(32) * 32, 0);
+            out
+        }
+        Token::Array(tokens) => {
+            let mut out = word(tokens.len() as u64).to_vec();
+            out.extend(encode_sequence(tokens));
+            out
+        }
+        Token::Tuple(tokens) => encode_sequence(tokens),
+    }
+}
+
+fn word(value: u64) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    out[24..].copy_from_slice(&value.to_be_bytes());
+    out
+}
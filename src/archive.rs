@@ -0,0 +1,189 @@
+//! Append-only archive of signed query responses.
+//!
+//! Every record stores the time it was archived, the guardian signatures and the raw
+//! response bytes exactly as relayed, so the response can later be re-verified. Records
+//! are framed like [`crate::stream`] records and followed by a CRC-32 of the frame body:
+//!
+//! ```text
+//! u32 body length
+//! body:
+//!     u64 timestamp
+//!     u8  signature count
+//!     [u8; 66] signature, repeated (65 byte signature followed by the guardian index)
+//!     u32 response length
+//!     response bytes
+//! u32 CRC-32 (IEEE) of body
+//! ```
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::io::{Cursor, Read, Write};
+
+use crate::stream::{read_record, write_framed};
+use crate::structs::wire::read_bytes;
+use crate::structs::QueryResponse;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArchiveRecord {
+    /// Caller defined timestamp, typically unix microseconds at the time of relaying.
+    pub timestamp: u64,
+    pub signatures: Vec<[u8; 66]>,
+    pub response: Vec<u8>,
+}
+
+impl ArchiveRecord {
+    pub fn parse_response(&self) -> std::result::Result<QueryResponse, std::io::Error> {
+        QueryResponse::deserialize(&self.response)
+    }
+
+    fn serialize_body(&self) -> std::result::Result<Vec<u8>, std::io::Error> {
+        let mut body = Vec::new();
+        body.write_u64::<BigEndian>(self.timestamp)?;
+        let num_signatures: u8 = self.signatures.len().try_into().map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, "TooManySignatures")
+        })?;
+        body.write_u8(num_signatures)?;
+        for signature in &self.signatures {
+            body.write_all(signature)?;
+        }
+        write_framed(&mut body, &self.response)?;
+        Ok(body)
+    }
+
+    fn deserialize_body(body: &[u8]) -> std::result::Result<ArchiveRecord, std::io::Error> {
+        let mut rdr = Cursor::new(body);
+        let timestamp = rdr.read_u64::<BigEndian>()?;
+        let num_signatures = rdr.read_u8()?;
+        let mut signatures = Vec::with_capacity(num_signatures.into());
+        for _ in 0..num_signatures {
+            let mut signature = [0u8; 66];
+            rdr.read_exact(&mut signature)?;
+            signatures.push(signature);
+        }
+        let response_len = rdr.read_u32::<BigEndian>()?;
+        let response = read_bytes(&mut rdr, response_len)?;
+        if rdr.position() != body.len() as u64 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "InvalidRecordLength",
+            ));
+        }
+        Ok(ArchiveRecord {
+            timestamp,
+            signatures,
+            response,
+        })
+    }
+}
+
+/// Appends [`ArchiveRecord`]s to an underlying writer, usually a file opened in append mode.
+pub struct ArchiveWriter<W> {
+    wtr: W,
+}
+
+impl<W: Write> ArchiveWriter<W> {
+    pub fn new(wtr: W) -> ArchiveWriter<W> {
+        ArchiveWriter { wtr }
+    }
+
+    pub fn append(&mut self, record: &ArchiveRecord) -> std::result::Result<(), std::io::Error> {
+        let body = record.serialize_body()?;
+        // Write the whole record at once so a crash can at worst truncate the last record.
+        let mut framed = Vec::with_capacity(body.len() + 8);
+        write_framed(&mut framed, &body)?;
+        framed.write_u32::<BigEndian>(crc32(&body))?;
+        self.wtr.write_all(&framed)
+    }
+
+    pub fn flush(&mut self) -> std::result::Result<(), std::io::Error> {
+        self.wtr.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.wtr
+    }
+}
+
+/// Reads [`ArchiveRecord`]s back in the order they were appended.
+///
+/// A record with a bad checksum is yielded as a `ChecksumMismatch` error and reading
+/// continues with the next record. A truncated record ends the iteration after its error.
+pub struct ArchiveReader<R> {
+    rdr: R,
+    done: bool,
+}
+
+impl<R: Read> ArchiveReader<R> {
+    pub fn new(rdr: R) -> ArchiveReader<R> {
+        ArchiveReader { rdr, done: false }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.rdr
+    }
+
+    fn read_next(&mut self) -> std::result::Result<Option<ArchiveRecord>, std::io::Error> {
+        let body = match read_record(&mut self.rdr)? {
+            Some(body) => body,
+            None => return Ok(None),
+        };
+        let checksum = self.rdr.read_u32::<BigEndian>().map_err(|err| {
+            if err.kind() == std::io::ErrorKind::UnexpectedEof {
+                std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "TruncatedRecord")
+            } else {
+                err
+            }
+        })?;
+        if checksum != crc32(&body) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "ChecksumMismatch",
+            ));
+        }
+        ArchiveRecord::deserialize_body(&body).map(Some)
+    }
+}
+
+impl<R: Read> Iterator for ArchiveReader<R> {
+    type Item = std::result::Result<ArchiveRecord, std::io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let record = self.read_next().transpose();
+        match &record {
+            None => self.done = true,
+            Some(Err(err)) if err.kind() != std::io::ErrorKind::InvalidData => self.done = true,
+            _ => {}
+        }
+        record
+    }
+}
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc = CRC32_TABLE[((crc ^ u32::from(*byte)) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
@@ -0,0 +1,97 @@
+//! Upper bounds on the serialized size of a response, before the request is sent.
+//!
+//! Everything in a response is fixed size or follows from the request except the data
+//! returned, which is bounded by [`ResponseSizeCaps`] unless a Solana data slice already
+//! limits it. Useful to check a response will fit in a transaction and to split requests
+//! which would not.
+
+#[cfg(feature = "experimental")]
+use crate::structs::NearAccountResult;
+use crate::structs::{ChainSpecificQuery, PerChainQueryRequest, QueryRequest};
+
+/// Largest data the caller expects back where the request does not bound it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResponseSizeCaps {
+    /// Per eth_call (or NEAR view call) result.
+    pub max_call_result_len: usize,
+    /// Per Solana account, when the request has no data slice.
+    pub max_account_data_len: usize,
+}
+
+// version, request chain id, off chain (signature) request id, request length.
+const RESPONSE_HEADER_LEN: usize = 1 + 2 + 65 + 4;
+// chain id, query type, response length.
+const PER_CHAIN_HEADER_LEN: usize = 2 + 1 + 4;
+// number, hash, time.
+const BLOCK_LEN: usize = 8 + 32 + 8;
+// lamports, rent epoch, executable, owner, data length.
+const ACCOUNT_HEADER_LEN: usize = 8 + 8 + 1 + 32 + 4;
+
+/// Maximum serialized size of the response to `request`. Fails with `SizeOverflow` if
+/// the bound does not fit in a `usize`.
+pub fn estimate_response_size(
+    request: &QueryRequest,
+    caps: &ResponseSizeCaps,
+) -> std::result::Result<usize, std::io::Error> {
+    let mut size = (RESPONSE_HEADER_LEN + 1)
+        .checked_add(request.serialize()?.len())
+        .ok_or_else(overflow)?;
+    for per_chain in &request.requests {
+        size = size
+            .checked_add(estimate_per_chain_response_size(per_chain, caps)?)
+            .ok_or_else(overflow)?;
+    }
+    Ok(size)
+}
+
+/// Maximum serialized size of the per chain response to `request`, including its chain
+/// id, query type and length prefix. Fails with `SizeOverflow` if the bound does not fit
+/// in a `usize`.
+pub fn estimate_per_chain_response_size(
+    request: &PerChainQueryRequest,
+    caps: &ResponseSizeCaps,
+) -> std::result::Result<usize, std::io::Error> {
+    // A count byte followed by `count` entries of `entry_len` bytes each.
+    let list = |count: usize, entry_len: Option<usize>| {
+        entry_len
+            .and_then(|entry_len| count.checked_mul(entry_len))
+            .and_then(|len| len.checked_add(1))
+    };
+    let call_results = |calls: usize| list(calls, caps.max_call_result_len.checked_add(4));
+    let body = match &request.query {
+        ChainSpecificQuery::EthCallQueryRequest(query) => call_results(query.call_data.len()),
+        ChainSpecificQuery::EthCallByTimestampQueryRequest(query) => {
+            call_results(query.call_data.len()).and_then(|len| len.checked_add(BLOCK_LEN))
+        }
+        ChainSpecificQuery::EthCallWithFinalityQueryRequest(query) => {
+            call_results(query.call_data.len())
+        }
+        #[cfg(feature = "experimental")]
+        ChainSpecificQuery::EthCallWithOverridesQueryRequest(query) => {
+            call_results(query.call_data.len())
+        }
+        #[cfg(feature = "experimental")]
+        ChainSpecificQuery::NearViewCallQueryRequest(query) => call_results(query.calls.len()),
+        #[cfg(feature = "experimental")]
+        ChainSpecificQuery::NearViewAccountQueryRequest(query) => {
+            list(query.account_ids.len(), Some(NearAccountResult::LEN))
+        }
+        ChainSpecificQuery::SolanaAccountQueryRequest(query) => {
+            let data_len = if query.data_slice_length > 0 {
+                usize::try_from(query.data_slice_length).ok()
+            } else {
+                Some(caps.max_account_data_len)
+            };
+            list(
+                query.accounts.len(),
+                data_len.and_then(|len| len.checked_add(ACCOUNT_HEADER_LEN)),
+            )
+        }
+    };
+    body.and_then(|len| len.checked_add(PER_CHAIN_HEADER_LEN + BLOCK_LEN))
+        .ok_or_else(overflow)
+}
+
+fn overflow() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidInput, "SizeOverflow")
+}
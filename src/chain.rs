@@ -0,0 +1,222 @@
+//! Wormhole chain ids.
+//!
+//! [`ChainId`] converts losslessly to and from `u16`, the same representation the
+//! `wormhole-sdk` / `wormhole-vaas-serde` `Chain` types convert through, so moving between
+//! the two is `Chain::from(u16::from(id))` and `ChainId::from(u16::from(chain))`. Names
+//! follow the `Chain` variant names and parse case insensitively.
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ChainId(pub u16);
+
+impl ChainId {
+    pub const UNSET: ChainId = ChainId(0);
+    pub const SOLANA: ChainId = ChainId(1);
+    pub const ETHEREUM: ChainId = ChainId(2);
+    pub const TERRA: ChainId = ChainId(3);
+    pub const BSC: ChainId = ChainId(4);
+    pub const POLYGON: ChainId = ChainId(5);
+    pub const AVALANCHE: ChainId = ChainId(6);
+    pub const OASIS: ChainId = ChainId(7);
+    pub const ALGORAND: ChainId = ChainId(8);
+    pub const AURORA: ChainId = ChainId(9);
+    pub const FANTOM: ChainId = ChainId(10);
+    pub const KARURA: ChainId = ChainId(11);
+    pub const ACALA: ChainId = ChainId(12);
+    pub const KLAYTN: ChainId = ChainId(13);
+    pub const CELO: ChainId = ChainId(14);
+    pub const NEAR: ChainId = ChainId(15);
+    pub const MOONBEAM: ChainId = ChainId(16);
+    pub const NEON: ChainId = ChainId(17);
+    pub const TERRA2: ChainId = ChainId(18);
+    pub const INJECTIVE: ChainId = ChainId(19);
+    pub const OSMOSIS: ChainId = ChainId(20);
+    pub const SUI: ChainId = ChainId(21);
+    pub const APTOS: ChainId = ChainId(22);
+    pub const ARBITRUM: ChainId = ChainId(23);
+    pub const OPTIMISM: ChainId = ChainId(24);
+    pub const GNOSIS: ChainId = ChainId(25);
+    pub const PYTHNET: ChainId = ChainId(26);
+    pub const XPLA: ChainId = ChainId(28);
+    pub const BTC: ChainId = ChainId(29);
+    pub const BASE: ChainId = ChainId(30);
+    pub const SEI: ChainId = ChainId(32);
+    pub const ROOTSTOCK: ChainId = ChainId(33);
+    pub const SCROLL: ChainId = ChainId(34);
+    pub const MANTLE: ChainId = ChainId(35);
+    pub const BLAST: ChainId = ChainId(36);
+    pub const XLAYER: ChainId = ChainId(37);
+    pub const LINEA: ChainId = ChainId(38);
+    pub const BERACHAIN: ChainId = ChainId(39);
+    pub const SEIEVM: ChainId = ChainId(40);
+    pub const UNICHAIN: ChainId = ChainId(44);
+    pub const WORLDCHAIN: ChainId = ChainId(45);
+    pub const INK: ChainId = ChainId(46);
+    pub const HYPEREVM: ChainId = ChainId(47);
+    pub const MONAD: ChainId = ChainId(48);
+    pub const WORMCHAIN: ChainId = ChainId(3104);
+    pub const SEPOLIA: ChainId = ChainId(10002);
+    pub const ARBITRUM_SEPOLIA: ChainId = ChainId(10003);
+    pub const BASE_SEPOLIA: ChainId = ChainId(10004);
+    pub const OPTIMISM_SEPOLIA: ChainId = ChainId(10005);
+    pub const HOLESKY: ChainId = ChainId(10006);
+    pub const POLYGON_SEPOLIA: ChainId = ChainId(10007);
+
+    /// The `Chain` variant name for known ids.
+    pub fn name(&self) -> Option<&'static str> {
+        CHAIN_NAMES
+            .iter()
+            .find(|(id, _)| *id == *self)
+            .map(|(_, name)| *name)
+    }
+
+    /// The EVM network chain id for EVM chains. Wormhole ids shared between mainnet and
+    /// testnet map to the mainnet network.
+    pub fn to_evm_chain_id(&self) -> Option<u64> {
+        EVM_CHAIN_IDS
+            .iter()
+            .find(|(id, _)| *id == *self)
+            .map(|(_, evm_chain_id)| *evm_chain_id)
+    }
+
+    pub fn from_evm_chain_id(evm_chain_id: u64) -> Option<ChainId> {
+        EVM_CHAIN_IDS
+            .iter()
+            .find(|(_, id)| *id == evm_chain_id)
+            .map(|(id, _)| *id)
+    }
+}
+
+const CHAIN_NAMES: &[(ChainId, &str)] = &[
+    (ChainId::UNSET, "Unset"),
+    (ChainId::SOLANA, "Solana"),
+    (ChainId::ETHEREUM, "Ethereum"),
+    (ChainId::TERRA, "Terra"),
+    (ChainId::BSC, "Bsc"),
+    (ChainId::POLYGON, "Polygon"),
+    (ChainId::AVALANCHE, "Avalanche"),
+    (ChainId::OASIS, "Oasis"),
+    (ChainId::ALGORAND, "Algorand"),
+    (ChainId::AURORA, "Aurora"),
+    (ChainId::FANTOM, "Fantom"),
+    (ChainId::KARURA, "Karura"),
+    (ChainId::ACALA, "Acala"),
+    (ChainId::KLAYTN, "Klaytn"),
+    (ChainId::CELO, "Celo"),
+    (ChainId::NEAR, "Near"),
+    (ChainId::MOONBEAM, "Moonbeam"),
+    (ChainId::NEON, "Neon"),
+    (ChainId::TERRA2, "Terra2"),
+    (ChainId::INJECTIVE, "Injective"),
+    (ChainId::OSMOSIS, "Osmosis"),
+    (ChainId::SUI, "Sui"),
+    (ChainId::APTOS, "Aptos"),
+    (ChainId::ARBITRUM, "Arbitrum"),
+    (ChainId::OPTIMISM, "Optimism"),
+    (ChainId::GNOSIS, "Gnosis"),
+    (ChainId::PYTHNET, "Pythnet"),
+    (ChainId::XPLA, "Xpla"),
+    (ChainId::BTC, "Btc"),
+    (ChainId::BASE, "Base"),
+    (ChainId::SEI, "Sei"),
+    (ChainId::ROOTSTOCK, "Rootstock"),
+    (ChainId::SCROLL, "Scroll"),
+    (ChainId::MANTLE, "Mantle"),
+    (ChainId::BLAST, "Blast"),
+    (ChainId::XLAYER, "Xlayer"),
+    (ChainId::LINEA, "Linea"),
+    (ChainId::BERACHAIN, "Berachain"),
+    (ChainId::SEIEVM, "Seievm"),
+    (ChainId::UNICHAIN, "Unichain"),
+    (ChainId::WORLDCHAIN, "Worldchain"),
+    (ChainId::INK, "Ink"),
+    (ChainId::HYPEREVM, "HyperEVM"),
+    (ChainId::MONAD, "Monad"),
+    (ChainId::WORMCHAIN, "Wormchain"),
+    (ChainId::SEPOLIA, "Sepolia"),
+    (ChainId::ARBITRUM_SEPOLIA, "ArbitrumSepolia"),
+    (ChainId::BASE_SEPOLIA, "BaseSepolia"),
+    (ChainId::OPTIMISM_SEPOLIA, "OptimismSepolia"),
+    (ChainId::HOLESKY, "Holesky"),
+    (ChainId::POLYGON_SEPOLIA, "PolygonSepolia"),
+];
+
+const EVM_CHAIN_IDS: &[(ChainId, u64)] = &[
+    (ChainId::ETHEREUM, 1),
+    (ChainId::BSC, 56),
+    (ChainId::POLYGON, 137),
+    (ChainId::AVALANCHE, 43114),
+    (ChainId::OASIS, 42262),
+    (ChainId::AURORA, 1313161554),
+    (ChainId::FANTOM, 250),
+    (ChainId::KARURA, 686),
+    (ChainId::ACALA, 787),
+    (ChainId::KLAYTN, 8217),
+    (ChainId::CELO, 42220),
+    (ChainId::MOONBEAM, 1284),
+    (ChainId::NEON, 245022934),
+    (ChainId::ARBITRUM, 42161),
+    (ChainId::OPTIMISM, 10),
+    (ChainId::GNOSIS, 100),
+    (ChainId::BASE, 8453),
+    (ChainId::ROOTSTOCK, 30),
+    (ChainId::SCROLL, 534352),
+    (ChainId::MANTLE, 5000),
+    (ChainId::BLAST, 81457),
+    (ChainId::XLAYER, 196),
+    (ChainId::LINEA, 59144),
+    (ChainId::BERACHAIN, 80094),
+    (ChainId::SEIEVM, 1329),
+    (ChainId::UNICHAIN, 130),
+    (ChainId::WORLDCHAIN, 480),
+    (ChainId::INK, 57073),
+    (ChainId::HYPEREVM, 999),
+    (ChainId::MONAD, 143),
+    (ChainId::SEPOLIA, 11155111),
+    (ChainId::ARBITRUM_SEPOLIA, 421614),
+    (ChainId::BASE_SEPOLIA, 84532),
+    (ChainId::OPTIMISM_SEPOLIA, 11155420),
+    (ChainId::HOLESKY, 17000),
+    (ChainId::POLYGON_SEPOLIA, 80002),
+];
+
+impl From<u16> for ChainId {
+    fn from(id: u16) -> ChainId {
+        ChainId(id)
+    }
+}
+
+impl From<ChainId> for u16 {
+    fn from(id: ChainId) -> u16 {
+        id.0
+    }
+}
+
+impl fmt::Display for ChainId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name() {
+            Some(name) => f.write_str(name),
+            None => write!(f, "Unknown({})", self.0),
+        }
+    }
+}
+
+impl FromStr for ChainId {
+    type Err = std::io::Error;
+
+    /// Accepts a chain name (case insensitive) or a numeric id.
+    fn from_str(s: &str) -> std::result::Result<ChainId, std::io::Error> {
+        if let Ok(id) = s.parse::<u16>() {
+            return Ok(ChainId(id));
+        }
+        CHAIN_NAMES
+            .iter()
+            .find(|(_, name)| name.eq_ignore_ascii_case(s))
+            .map(|(id, _)| *id)
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "UnknownChain"))
+    }
+}
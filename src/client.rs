@@ -0,0 +1,443 @@
+//! Client for a Wormhole query proxy (CCQ server).
+
+use std::fmt;
+
+use serde_json::{json, Value};
+
+use crate::structs::wire::decode_hex;
+use crate::structs::{QueryRequest, QueryResponse};
+
+#[cfg(all(feature = "crypto", feature = "tokio"))]
+use crate::signature::{GuardianSet, VerifiedResponse};
+#[cfg(all(feature = "crypto", feature = "tokio"))]
+use futures_core::Stream;
+#[cfg(all(feature = "crypto", feature = "tokio"))]
+use std::future::Future;
+#[cfg(all(feature = "crypto", feature = "tokio"))]
+use std::pin::Pin;
+#[cfg(all(feature = "crypto", feature = "tokio"))]
+use std::sync::Arc;
+#[cfg(all(feature = "crypto", feature = "tokio"))]
+use std::task::{ready, Context, Poll};
+#[cfg(all(feature = "crypto", feature = "tokio"))]
+use std::time::Duration;
+#[cfg(all(feature = "crypto", feature = "tokio"))]
+use tokio::task::JoinHandle;
+#[cfg(all(feature = "crypto", feature = "tokio"))]
+use tokio::time::MissedTickBehavior;
+
+#[cfg(feature = "websocket")]
+use futures_util::{SinkExt, StreamExt};
+#[cfg(feature = "websocket")]
+use tokio::net::TcpStream;
+#[cfg(feature = "websocket")]
+use tokio_tungstenite::tungstenite::Message;
+#[cfg(feature = "websocket")]
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+/// A response as returned by the proxy: the raw signed bytes and the guardian signatures
+/// over them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignedQueryResponse {
+    pub bytes: Vec<u8>,
+    pub signatures: Vec<[u8; 66]>,
+}
+
+impl SignedQueryResponse {
+    pub fn parse(&self) -> std::result::Result<QueryResponse, std::io::Error> {
+        QueryResponse::deserialize(&self.bytes)
+    }
+}
+
+#[derive(Clone)]
+pub struct QueryProxyClient {
+    pub url: String,
+    pub api_key: String,
+}
+
+/// The API key is never printed.
+impl fmt::Debug for QueryProxyClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryProxyClient")
+            .field("url", &self.url)
+            .field("api_key", &format_args!("<redacted>"))
+            .finish()
+    }
+}
+
+impl QueryProxyClient {
+    /// `url` is the proxy base url, e.g. `https://testnet.query.wormhole.com`.
+    pub fn new(url: impl Into<String>, api_key: impl Into<String>) -> QueryProxyClient {
+        QueryProxyClient {
+            url: url.into(),
+            api_key: api_key.into(),
+        }
+    }
+
+    /// Submits `request` to the proxy's `/v1/query` endpoint. The proxy signs the request
+    /// on the caller's behalf.
+    pub fn query(
+        &self,
+        request: &QueryRequest,
+    ) -> std::result::Result<SignedQueryResponse, QueryError> {
+        let body = json!({ "bytes": request.to_hex()? });
+        let mut response = ureq::post(format!("{}/v1/query", self.url.trim_end_matches('/')))
+            .config()
+            .http_status_as_error(false)
+            .build()
+            .header("X-API-Key", &self.api_key)
+            .send_json(&body)
+            .map_err(std::io::Error::other)?;
+        let status = response.status().as_u16();
+        let text = response
+            .body_mut()
+            .read_to_string()
+            .map_err(std::io::Error::other)?;
+        if status != 200 {
+            return Err(QueryError::from_proxy_reply(status, &text));
+        }
+        let reply: Value = serde_json::from_str(&text).map_err(|_| invalid_proxy_response())?;
+        Ok(parse_proxy_reply(&reply)?)
+    }
+}
+
+/// Why the proxy did not return a response.
+#[derive(Debug)]
+pub enum QueryError {
+    /// Too many requests for the API key; retry later.
+    RateLimited,
+    /// The API key is missing or unknown.
+    Unauthorized,
+    /// The request signature was rejected.
+    InvalidSignature,
+    /// The API key is not permitted to make a call in the request.
+    NotAllowed(String),
+    /// The guardians did not reach quorum on a response.
+    NoQuorum(String),
+    /// The proxy gave up waiting for the guardians.
+    Timeout,
+    /// Any other rejection of the request itself.
+    BadRequest(String),
+    /// Any other failure on the proxy side.
+    Proxy { status: u16, message: String },
+    /// The proxy could not be reached, or its reply could not be read.
+    Io(std::io::Error),
+}
+
+impl QueryError {
+    /// Classifies an error reply by status, and by message where the status is shared.
+    pub fn from_proxy_reply(status: u16, body: &str) -> QueryError {
+        let message = body.trim().to_string();
+        let lower = message.to_ascii_lowercase();
+        match status {
+            429 => QueryError::RateLimited,
+            401 => QueryError::Unauthorized,
+            403 if lower.contains("api key") => QueryError::Unauthorized,
+            403 => QueryError::NotAllowed(message),
+            504 => QueryError::Timeout,
+            _ if lower.contains("quorum") => QueryError::NoQuorum(message),
+            400 if lower.contains("signature") => QueryError::InvalidSignature,
+            400 => QueryError::BadRequest(message),
+            _ => QueryError::Proxy { status, message },
+        }
+    }
+
+    /// The closest [`std::io::ErrorKind`]. Rate limiting maps to `Other`, as
+    /// `ErrorKind::QuotaExceeded` needs Rust 1.85.
+    fn io_error_kind(&self) -> std::io::ErrorKind {
+        match self {
+            QueryError::Io(err) => err.kind(),
+            QueryError::Unauthorized | QueryError::InvalidSignature | QueryError::NotAllowed(_) => {
+                std::io::ErrorKind::PermissionDenied
+            }
+            QueryError::Timeout => std::io::ErrorKind::TimedOut,
+            QueryError::BadRequest(_) => std::io::ErrorKind::InvalidInput,
+            QueryError::RateLimited | QueryError::NoQuorum(_) | QueryError::Proxy { .. } => {
+                std::io::ErrorKind::Other
+            }
+        }
+    }
+
+    /// Whether the same request may succeed if submitted again later. Rejections of the
+    /// request itself never will.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            QueryError::RateLimited
+            | QueryError::NoQuorum(_)
+            | QueryError::Timeout
+            | QueryError::Io(_) => true,
+            QueryError::Proxy { status, .. } => *status >= 500,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryError::RateLimited => write!(f, "rate limited"),
+            QueryError::Unauthorized => write!(f, "missing or invalid api key"),
+            QueryError::InvalidSignature => write!(f, "invalid request signature"),
+            QueryError::NotAllowed(message) => write!(f, "call not allowed: {}", message),
+            QueryError::NoQuorum(message) => write!(f, "no quorum: {}", message),
+            QueryError::Timeout => write!(f, "timed out waiting for guardians"),
+            QueryError::BadRequest(message) => write!(f, "bad request: {}", message),
+            QueryError::Proxy { status, message } => {
+                write!(f, "proxy error {}: {}", status, message)
+            }
+            QueryError::Io(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            QueryError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for QueryError {
+    fn from(err: std::io::Error) -> QueryError {
+        QueryError::Io(err)
+    }
+}
+
+impl From<QueryError> for std::io::Error {
+    fn from(err: QueryError) -> std::io::Error {
+        match err {
+            QueryError::Io(err) => err,
+            err => std::io::Error::new(err.io_error_kind(), err),
+        }
+    }
+}
+
+/// Re-issues a template request each time a trigger fires, as a stream of verified
+/// responses.
+///
+/// Query proxies do not offer subscriptions, so every round is a fresh submission of the
+/// template with the next nonce. [`RecurringQuery::every`] fires at a fixed interval and
+/// [`RecurringQuery::per_block`] once per item of a stream of new blocks, such as the
+/// `NewHeads` subscription of the `websocket` feature. Rounds run one at a time on tokio's blocking pool; a
+/// trigger which fires during a round is seen once it completes. Failed rounds are
+/// yielded as errors and polling continues until the trigger ends.
+#[cfg(all(feature = "crypto", feature = "tokio"))]
+pub struct RecurringQuery<T> {
+    client: Arc<QueryProxyClient>,
+    template: QueryRequest,
+    guardian_set: Arc<GuardianSet>,
+    trigger: T,
+    round: Option<JoinHandle<std::result::Result<VerifiedResponse, std::io::Error>>>,
+}
+
+#[cfg(all(feature = "crypto", feature = "tokio"))]
+impl RecurringQuery<IntervalTrigger> {
+    /// The first round starts immediately. Must be called within a tokio runtime.
+    pub fn every(
+        client: QueryProxyClient,
+        template: QueryRequest,
+        guardian_set: GuardianSet,
+        interval: Duration,
+    ) -> RecurringQuery<IntervalTrigger> {
+        let mut interval = tokio::time::interval(interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        RecurringQuery::per_block(client, template, guardian_set, IntervalTrigger(interval))
+    }
+}
+
+#[cfg(all(feature = "crypto", feature = "tokio"))]
+impl<T: Stream + Unpin> RecurringQuery<T> {
+    /// Starts a round for every item of `blocks`. Any stream works as a trigger; its items
+    /// are ignored.
+    pub fn per_block(
+        client: QueryProxyClient,
+        template: QueryRequest,
+        guardian_set: GuardianSet,
+        blocks: T,
+    ) -> RecurringQuery<T> {
+        RecurringQuery {
+            client: Arc::new(client),
+            template,
+            guardian_set: Arc::new(guardian_set),
+            trigger: blocks,
+            round: None,
+        }
+    }
+
+    /// Starts a round with the current template, then moves on to the next nonce.
+    fn start_round(&mut self) {
+        let client = Arc::clone(&self.client);
+        let guardian_set = Arc::clone(&self.guardian_set);
+        let template = self.template.clone();
+        self.template.nonce = self.template.nonce.wrapping_add(1);
+        self.round = Some(tokio::task::spawn_blocking(move || {
+            query_verified(&client, &template, &guardian_set)
+        }));
+    }
+}
+
+#[cfg(all(feature = "crypto", feature = "tokio"))]
+impl<T: Stream + Unpin> Stream for RecurringQuery<T> {
+    type Item = std::result::Result<VerifiedResponse, std::io::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(round) = &mut this.round {
+                let result = ready!(Pin::new(round).poll(cx));
+                this.round = None;
+                return Poll::Ready(Some(result.unwrap_or_else(|err| Err(err.into()))));
+            }
+            if ready!(Pin::new(&mut this.trigger).poll_next(cx)).is_none() {
+                return Poll::Ready(None);
+            }
+            this.start_round();
+        }
+    }
+}
+
+/// Fires at a fixed interval, for [`RecurringQuery::every`]. Ticks missed during a slow
+/// round are not made up.
+#[cfg(all(feature = "crypto", feature = "tokio"))]
+#[derive(Debug)]
+pub struct IntervalTrigger(tokio::time::Interval);
+
+#[cfg(all(feature = "crypto", feature = "tokio"))]
+impl Stream for IntervalTrigger {
+    type Item = tokio::time::Instant;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().0.poll_tick(cx).map(Some)
+    }
+}
+
+/// Block numbers from an EVM node's `newHeads` subscription over a WebSocket, as a trigger
+/// for [`RecurringQuery::per_block`].
+///
+/// Query proxies only accept queries over HTTP, so the WebSocket is kept open to a node of
+/// the queried chain and every head it announces starts an HTTP round. The stream ends
+/// when the node closes the connection or it fails; subscribe again to resume.
+#[cfg(feature = "websocket")]
+#[derive(Debug)]
+pub struct NewHeads {
+    socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    subscription: String,
+}
+
+#[cfg(feature = "websocket")]
+impl NewHeads {
+    /// Connects to a `ws://` or `wss://` node url and subscribes to new heads.
+    pub async fn subscribe(url: &str) -> std::result::Result<NewHeads, std::io::Error> {
+        let (mut socket, _) = tokio_tungstenite::connect_async(url)
+            .await
+            .map_err(std::io::Error::other)?;
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_subscribe",
+            "params": ["newHeads"],
+        });
+        socket
+            .send(Message::text(request.to_string()))
+            .await
+            .map_err(std::io::Error::other)?;
+        loop {
+            let message = match socket.next().await {
+                Some(message) => message.map_err(std::io::Error::other)?,
+                None => return Err(std::io::ErrorKind::UnexpectedEof.into()),
+            };
+            let Message::Text(text) = message else {
+                continue;
+            };
+            let reply: Value = serde_json::from_str(&text).map_err(|_| subscription_rejected())?;
+            if reply["id"] != 1 {
+                continue;
+            }
+            let subscription = reply["result"].as_str().ok_or_else(subscription_rejected)?;
+            return Ok(NewHeads {
+                subscription: subscription.to_string(),
+                socket,
+            });
+        }
+    }
+
+    /// The block number of a notification for this subscription.
+    fn head_number(&self, text: &str) -> Option<u64> {
+        let notification: Value = serde_json::from_str(text).ok()?;
+        let params = &notification["params"];
+        if notification["method"] != "eth_subscription"
+            || params["subscription"] != *self.subscription
+        {
+            return None;
+        }
+        let number = params["result"]["number"].as_str()?.strip_prefix("0x")?;
+        u64::from_str_radix(number, 16).ok()
+    }
+}
+
+#[cfg(feature = "websocket")]
+impl Stream for NewHeads {
+    type Item = u64;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            let message = match ready!(this.socket.poll_next_unpin(cx)) {
+                Some(Ok(message)) => message,
+                Some(Err(_)) | None => return Poll::Ready(None),
+            };
+            if let Message::Text(text) = message {
+                if let Some(number) = this.head_number(&text) {
+                    return Poll::Ready(Some(number));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "websocket")]
+fn subscription_rejected() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, "SubscriptionRejected")
+}
+
+#[cfg(all(feature = "crypto", feature = "tokio"))]
+fn query_verified(
+    client: &QueryProxyClient,
+    request: &QueryRequest,
+    guardian_set: &GuardianSet,
+) -> std::result::Result<VerifiedResponse, std::io::Error> {
+    let signed = client.query(request)?;
+    let verified = VerifiedResponse::verify(signed.bytes, signed.signatures, guardian_set)?;
+    if verified.response().request != *request {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "RequestMismatch",
+        ));
+    }
+    Ok(verified)
+}
+
+fn invalid_proxy_response() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, "InvalidProxyResponse")
+}
+
+fn parse_proxy_reply(reply: &Value) -> std::result::Result<SignedQueryResponse, std::io::Error> {
+    let invalid = invalid_proxy_response;
+    let bytes = reply["bytes"].as_str().ok_or_else(invalid)?;
+    let bytes = decode_hex(bytes).map_err(|_| invalid())?;
+    let signatures = reply["signatures"]
+        .as_array()
+        .ok_or_else(invalid)?
+        .iter()
+        .map(|signature| {
+            signature
+                .as_str()
+                .and_then(|signature| decode_hex(signature).ok())
+                .and_then(|signature| signature.try_into().ok())
+                .ok_or_else(invalid)
+        })
+        .collect::<std::result::Result<Vec<[u8; 66]>, std::io::Error>>()?;
+    Ok(SignedQueryResponse { bytes, signatures })
+}
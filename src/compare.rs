@@ -0,0 +1,150 @@
+//! Consistency checks between responses to the same request from different sources.
+//!
+//! Only the request and the per chain responses are compared; the request id differs
+//! between sources and signatures are not part of the response.
+
+use std::fmt;
+
+#[cfg(feature = "experimental")]
+use crate::structs::NearAccountResult;
+use crate::structs::{ChainSpecificResponse, QueryResponse, SolanaAccountResult};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Divergence {
+    /// The responses answer different requests.
+    Request,
+    ResponseCount,
+    ChainId {
+        response_index: usize,
+    },
+    QueryType {
+        response_index: usize,
+    },
+    /// The block number, hash or time differs.
+    Block {
+        response_index: usize,
+    },
+    ResultCount {
+        response_index: usize,
+    },
+    Result {
+        response_index: usize,
+        result_index: usize,
+    },
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Divergence::Request => write!(f, "requests differ"),
+            Divergence::ResponseCount => write!(f, "number of per chain responses differs"),
+            Divergence::ChainId { response_index } => {
+                write!(f, "response {}: chain id differs", response_index)
+            }
+            Divergence::QueryType { response_index } => {
+                write!(f, "response {}: query type differs", response_index)
+            }
+            Divergence::Block { response_index } => {
+                write!(f, "response {}: block differs", response_index)
+            }
+            Divergence::ResultCount { response_index } => {
+                write!(f, "response {}: number of results differs", response_index)
+            }
+            Divergence::Result {
+                response_index,
+                result_index,
+            } => write!(
+                f,
+                "response {}: result {} differs",
+                response_index, result_index
+            ),
+        }
+    }
+}
+
+/// Returns the first point at which `left` and `right` diverge.
+pub fn compare_responses(
+    left: &QueryResponse,
+    right: &QueryResponse,
+) -> std::result::Result<(), Divergence> {
+    if left.request != right.request {
+        return Err(Divergence::Request);
+    }
+    if left.responses.len() != right.responses.len() {
+        return Err(Divergence::ResponseCount);
+    }
+    for (response_index, (l, r)) in left.responses.iter().zip(&right.responses).enumerate() {
+        if l.chain_id != r.chain_id {
+            return Err(Divergence::ChainId { response_index });
+        }
+        if l.response.query_type() != r.response.query_type() {
+            return Err(Divergence::QueryType { response_index });
+        }
+        let same_block = match (&l.response, &r.response) {
+            (
+                ChainSpecificResponse::EthCallByTimestampQueryResponse(l),
+                ChainSpecificResponse::EthCallByTimestampQueryResponse(r),
+            ) => {
+                l.target_block_info() == r.target_block_info()
+                    && l.following_block_info() == r.following_block_info()
+            }
+            _ => l.response.block_info() == r.response.block_info(),
+        };
+        if !same_block {
+            return Err(Divergence::Block { response_index });
+        }
+        let (l, r) = (results(&l.response), results(&r.response));
+        if l.len() != r.len() {
+            return Err(Divergence::ResultCount { response_index });
+        }
+        if let Some(result_index) = l.iter().zip(&r).position(|(l, r)| l != r) {
+            return Err(Divergence::Result {
+                response_index,
+                result_index,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Compares every response against the first, returning the index of the first one which
+/// diverges along with how.
+pub fn compare_all(responses: &[QueryResponse]) -> std::result::Result<(), (usize, Divergence)> {
+    let Some((first, rest)) = responses.split_first() else {
+        return Ok(());
+    };
+    for (i, response) in rest.iter().enumerate() {
+        compare_responses(first, response).map_err(|divergence| (i + 1, divergence))?;
+    }
+    Ok(())
+}
+
+#[derive(PartialEq, Eq)]
+enum ResultRef<'a> {
+    Bytes(&'a [u8]),
+    Account(&'a SolanaAccountResult),
+    #[cfg(feature = "experimental")]
+    NearAccount(&'a NearAccountResult),
+}
+
+fn results(response: &ChainSpecificResponse) -> Vec<ResultRef<'_>> {
+    fn bytes(results: &[Vec<u8>]) -> Vec<ResultRef<'_>> {
+        results.iter().map(|r| ResultRef::Bytes(r)).collect()
+    }
+    match response {
+        ChainSpecificResponse::EthCallQueryResponse(r) => bytes(&r.results),
+        ChainSpecificResponse::EthCallByTimestampQueryResponse(r) => bytes(&r.results),
+        ChainSpecificResponse::EthCallWithFinalityQueryResponse(r) => bytes(&r.results),
+        #[cfg(feature = "experimental")]
+        ChainSpecificResponse::EthCallWithOverridesQueryResponse(r) => bytes(&r.results),
+        #[cfg(feature = "experimental")]
+        ChainSpecificResponse::NearViewCallQueryResponse(r) => bytes(&r.results),
+        #[cfg(feature = "experimental")]
+        ChainSpecificResponse::NearViewAccountQueryResponse(r) => {
+            r.results.iter().map(ResultRef::NearAccount).collect()
+        }
+        ChainSpecificResponse::SolanaAccountQueryResponse(r) => {
+            r.results.iter().map(ResultRef::Account).collect()
+        }
+    }
+}
@@ -0,0 +1,47 @@
+//! The well-known devnet (Tilt) guardian, for tests only.
+//!
+//! This key is public. Anything it signs is worthless outside of a local devnet, and it
+//! must never be part of a guardian set used to verify real responses.
+
+use crate::signature::{self, GuardianSet};
+use crate::structs::QueryResponse;
+
+/// Private key of the single devnet guardian.
+pub const DEVNET_GUARDIAN_PRIVATE_KEY: [u8; 32] = [
+    0xcf, 0xb1, 0x23, 0x03, 0xa1, 0x9c, 0xde, 0x58, 0x0b, 0xb4, 0xdd, 0x77, 0x16, 0x39, 0xb0, 0xd2,
+    0x6b, 0xc6, 0x83, 0x53, 0x64, 0x55, 0x71, 0xa8, 0xcf, 0xf5, 0x16, 0xab, 0x2e, 0xe1, 0x13, 0xa0,
+];
+
+/// Address of the single devnet guardian, `0xbeFA429d57cD18b7F8A4d91A2da9AB4AF05d0FBe`.
+pub const DEVNET_GUARDIAN_ADDRESS: [u8; 20] = [
+    0xbe, 0xfa, 0x42, 0x9d, 0x57, 0xcd, 0x18, 0xb7, 0xf8, 0xa4, 0xd9, 0x1a, 0x2d, 0xa9, 0xab, 0x4a,
+    0xf0, 0x5d, 0x0f, 0xbe,
+];
+
+/// The devnet guardian set: index 0, containing only the devnet guardian.
+pub fn devnet_guardian_set() -> GuardianSet {
+    GuardianSet {
+        index: 0,
+        keys: vec![DEVNET_GUARDIAN_ADDRESS],
+    }
+}
+
+/// Signs `response` with the devnet guardian, producing a signature set which reaches
+/// quorum for [`devnet_guardian_set`].
+pub fn sign_response(response: &[u8]) -> std::result::Result<Vec<[u8; 66]>, std::io::Error> {
+    Ok(vec![signature::sign_response(
+        response,
+        &DEVNET_GUARDIAN_PRIVATE_KEY,
+        0,
+    )?])
+}
+
+/// Serializes `response` and signs it with the devnet guardian, returning the bytes and
+/// signatures as a query proxy would. Pairs with [`QueryResponse::from_parts`].
+pub fn sign_query_response(
+    response: &QueryResponse,
+) -> std::result::Result<(Vec<u8>, Vec<[u8; 66]>), std::io::Error> {
+    let bytes = response.serialize()?;
+    let signatures = sign_response(&bytes)?;
+    Ok((bytes, signatures))
+}
@@ -0,0 +1,237 @@
+//! Byte level comparison of serialized requests, for tracking down digest mismatches.
+//!
+//! A locally built request that does not match the one the guardians signed almost
+//! always differs in a single field. [`diff_requests`] names that field.
+
+use std::fmt;
+use std::ops::Range;
+
+/// The first point at which two serialized requests differ.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Difference {
+    pub offset: usize,
+    /// The field containing `offset`, e.g. `requests[0].call_data[1].data`.
+    pub field: String,
+    /// The byte at `offset` on each side, `None` past the end of the data.
+    pub left: Option<u8>,
+    pub right: Option<u8>,
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let byte = |b: Option<u8>| b.map_or("end of data".to_string(), |b| format!("0x{:02x}", b));
+        write!(
+            f,
+            "first difference at byte {} ({}): {} != {}",
+            self.offset,
+            self.field,
+            byte(self.left),
+            byte(self.right)
+        )
+    }
+}
+
+/// Compares two serialized requests, returning `None` if they are identical.
+///
+/// Neither side has to parse; fields are named as far as the layout can be followed.
+pub fn diff_requests(left: &[u8], right: &[u8]) -> Option<Difference> {
+    let offset = match left.iter().zip(right).position(|(l, r)| l != r) {
+        Some(offset) => offset,
+        None if left.len() == right.len() => return None,
+        None => left.len().min(right.len()),
+    };
+    let longer = if offset < left.len() { left } else { right };
+    let field = request_layout(longer)
+        .into_iter()
+        .find(|(range, _)| range.contains(&offset))
+        .map_or_else(|| "trailing bytes".to_string(), |(_, name)| name);
+    Some(Difference {
+        offset,
+        field,
+        left: left.get(offset).copied(),
+        right: right.get(offset).copied(),
+    })
+}
+
+/// Compares a serialized request against the request embedded in a serialized response.
+/// Offsets are relative to the start of the request.
+pub fn diff_request_with_response(
+    request: &[u8],
+    response: &[u8],
+) -> std::result::Result<Option<Difference>, std::io::Error> {
+    Ok(diff_requests(request, embedded_request(response)?))
+}
+
+/// The request bytes inside a serialized response, as delimited by its length prefix.
+fn embedded_request(response: &[u8]) -> std::result::Result<&[u8], std::io::Error> {
+    let truncated = || std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "TruncatedResponse");
+    let request_chain_id = u16::from_be_bytes(
+        response
+            .get(1..3)
+            .ok_or_else(truncated)?
+            .try_into()
+            .unwrap(),
+    );
+    let start = 3 + if request_chain_id == 0 { 65 } else { 32 };
+    let len = u32::from_be_bytes(
+        response
+            .get(start..start + 4)
+            .ok_or_else(truncated)?
+            .try_into()
+            .unwrap(),
+    ) as usize;
+    response
+        .get(start + 4..start + 4 + len)
+        .ok_or_else(truncated)
+}
+
+/// Walks a serialized request, naming the byte range of every field. Stops at the first
+/// field which runs past the end of `data`.
+fn request_layout(data: &[u8]) -> Vec<(Range<usize>, String)> {
+    let mut layout = Layout {
+        data,
+        pos: 0,
+        fields: Vec::new(),
+    };
+    let _ = layout.request();
+    layout.fields
+}
+
+struct Layout<'a> {
+    data: &'a [u8],
+    pos: usize,
+    fields: Vec<(Range<usize>, String)>,
+}
+
+impl Layout<'_> {
+    /// Records a field of `len` bytes, returning its value as a big endian integer when it
+    /// is at most eight bytes long.
+    fn field(&mut self, len: usize, name: impl Into<String>) -> Option<u64> {
+        let end = self.pos.checked_add(len)?;
+        let name = name.into();
+        if end > self.data.len() {
+            self.fields.push((self.pos..usize::MAX, name));
+            return None;
+        }
+        let value = self.data[self.pos..end]
+            .iter()
+            .take(8)
+            .fold(0u64, |acc, b| acc << 8 | u64::from(*b));
+        self.fields.push((self.pos..end, name));
+        self.pos = end;
+        Some(value)
+    }
+
+    fn string(&mut self, name: &str) -> Option<()> {
+        let len = self.field(4, format!("{}.len", name))?;
+        self.field(len as usize, name)?;
+        Some(())
+    }
+
+    fn request(&mut self) -> Option<()> {
+        self.field(1, "version")?;
+        self.field(4, "nonce")?;
+        let count = self.field(1, "num_per_chain_queries")?;
+        for i in 0..count {
+            let prefix = format!("requests[{}]", i);
+            self.field(2, format!("{}.chain_id", prefix))?;
+            let query_type = self.field(1, format!("{}.query_type", prefix))?;
+            let len = self.field(4, format!("{}.query_len", prefix))? as usize;
+            let end = self.pos + len;
+            self.query(&prefix, query_type);
+            // Anything the query did not account for is named after the whole query.
+            if self.pos < end {
+                self.fields
+                    .push((self.pos..end, format!("{}.query", prefix)));
+            }
+            self.pos = end;
+        }
+        Some(())
+    }
+
+    fn query(&mut self, prefix: &str, query_type: u64) -> Option<()> {
+        match query_type {
+            1 => {
+                self.string(&format!("{}.block_tag", prefix))?;
+                self.call_data(prefix)
+            }
+            2 => {
+                self.field(8, format!("{}.target_timestamp", prefix))?;
+                self.string(&format!("{}.target_block_hint", prefix))?;
+                self.string(&format!("{}.following_block_hint", prefix))?;
+                self.call_data(prefix)
+            }
+            3 => {
+                self.string(&format!("{}.block_tag", prefix))?;
+                self.string(&format!("{}.finality", prefix))?;
+                self.call_data(prefix)
+            }
+            4 => {
+                self.string(&format!("{}.commitment", prefix))?;
+                self.field(8, format!("{}.min_context_slot", prefix))?;
+                self.field(8, format!("{}.data_slice_offset", prefix))?;
+                self.field(8, format!("{}.data_slice_length", prefix))?;
+                let count = self.field(1, format!("{}.num_accounts", prefix))?;
+                for i in 0..count {
+                    self.field(32, format!("{}.accounts[{}]", prefix, i))?;
+                }
+                Some(())
+            }
+            6 => {
+                self.string(&format!("{}.block_tag", prefix))?;
+                let count = self.field(1, format!("{}.num_overrides", prefix))?;
+                for i in 0..count {
+                    self.state_override(&format!("{}.overrides[{}]", prefix, i))?;
+                }
+                self.call_data(prefix)
+            }
+            7 => {
+                self.string(&format!("{}.block_id", prefix))?;
+                let count = self.field(1, format!("{}.num_calls", prefix))?;
+                for i in 0..count {
+                    self.string(&format!("{}.calls[{}].account_id", prefix, i))?;
+                    self.string(&format!("{}.calls[{}].method_name", prefix, i))?;
+                    self.string(&format!("{}.calls[{}].args", prefix, i))?;
+                }
+                Some(())
+            }
+            8 => {
+                self.string(&format!("{}.block_id", prefix))?;
+                let count = self.field(1, format!("{}.num_account_ids", prefix))?;
+                for i in 0..count {
+                    self.string(&format!("{}.account_ids[{}]", prefix, i))?;
+                }
+                Some(())
+            }
+            _ => None,
+        }
+    }
+
+    fn state_override(&mut self, prefix: &str) -> Option<()> {
+        self.field(20, format!("{}.address", prefix))?;
+        if self.field(1, format!("{}.has_balance", prefix))? != 0 {
+            self.field(32, format!("{}.balance", prefix))?;
+        }
+        if self.field(1, format!("{}.has_nonce", prefix))? != 0 {
+            self.field(8, format!("{}.nonce", prefix))?;
+        }
+        if self.field(1, format!("{}.has_code", prefix))? != 0 {
+            self.string(&format!("{}.code", prefix))?;
+        }
+        let count = self.field(1, format!("{}.num_state_diff", prefix))?;
+        for i in 0..count {
+            self.field(32, format!("{}.state_diff[{}].slot", prefix, i))?;
+            self.field(32, format!("{}.state_diff[{}].value", prefix, i))?;
+        }
+        Some(())
+    }
+
+    fn call_data(&mut self, prefix: &str) -> Option<()> {
+        let count = self.field(1, format!("{}.num_call_data", prefix))?;
+        for i in 0..count {
+            self.field(20, format!("{}.call_data[{}].to", prefix, i))?;
+            self.string(&format!("{}.call_data[{}].data", prefix, i))?;
+        }
+        Some(())
+    }
+}
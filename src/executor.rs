@@ -0,0 +1,659 @@
+//! Dev-mode executor which answers a [`QueryRequest`] directly against RPC nodes.
+//!
+//! This bypasses the guardian network entirely: nothing is signed and nothing is
+//! attested. It exists so consumer logic can be iterated on locally without a
+//! guardian or proxy. Never treat its output as verified data.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use serde_json::{json, Value};
+
+use crate::chain::ChainId;
+#[cfg(feature = "experimental")]
+use crate::structs::{
+    BlockInfo, EthCallWithOverridesQueryRequest, EthCallWithOverridesQueryResponse,
+    NearAccountResult, NearViewAccountQueryRequest, NearViewAccountQueryResponse,
+    NearViewCallQueryRequest, NearViewCallQueryResponse, StateOverride,
+};
+use crate::structs::{
+    ChainOutcome, ChainSpecificQuery, ChainSpecificResponse, EthCallByTimestampQueryRequest,
+    EthCallByTimestampQueryResponse, EthCallData, EthCallQueryRequest, EthCallQueryResponse,
+    EthCallWithFinalityQueryRequest, EthCallWithFinalityQueryResponse, PerChainOutcome,
+    PerChainQueryResponse, QueryRequest, QueryResponse, SolanaAccountQueryRequest,
+    SolanaAccountQueryResponse, SolanaAccountResult,
+};
+
+/// A [`QueryResponse`] produced by [`execute_unverified`].
+///
+/// It carries no guardian signatures and an all-zero request id, so it must only be
+/// used for local development.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnverifiedQueryResponse {
+    pub response: QueryResponse,
+}
+
+impl UnverifiedQueryResponse {
+    pub fn into_inner(self) -> QueryResponse {
+        self.response
+    }
+}
+
+/// Performs every per chain query in `request` against the RPC url registered for its
+/// chain in `rpc_map` and assembles the results into an unsigned response.
+pub fn execute_unverified(
+    request: &QueryRequest,
+    rpc_map: &HashMap<ChainId, String>,
+) -> std::result::Result<UnverifiedQueryResponse, std::io::Error> {
+    QueryProxyMock::new(rpc_map.clone())
+        .query(request)
+        .into_response()
+}
+
+/// Stand-in for a query proxy in tests, answering like [`execute_unverified`] while recording
+/// how long each chain took. Chosen chains can be slowed down or made to fail.
+#[derive(Clone, Default)]
+pub struct QueryProxyMock {
+    rpc_map: HashMap<ChainId, String>,
+    latency: HashMap<ChainId, Duration>,
+    failures: HashMap<ChainId, usize>,
+}
+
+/// RPC urls often carry provider API keys, so only their scheme and host are printed.
+impl std::fmt::Debug for QueryProxyMock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rpc_hosts: HashMap<ChainId, &str> = self
+            .rpc_map
+            .iter()
+            .map(|(chain_id, url)| (*chain_id, redact_url(url)))
+            .collect();
+        f.debug_struct("QueryProxyMock")
+            .field("rpc_map", &rpc_hosts)
+            .field("latency", &self.latency)
+            .field("failures", &self.failures)
+            .finish()
+    }
+}
+
+/// Time spent on one per chain request, including any injected latency.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainTiming {
+    pub chain_id: ChainId,
+    pub elapsed: Duration,
+}
+
+/// What each chain of a [`QueryProxyMock::query`] returned, and how long it took.
+#[derive(Debug)]
+pub struct MockQueryResult {
+    pub request: QueryRequest,
+    /// One entry per per chain request, in request order.
+    pub results: Vec<std::result::Result<PerChainQueryResponse, std::io::Error>>,
+    /// One entry per per chain request, in request order, failed chains included.
+    pub timings: Vec<ChainTiming>,
+}
+
+impl MockQueryResult {
+    /// Each per chain request with its response or failure, as [`QueryResponse::outcomes`]
+    /// reports them.
+    pub fn outcomes(&self) -> Vec<ChainOutcome<'_>> {
+        self.request
+            .requests
+            .iter()
+            .zip(&self.results)
+            .map(|(request, result)| ChainOutcome {
+                request,
+                outcome: match result {
+                    Ok(response) => PerChainOutcome::Response(response),
+                    Err(err) => {
+                        PerChainOutcome::Error(std::io::Error::new(err.kind(), err.to_string()))
+                    }
+                },
+            })
+            .collect()
+    }
+
+    /// Assembles the unsigned response, failing with the first chain's error if any chain
+    /// failed.
+    pub fn into_response(self) -> std::result::Result<UnverifiedQueryResponse, std::io::Error> {
+        let responses = self
+            .results
+            .into_iter()
+            .collect::<std::result::Result<Vec<_>, std::io::Error>>()?;
+        Ok(UnverifiedQueryResponse {
+            response: QueryResponse {
+                version: QueryResponse::RESPONSE_VERSION,
+                request_chain_id: 0,
+                request_id: vec![0u8; 65],
+                request: self.request,
+                responses,
+            },
+        })
+    }
+}
+
+impl QueryProxyMock {
+    pub fn new(rpc_map: HashMap<ChainId, String>) -> QueryProxyMock {
+        QueryProxyMock {
+            rpc_map,
+            ..Default::default()
+        }
+    }
+
+    /// Sleeps for `latency` before every request to `chain_id`.
+    pub fn with_latency(mut self, chain_id: ChainId, latency: Duration) -> QueryProxyMock {
+        self.latency.insert(chain_id, latency);
+        self
+    }
+
+    /// Fails the next `times` queries touching `chain_id` with "InjectedFailure", after
+    /// any injected latency.
+    pub fn fail_next(mut self, chain_id: ChainId, times: usize) -> QueryProxyMock {
+        self.failures.insert(chain_id, times);
+        self
+    }
+
+    /// Executes every per chain request of `request` like [`execute_unverified`], carrying
+    /// on past failing chains so each one gets an outcome and a timing.
+    pub fn query(&mut self, request: &QueryRequest) -> MockQueryResult {
+        let mut results = Vec::with_capacity(request.requests.len());
+        let mut timings = Vec::with_capacity(request.requests.len());
+        for per_chain_request in &request.requests {
+            let chain_id = per_chain_request.chain();
+            let start = Instant::now();
+            results.push(self.query_chain(chain_id, &per_chain_request.query));
+            timings.push(ChainTiming {
+                chain_id,
+                elapsed: start.elapsed(),
+            });
+        }
+        MockQueryResult {
+            request: request.clone(),
+            results,
+            timings,
+        }
+    }
+
+    fn query_chain(
+        &mut self,
+        chain_id: ChainId,
+        query: &ChainSpecificQuery,
+    ) -> std::result::Result<PerChainQueryResponse, std::io::Error> {
+        if let Some(latency) = self.latency.get(&chain_id) {
+            std::thread::sleep(*latency);
+        }
+        if let Some(remaining) = self.failures.get_mut(&chain_id).filter(|n| **n > 0) {
+            *remaining -= 1;
+            return Err(std::io::Error::other("InjectedFailure"));
+        }
+        let rpc_url = self
+            .rpc_map
+            .get(&chain_id)
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "MissingRpcUrl"))?;
+        Ok(PerChainQueryResponse {
+            chain_id: chain_id.into(),
+            response: execute_query(query, rpc_url)?,
+        })
+    }
+}
+
+fn execute_query(
+    query: &ChainSpecificQuery,
+    rpc_url: &str,
+) -> std::result::Result<ChainSpecificResponse, std::io::Error> {
+    Ok(match query {
+        ChainSpecificQuery::EthCallQueryRequest(query) => {
+            ChainSpecificResponse::EthCallQueryResponse(execute_eth_call(query, rpc_url)?)
+        }
+        ChainSpecificQuery::EthCallByTimestampQueryRequest(query) => {
+            ChainSpecificResponse::EthCallByTimestampQueryResponse(execute_eth_call_by_timestamp(
+                query, rpc_url,
+            )?)
+        }
+        ChainSpecificQuery::EthCallWithFinalityQueryRequest(query) => {
+            ChainSpecificResponse::EthCallWithFinalityQueryResponse(execute_eth_call_with_finality(
+                query, rpc_url,
+            )?)
+        }
+        ChainSpecificQuery::SolanaAccountQueryRequest(query) => {
+            ChainSpecificResponse::SolanaAccountQueryResponse(execute_solana_account(
+                query, rpc_url,
+            )?)
+        }
+        #[cfg(feature = "experimental")]
+        ChainSpecificQuery::EthCallWithOverridesQueryRequest(query) => {
+            ChainSpecificResponse::EthCallWithOverridesQueryResponse(
+                execute_eth_call_with_overrides(query, rpc_url)?,
+            )
+        }
+        #[cfg(feature = "experimental")]
+        ChainSpecificQuery::NearViewCallQueryRequest(query) => {
+            ChainSpecificResponse::NearViewCallQueryResponse(execute_near_view_call(
+                query, rpc_url,
+            )?)
+        }
+        #[cfg(feature = "experimental")]
+        ChainSpecificQuery::NearViewAccountQueryRequest(query) => {
+            ChainSpecificResponse::NearViewAccountQueryResponse(execute_near_view_account(
+                query, rpc_url,
+            )?)
+        }
+    })
+}
+
+fn execute_eth_call(
+    query: &EthCallQueryRequest,
+    rpc_url: &str,
+) -> std::result::Result<EthCallQueryResponse, std::io::Error> {
+    let block = get_evm_block(rpc_url, &query.block_tag)?;
+    let results = eth_calls(rpc_url, &query.call_data, block.number, None)?;
+    Ok(EthCallQueryResponse {
+        block_number: block.number,
+        block_hash: block.hash,
+        block_time: block.time,
+        results,
+    })
+}
+
+fn execute_eth_call_by_timestamp(
+    query: &EthCallByTimestampQueryRequest,
+    rpc_url: &str,
+) -> std::result::Result<EthCallByTimestampQueryResponse, std::io::Error> {
+    let target = get_evm_block(rpc_url, &query.target_block_hint)?;
+    let following = get_evm_block(rpc_url, &query.following_block_hint)?;
+
+    // Mirror the guardian checks: the blocks must be adjacent and bracket the timestamp.
+    if target.number.checked_add(1) != Some(following.number)
+        || query.target_timestamp < target.time
+        || query.target_timestamp >= following.time
+    {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "InvalidBlockHints",
+        ));
+    }
+
+    let results = eth_calls(rpc_url, &query.call_data, target.number, None)?;
+    Ok(EthCallByTimestampQueryResponse {
+        target_block_number: target.number,
+        target_block_hash: target.hash,
+        target_block_time: target.time,
+        following_block_number: following.number,
+        following_block_hash: following.hash,
+        following_block_time: following.time,
+        results,
+    })
+}
+
+fn execute_eth_call_with_finality(
+    query: &EthCallWithFinalityQueryRequest,
+    rpc_url: &str,
+) -> std::result::Result<EthCallWithFinalityQueryResponse, std::io::Error> {
+    let block = get_evm_block(rpc_url, &query.block_tag)?;
+    let finalized = get_evm_block(rpc_url, &query.finality)?;
+    if block.number > finalized.number {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "BlockNotFinal",
+        ));
+    }
+
+    let results = eth_calls(rpc_url, &query.call_data, block.number, None)?;
+    Ok(EthCallWithFinalityQueryResponse {
+        block_number: block.number,
+        block_hash: block.hash,
+        block_time: block.time,
+        results,
+    })
+}
+
+#[cfg(feature = "experimental")]
+fn execute_eth_call_with_overrides(
+    query: &EthCallWithOverridesQueryRequest,
+    rpc_url: &str,
+) -> std::result::Result<EthCallWithOverridesQueryResponse, std::io::Error> {
+    let block = get_evm_block(rpc_url, &query.block_tag)?;
+    let overrides = state_overrides_json(&query.overrides);
+    let results = eth_calls(rpc_url, &query.call_data, block.number, Some(&overrides))?;
+    Ok(EthCallWithOverridesQueryResponse {
+        block_number: block.number,
+        block_hash: block.hash,
+        block_time: block.time,
+        results,
+    })
+}
+
+#[cfg(feature = "experimental")]
+fn execute_near_view_call(
+    query: &NearViewCallQueryRequest,
+    rpc_url: &str,
+) -> std::result::Result<NearViewCallQueryResponse, std::io::Error> {
+    let block = get_near_block(rpc_url, &query.block_id)?;
+
+    // Pin every call to the resolved height, so they all see the same state.
+    let mut results = Vec::with_capacity(query.calls.len());
+    for call in &query.calls {
+        let result = rpc_call(
+            rpc_url,
+            "query",
+            json!({
+                "request_type": "call_function",
+                "block_id": block.number,
+                "account_id": call.account_id,
+                "method_name": call.method_name,
+                "args_base64": base64::engine::general_purpose::STANDARD.encode(&call.args),
+            }),
+        )?;
+        let bytes = result["result"]
+            .as_array()
+            .ok_or_else(invalid_rpc_response)?
+            .iter()
+            .map(|byte| {
+                byte.as_u64()
+                    .and_then(|byte| u8::try_from(byte).ok())
+                    .ok_or_else(invalid_rpc_response)
+            })
+            .collect::<std::result::Result<Vec<u8>, std::io::Error>>()?;
+        results.push(bytes);
+    }
+
+    Ok(NearViewCallQueryResponse {
+        block_height: block.number,
+        block_hash: block.hash,
+        block_time: block.time,
+        results,
+    })
+}
+
+#[cfg(feature = "experimental")]
+fn execute_near_view_account(
+    query: &NearViewAccountQueryRequest,
+    rpc_url: &str,
+) -> std::result::Result<NearViewAccountQueryResponse, std::io::Error> {
+    let block = get_near_block(rpc_url, &query.block_id)?;
+
+    let mut results = Vec::with_capacity(query.account_ids.len());
+    for account_id in &query.account_ids {
+        let account = rpc_call(
+            rpc_url,
+            "query",
+            json!({
+                "request_type": "view_account",
+                "block_id": block.number,
+                "account_id": account_id,
+            }),
+        )?;
+        results.push(NearAccountResult {
+            // Balances are decimal strings, as they do not fit in a JSON number.
+            amount: decimal_u128(&account["amount"])?,
+            locked: decimal_u128(&account["locked"])?,
+            code_hash: decode_base58_32(&account["code_hash"])?,
+            storage_usage: as_u64(&account["storage_usage"])?,
+        });
+    }
+
+    Ok(NearViewAccountQueryResponse {
+        block_height: block.number,
+        block_hash: block.hash,
+        block_time: block.time,
+        results,
+    })
+}
+
+/// Resolves a NEAR block id, which is a height, a hash or a finality.
+#[cfg(feature = "experimental")]
+fn get_near_block(rpc_url: &str, block_id: &str) -> std::result::Result<BlockInfo, std::io::Error> {
+    let block_reference = if block_id == "final" || block_id == "optimistic" {
+        json!({ "finality": block_id })
+    } else if let Ok(height) = block_id.parse::<u64>() {
+        json!({ "block_id": height })
+    } else {
+        json!({ "block_id": block_id })
+    };
+    let block = rpc_call(rpc_url, "block", block_reference)?;
+    let header = &block["header"];
+    Ok(BlockInfo {
+        number: as_u64(&header["height"])?,
+        hash: decode_base58_32(&header["hash"])?,
+        // NEAR timestamps are in nanoseconds.
+        time: as_u64(&header["timestamp"])? / 1000,
+    })
+}
+
+fn execute_solana_account(
+    query: &SolanaAccountQueryRequest,
+    rpc_url: &str,
+) -> std::result::Result<SolanaAccountQueryResponse, std::io::Error> {
+    let accounts: Vec<String> = query
+        .accounts
+        .iter()
+        .map(|account| bs58::encode(account).into_string())
+        .collect();
+    let mut config = json!({ "commitment": query.commitment, "encoding": "base64" });
+    if query.min_context_slot != 0 {
+        config["minContextSlot"] = json!(query.min_context_slot);
+    }
+    if query.has_data_slice() {
+        config["dataSlice"] =
+            json!({ "offset": query.data_slice_offset, "length": query.data_slice_length });
+    }
+    let result = rpc_call(rpc_url, "getMultipleAccounts", json!([accounts, config]))?;
+
+    let slot_number = as_u64(&result["context"]["slot"])?;
+    let values = result["value"]
+        .as_array()
+        .ok_or_else(invalid_rpc_response)?;
+    let mut results = Vec::with_capacity(values.len());
+    for value in values {
+        if value.is_null() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "AccountNotFound",
+            ));
+        }
+        let data = value["data"][0].as_str().ok_or_else(invalid_rpc_response)?;
+        results.push(SolanaAccountResult {
+            lamports: as_u64(&value["lamports"])?,
+            rent_epoch: as_u64(&value["rentEpoch"])?,
+            executable: value["executable"]
+                .as_bool()
+                .ok_or_else(invalid_rpc_response)?,
+            owner: decode_base58_32(&value["owner"])?,
+            data: base64::engine::general_purpose::STANDARD
+                .decode(data)
+                .map_err(|_| invalid_rpc_response())?,
+        })
+    }
+
+    let block = rpc_call(
+        rpc_url,
+        "getBlock",
+        json!([slot_number, {
+            "commitment": query.commitment,
+            "transactionDetails": "none",
+            "rewards": false,
+            "maxSupportedTransactionVersion": 0,
+        }]),
+    )?;
+
+    Ok(SolanaAccountQueryResponse {
+        slot_number,
+        block_time: seconds_to_micros(as_u64(&block["blockTime"])?)?,
+        block_hash: decode_base58_32(&block["blockhash"])?,
+        results,
+    })
+}
+
+struct EvmBlock {
+    number: u64,
+    hash: [u8; 32],
+    /// Microseconds, matching the guardian encoding.
+    time: u64,
+}
+
+fn get_evm_block(rpc_url: &str, block_id: &str) -> std::result::Result<EvmBlock, std::io::Error> {
+    // A 32 byte hex string is a block hash, anything else is a number or a tag.
+    let block = if block_id.len() == 66 {
+        rpc_call(rpc_url, "eth_getBlockByHash", json!([block_id, false]))?
+    } else {
+        rpc_call(rpc_url, "eth_getBlockByNumber", json!([block_id, false]))?
+    };
+    if block.is_null() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "BlockNotFound",
+        ));
+    }
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&decode_hex_value(&block["hash"], Some(32))?);
+    Ok(EvmBlock {
+        number: hex_quantity(&block["number"])?,
+        hash,
+        time: seconds_to_micros(hex_quantity(&block["timestamp"])?)?,
+    })
+}
+
+fn eth_calls(
+    rpc_url: &str,
+    call_data: &[EthCallData],
+    block_number: u64,
+    overrides: Option<&Value>,
+) -> std::result::Result<Vec<Vec<u8>>, std::io::Error> {
+    let block = format!("0x{:x}", block_number);
+    let mut results = Vec::with_capacity(call_data.len());
+    for call in call_data {
+        let mut params = json!([
+            { "to": encode_hex(&call.to), "data": encode_hex(&call.data) },
+            block,
+        ]);
+        if let Some(overrides) = overrides {
+            params.as_array_mut().unwrap().push(overrides.clone());
+        }
+        let result = rpc_call(rpc_url, "eth_call", params)?;
+        results.push(decode_hex_value(&result, None)?);
+    }
+    Ok(results)
+}
+
+/// The state override set parameter of geth's `eth_call`.
+#[cfg(feature = "experimental")]
+fn state_overrides_json(overrides: &[StateOverride]) -> Value {
+    let mut set = serde_json::Map::new();
+    for entry in overrides {
+        let mut account = serde_json::Map::new();
+        if let Some(balance) = &entry.balance {
+            let balance = hex::encode(balance);
+            let balance = balance.trim_start_matches('0');
+            account.insert(
+                "balance".to_string(),
+                json!(format!(
+                    "0x{}",
+                    if balance.is_empty() { "0" } else { balance }
+                )),
+            );
+        }
+        if let Some(nonce) = entry.nonce {
+            account.insert("nonce".to_string(), json!(format!("0x{:x}", nonce)));
+        }
+        if let Some(code) = &entry.code {
+            account.insert("code".to_string(), json!(encode_hex(code)));
+        }
+        if !entry.state_diff.is_empty() {
+            let state_diff: serde_json::Map<String, Value> = entry
+                .state_diff
+                .iter()
+                .map(|slot| (encode_hex(&slot.slot), json!(encode_hex(&slot.value))))
+                .collect();
+            account.insert("stateDiff".to_string(), Value::Object(state_diff));
+        }
+        set.insert(encode_hex(&entry.address), Value::Object(account));
+    }
+    Value::Object(set)
+}
+
+fn rpc_call(
+    rpc_url: &str,
+    method: &str,
+    params: Value,
+) -> std::result::Result<Value, std::io::Error> {
+    let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+    let mut response = ureq::post(rpc_url)
+        .send_json(&body)
+        .map_err(std::io::Error::other)?;
+    let mut reply: Value = response
+        .body_mut()
+        .read_json()
+        .map_err(std::io::Error::other)?;
+    if let Some(error) = reply.get("error") {
+        return Err(std::io::Error::other(format!("RpcError: {}", error)));
+    }
+    Ok(reply["result"].take())
+}
+
+/// The url up to the end of its host, dropping any credentials, path and query.
+fn redact_url(url: &str) -> &str {
+    let host_start = url.find("://").map_or(0, |i| i + 3);
+    let host_end = url[host_start..]
+        .find(['/', '?', '#'])
+        .map_or(url.len(), |i| host_start + i);
+    match url[host_start..host_end].rfind('@') {
+        // Credentials before the host cannot be cut out of a borrowed prefix.
+        Some(_) => "<redacted>",
+        None => &url[..host_end],
+    }
+}
+
+fn invalid_rpc_response() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, "InvalidRpcResponse")
+}
+
+fn as_u64(value: &Value) -> std::result::Result<u64, std::io::Error> {
+    value.as_u64().ok_or_else(invalid_rpc_response)
+}
+
+#[cfg(feature = "experimental")]
+fn decimal_u128(value: &Value) -> std::result::Result<u128, std::io::Error> {
+    let s = value.as_str().ok_or_else(invalid_rpc_response)?;
+    s.parse().map_err(|_| invalid_rpc_response())
+}
+
+fn hex_quantity(value: &Value) -> std::result::Result<u64, std::io::Error> {
+    let s = value.as_str().ok_or_else(invalid_rpc_response)?;
+    u64::from_str_radix(s.trim_start_matches("0x"), 16).map_err(|_| invalid_rpc_response())
+}
+
+/// Converts an RPC block time in seconds to the microseconds used in responses.
+fn seconds_to_micros(seconds: u64) -> std::result::Result<u64, std::io::Error> {
+    seconds
+        .checked_mul(1_000_000)
+        .ok_or_else(invalid_rpc_response)
+}
+
+fn encode_hex(data: &[u8]) -> String {
+    format!("0x{}", hex::encode(data))
+}
+
+fn decode_hex_value(
+    value: &Value,
+    expected_len: Option<usize>,
+) -> std::result::Result<Vec<u8>, std::io::Error> {
+    let s = value.as_str().ok_or_else(invalid_rpc_response)?;
+    let bytes =
+        hex::decode(s.strip_prefix("0x").unwrap_or(s)).map_err(|_| invalid_rpc_response())?;
+    if expected_len.is_some_and(|len| len != bytes.len()) {
+        return Err(invalid_rpc_response());
+    }
+    Ok(bytes)
+}
+
+fn decode_base58_32(value: &Value) -> std::result::Result<[u8; 32], std::io::Error> {
+    let s = value.as_str().ok_or_else(invalid_rpc_response)?;
+    let mut out = [0u8; 32];
+    let len = bs58::decode(s)
+        .onto(&mut out)
+        .map_err(|_| invalid_rpc_response())?;
+    if len != 32 {
+        return Err(invalid_rpc_response());
+    }
+    Ok(out)
+}
@@ -0,0 +1,177 @@
+//! Flattening of responses into one row per call or account, for analytics pipelines.
+
+use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::chain::ChainId;
+use crate::structs::{ChainSpecificQuery, ChainSpecificResponse, EthCallData, QueryResponse};
+
+/// One call result or account of a response.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExportRecord {
+    pub nonce: u32,
+    /// Position of the per chain response within the response.
+    pub response_index: usize,
+    pub chain_id: ChainId,
+    pub query_type: u8,
+    pub block_number: u64,
+    /// Hex, without a `0x` prefix, as are the other byte fields.
+    pub block_hash: String,
+    /// Microseconds since the epoch.
+    pub block_time: u64,
+    /// Microseconds between `block_time` and when the response was received, if known.
+    pub block_age: Option<u64>,
+    /// Position of the call or account within the per chain request.
+    pub result_index: usize,
+    /// The contract called, or the Solana account read, in hex. For NEAR, the contract
+    /// account id.
+    pub target: String,
+    /// The call return data, or the Solana account data.
+    pub result: String,
+}
+
+impl ExportRecord {
+    pub const CSV_HEADER: &'static str = "nonce,response_index,chain_id,query_type,block_number,block_hash,block_time,block_age,result_index,target,result";
+
+    /// Every field but `target` is a number or hex. `target` is quoted per RFC 4180
+    /// when it holds a NEAR account id containing a comma, quote or line break.
+    pub fn to_csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{}",
+            self.nonce,
+            self.response_index,
+            self.chain_id.0,
+            self.query_type,
+            self.block_number,
+            self.block_hash,
+            self.block_time,
+            self.block_age
+                .map(|age| age.to_string())
+                .unwrap_or_default(),
+            self.result_index,
+            csv_field(&self.target),
+            self.result
+        )
+    }
+}
+
+/// Quotes `field` if it contains a separator, quote or line break, doubling any quotes.
+fn csv_field(field: &str) -> std::borrow::Cow<'_, str> {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\"")).into()
+    } else {
+        field.into()
+    }
+}
+
+/// Flattens `response` into one record per call result or account. `received_at`, if
+/// given, fills in [`ExportRecord::block_age`].
+pub fn flatten(response: &QueryResponse, received_at: Option<SystemTime>) -> Vec<ExportRecord> {
+    let received_at = received_at
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|since_epoch| since_epoch.as_micros() as u64);
+
+    let mut records = Vec::new();
+    for (response_index, per_chain) in response.responses.iter().enumerate() {
+        let block = per_chain.response.block_info();
+        let query = response
+            .request
+            .requests
+            .get(response_index)
+            .map(|request| &request.query);
+        let mut push = |result_index: usize, target: String, result: &[u8]| {
+            records.push(ExportRecord {
+                nonce: response.request.nonce,
+                response_index,
+                chain_id: per_chain.chain(),
+                query_type: per_chain.response.query_type(),
+                block_number: block.number,
+                block_hash: hex::encode(block.hash),
+                block_time: block.time,
+                block_age: received_at.map(|now| now.saturating_sub(block.time)),
+                result_index,
+                target,
+                result: hex::encode(result),
+            })
+        };
+
+        let results = match &per_chain.response {
+            ChainSpecificResponse::EthCallQueryResponse(r) => &r.results,
+            ChainSpecificResponse::EthCallByTimestampQueryResponse(r) => &r.results,
+            ChainSpecificResponse::EthCallWithFinalityQueryResponse(r) => &r.results,
+            #[cfg(feature = "experimental")]
+            ChainSpecificResponse::EthCallWithOverridesQueryResponse(r) => &r.results,
+            ChainSpecificResponse::SolanaAccountQueryResponse(r) => {
+                let accounts = match query {
+                    Some(ChainSpecificQuery::SolanaAccountQueryRequest(q)) => &q.accounts[..],
+                    _ => &[],
+                };
+                for (i, result) in r.results.iter().enumerate() {
+                    let target = accounts.get(i).map(hex::encode).unwrap_or_default();
+                    push(i, target, &result.data);
+                }
+                continue;
+            }
+            #[cfg(feature = "experimental")]
+            ChainSpecificResponse::NearViewCallQueryResponse(r) => {
+                let calls = match query {
+                    Some(ChainSpecificQuery::NearViewCallQueryRequest(q)) => &q.calls[..],
+                    _ => &[],
+                };
+                for (i, result) in r.results.iter().enumerate() {
+                    let target = calls
+                        .get(i)
+                        .map(|call| call.account_id.clone())
+                        .unwrap_or_default();
+                    push(i, target, result);
+                }
+                continue;
+            }
+            #[cfg(feature = "experimental")]
+            ChainSpecificResponse::NearViewAccountQueryResponse(r) => {
+                let account_ids = match query {
+                    Some(ChainSpecificQuery::NearViewAccountQueryRequest(q)) => &q.account_ids[..],
+                    _ => &[],
+                };
+                for (i, result) in r.results.iter().enumerate() {
+                    let target = account_ids.get(i).cloned().unwrap_or_default();
+                    push(i, target, &result.serialize());
+                }
+                continue;
+            }
+        };
+        let calls: &[EthCallData] = match query {
+            Some(ChainSpecificQuery::EthCallQueryRequest(q)) => &q.call_data,
+            Some(ChainSpecificQuery::EthCallByTimestampQueryRequest(q)) => &q.call_data,
+            Some(ChainSpecificQuery::EthCallWithFinalityQueryRequest(q)) => &q.call_data,
+            #[cfg(feature = "experimental")]
+            Some(ChainSpecificQuery::EthCallWithOverridesQueryRequest(q)) => &q.call_data,
+            _ => &[],
+        };
+        for (i, result) in results.iter().enumerate() {
+            let target = calls
+                .get(i)
+                .map(|call| hex::encode(call.to))
+                .unwrap_or_default();
+            push(i, target, result);
+        }
+    }
+    records
+}
+
+/// Writes a header followed by the flattened records of every response. Stops at the
+/// first error, so `responses` can be a [`crate::stream::ResponseStream`] directly.
+pub fn write_csv<W, I>(wtr: &mut W, responses: I) -> std::result::Result<(), std::io::Error>
+where
+    W: Write,
+    I: IntoIterator<Item = std::result::Result<QueryResponse, std::io::Error>>,
+{
+    writeln!(wtr, "{}", ExportRecord::CSV_HEADER)?;
+    for response in responses {
+        for record in flatten(&response?, None) {
+            writeln!(wtr, "{}", record.to_csv_row())?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,70 @@
+//! Lookup of per chain requests and responses by chain and position, in place of zipping
+//! [`QueryRequest::requests`](crate::structs::QueryRequest::requests) with
+//! [`QueryResponse::responses`] by hand.
+
+use std::collections::BTreeMap;
+
+use crate::chain::ChainId;
+use crate::structs::{PerChainQueryRequest, PerChainQueryResponse, QueryResponse};
+
+/// The per chain requests of a response paired with their responses, ordered by chain id
+/// and then by position among the requests for that chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexedResponse<'a> {
+    entries: BTreeMap<(ChainId, usize), (&'a PerChainQueryRequest, &'a PerChainQueryResponse)>,
+}
+
+impl<'a> IndexedResponse<'a> {
+    /// The `query_index`th request for `chain` and its response.
+    pub fn get(
+        &self,
+        chain: ChainId,
+        query_index: usize,
+    ) -> Option<(&'a PerChainQueryRequest, &'a PerChainQueryResponse)> {
+        self.entries.get(&(chain, query_index)).copied()
+    }
+
+    /// All requests for `chain` and their responses, in request order.
+    pub fn chain(
+        &self,
+        chain: ChainId,
+    ) -> impl Iterator<Item = (&'a PerChainQueryRequest, &'a PerChainQueryResponse)> + '_ {
+        self.entries
+            .range((chain, 0)..=(chain, usize::MAX))
+            .map(|(_, entry)| *entry)
+    }
+
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<
+        Item = (
+            (ChainId, usize),
+            (&'a PerChainQueryRequest, &'a PerChainQueryResponse),
+        ),
+    > + '_ {
+        self.entries.iter().map(|(key, entry)| (*key, *entry))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl QueryResponse {
+    /// Indexes each per chain request and its response by chain id and position among the
+    /// requests for that chain. Fails if the responses do not line up with the requests.
+    pub fn indexed(&self) -> std::result::Result<IndexedResponse<'_>, std::io::Error> {
+        let mut next_index: BTreeMap<ChainId, usize> = BTreeMap::new();
+        let mut entries = BTreeMap::new();
+        for (request, response) in self.aligned_pairs()? {
+            let query_index = next_index.entry(request.chain()).or_default();
+            entries.insert((request.chain(), *query_index), (request, response));
+            *query_index += 1;
+        }
+        Ok(IndexedResponse { entries })
+    }
+}
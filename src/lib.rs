@@ -3,3 +3,40 @@ pub const MESSAGE_PREFIX: &[u8] = b"query_response_0000000000000000000|";
 pub const QUERY_MESSAGE_LEN: usize = MESSAGE_PREFIX.len() + 32;
 
 pub mod structs;
+
+pub mod abi;
+pub mod archive;
+pub mod budget;
+pub mod chain;
+pub mod compare;
+pub mod diff;
+pub mod export;
+pub mod indexed;
+pub mod pending;
+pub mod plan;
+pub mod stream;
+pub mod templates;
+pub mod vaa;
+pub mod verified;
+pub mod visit;
+
+#[cfg(feature = "client")]
+pub mod client;
+
+#[cfg(feature = "client")]
+pub mod executor;
+
+#[cfg(feature = "policy")]
+pub mod policy;
+
+#[cfg(feature = "crypto")]
+pub mod signature;
+
+#[cfg(feature = "solana")]
+pub mod solana;
+
+#[cfg(feature = "stats")]
+pub mod stats;
+
+#[cfg(feature = "devnet")]
+pub mod devnet;
@@ -0,0 +1,158 @@
+//! Freshness bookkeeping for submitted requests.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::chain::ChainId;
+use crate::structs::{ChainSpecificResponse, QueryRequest, QueryResponse};
+
+/// A submitted request along with how long its response stays acceptable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingQuery {
+    pub request: QueryRequest,
+    pub created_at: SystemTime,
+    /// Responses received after this are rejected.
+    pub deadline: SystemTime,
+    /// Oldest block a response may have been read at, relative to when it was received.
+    pub max_block_age: Option<Duration>,
+}
+
+impl PendingQuery {
+    /// Starts tracking `request` now, accepting responses for `timeout`. A timeout past
+    /// the latest time the platform can represent never expires.
+    pub fn new(request: QueryRequest, timeout: Duration) -> PendingQuery {
+        let created_at = SystemTime::now();
+        PendingQuery {
+            request,
+            created_at,
+            deadline: saturating_add(created_at, timeout),
+            max_block_age: None,
+        }
+    }
+
+    pub fn with_max_block_age(mut self, max_block_age: Duration) -> PendingQuery {
+        self.max_block_age = Some(max_block_age);
+        self
+    }
+
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        now > self.deadline
+    }
+
+    /// Checks that `response` answers this request, arrived by the deadline and, if a
+    /// maximum block age is set, that every block it was read at is recent enough.
+    ///
+    /// eth_call_by_timestamp responses are exempt from the block age check, as they
+    /// deliberately target a block in the past.
+    pub fn accept(
+        &self,
+        response: &QueryResponse,
+        received_at: SystemTime,
+    ) -> std::result::Result<(), std::io::Error> {
+        if response.request != self.request {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "RequestMismatch",
+            ));
+        }
+        if self.is_expired(received_at) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "DeadlineExceeded",
+            ));
+        }
+        let Some(max_block_age) = self.max_block_age else {
+            return Ok(());
+        };
+        let received_at = received_at
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, "InvalidTime"))?
+            .as_micros() as u64;
+        for per_chain in &response.responses {
+            if let ChainSpecificResponse::EthCallByTimestampQueryResponse(_) = per_chain.response {
+                continue;
+            }
+            let age = received_at.saturating_sub(per_chain.response.block_info().time);
+            if age > max_block_age.as_micros() as u64 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "BlockTooOld",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Measures `response` against when this request was created, see
+    /// [`QueryTiming::measure`].
+    pub fn timing(&self, response: &QueryResponse, received_at: SystemTime) -> QueryTiming {
+        QueryTiming::measure(self.created_at, received_at, response)
+    }
+}
+
+/// How long a response took and how old its data was, for latency and freshness
+/// monitoring. Durations which would be negative because of clock skew are zero.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryTiming {
+    /// From request creation until the response was received.
+    pub latency: Duration,
+    /// One entry per per chain response, in order.
+    pub chains: Vec<ChainTiming>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainTiming {
+    pub chain_id: ChainId,
+    pub block_time: SystemTime,
+    /// Age of the block when the response was received.
+    pub staleness: Duration,
+    /// Age of the block when the request was created.
+    pub age_at_request: Duration,
+}
+
+impl QueryTiming {
+    /// Converts each block time from microseconds since the epoch and compares it with
+    /// `created_at` and `received_at`.
+    pub fn measure(
+        created_at: SystemTime,
+        received_at: SystemTime,
+        response: &QueryResponse,
+    ) -> QueryTiming {
+        let elapsed =
+            |from: SystemTime, to: SystemTime| to.duration_since(from).unwrap_or_default();
+        let chains = response
+            .responses
+            .iter()
+            .map(|per_chain| {
+                let block_time = saturating_add(
+                    UNIX_EPOCH,
+                    Duration::from_micros(per_chain.response.block_time()),
+                );
+                ChainTiming {
+                    chain_id: per_chain.chain(),
+                    block_time,
+                    staleness: elapsed(block_time, received_at),
+                    age_at_request: elapsed(block_time, created_at),
+                }
+            })
+            .collect();
+        QueryTiming {
+            latency: elapsed(created_at, received_at),
+            chains,
+        }
+    }
+
+    /// The oldest data in the response, as of when it was received.
+    pub fn max_staleness(&self) -> Option<Duration> {
+        self.chains.iter().map(|chain| chain.staleness).max()
+    }
+}
+
+/// `time + duration`, or as close to it as the platform can represent.
+fn saturating_add(time: SystemTime, mut duration: Duration) -> SystemTime {
+    loop {
+        if let Some(sum) = time.checked_add(duration) {
+            return sum;
+        }
+        duration /= 2;
+    }
+}
@@ -0,0 +1,378 @@
+//! Declarative query plans.
+//!
+//! A [`QueryPlan`] collects labelled items ("ERC20 balance of X on Ethereum", "account Y
+//! on Solana") and compiles them into a single [`QueryRequest`], batching every call
+//! against the same chain and block into one per chain request. Results are read back
+//! by label.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::chain::ChainId;
+use crate::structs::{
+    ChainSpecificQuery, ChainSpecificResponse, EthCallData, EthCallQueryRequest,
+    EthCallWithFinalityQueryRequest, PerChainQueryRequest, QueryRequest, QueryResponse,
+    SolanaAccountQueryRequest, SolanaAccountResult,
+};
+
+/// `balanceOf(address)`
+const ERC20_BALANCE_OF: [u8; 4] = [0x70, 0xa0, 0x82, 0x31];
+
+/// Most calls or accounts a per chain request can carry, as their count is one byte.
+const MAX_ENTRIES: usize = u8::MAX as usize;
+
+/// The block an EVM call is made against.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum EvmBlock {
+    /// A block number or hash, queried with `eth_call`.
+    At(String),
+    /// A block number or hash which must have reached `finality`, queried with
+    /// `eth_call_with_finality`.
+    WithFinality { block_tag: String, finality: String },
+}
+
+impl EvmBlock {
+    pub fn finalized(block_tag: impl Into<String>) -> EvmBlock {
+        EvmBlock::WithFinality {
+            block_tag: block_tag.into(),
+            finality: EthCallWithFinalityQueryRequest::FINALITY_FINALIZED.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum PlanItem {
+    EthCall {
+        chain: ChainId,
+        block: EvmBlock,
+        call: EthCallData,
+        erc20_balance: bool,
+    },
+    SolanaAccount {
+        account: [u8; 32],
+    },
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QueryPlan {
+    nonce: u32,
+    items: Vec<(String, PlanItem)>,
+}
+
+impl QueryPlan {
+    pub fn new(nonce: u32) -> QueryPlan {
+        QueryPlan {
+            nonce,
+            items: Vec::new(),
+        }
+    }
+
+    /// A raw `eth_call` of `data` against `to`.
+    pub fn eth_call(
+        mut self,
+        label: impl Into<String>,
+        chain: ChainId,
+        block: EvmBlock,
+        to: [u8; 20],
+        data: Vec<u8>,
+    ) -> QueryPlan {
+        self.items.push((
+            label.into(),
+            PlanItem::EthCall {
+                chain,
+                block,
+                call: EthCallData { to, data },
+                erc20_balance: false,
+            },
+        ));
+        self
+    }
+
+    /// The `balanceOf(owner)` of the ERC20 `token`.
+    pub fn erc20_balance(
+        mut self,
+        label: impl Into<String>,
+        chain: ChainId,
+        block: EvmBlock,
+        token: [u8; 20],
+        owner: [u8; 20],
+    ) -> QueryPlan {
+        let mut data = ERC20_BALANCE_OF.to_vec();
+        data.extend_from_slice(&[0u8; 12]);
+        data.extend_from_slice(&owner);
+        self.items.push((
+            label.into(),
+            PlanItem::EthCall {
+                chain,
+                block,
+                call: EthCallData { to: token, data },
+                erc20_balance: true,
+            },
+        ));
+        self
+    }
+
+    /// A Solana account, read at the `finalized` commitment.
+    pub fn solana_account(mut self, label: impl Into<String>, account: [u8; 32]) -> QueryPlan {
+        self.items
+            .push((label.into(), PlanItem::SolanaAccount { account }));
+        self
+    }
+
+    /// Compiles the plan into a request, with one per chain request for each distinct
+    /// chain and block, or more where one would exceed 255 calls or accounts.
+    pub fn compile(&self) -> std::result::Result<CompiledPlan, std::io::Error> {
+        if self.items.is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "EmptyPlan",
+            ));
+        }
+
+        let mut requests: Vec<PerChainQueryRequest> = Vec::new();
+        let mut slots = Vec::with_capacity(self.items.len());
+        let mut seen = HashSet::new();
+        for (label, item) in &self.items {
+            if !seen.insert(label.as_str()) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "DuplicateLabel",
+                ));
+            }
+            let (chain, query, kind) = match item {
+                PlanItem::EthCall {
+                    chain,
+                    block,
+                    call,
+                    erc20_balance,
+                } => {
+                    let query = match block {
+                        EvmBlock::At(block_tag) => {
+                            ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+                                block_tag: block_tag.clone(),
+                                call_data: vec![call.clone()],
+                            })
+                        }
+                        EvmBlock::WithFinality {
+                            block_tag,
+                            finality,
+                        } => ChainSpecificQuery::EthCallWithFinalityQueryRequest(
+                            EthCallWithFinalityQueryRequest::new(
+                                block_tag.clone(),
+                                finality.clone(),
+                                vec![call.clone()],
+                            )?,
+                        ),
+                    };
+                    let kind = if *erc20_balance {
+                        SlotKind::Erc20Balance
+                    } else {
+                        SlotKind::Call
+                    };
+                    (*chain, query, kind)
+                }
+                PlanItem::SolanaAccount { account } => (
+                    ChainId::SOLANA,
+                    ChainSpecificQuery::SolanaAccountQueryRequest(SolanaAccountQueryRequest {
+                        commitment: SolanaAccountQueryRequest::COMMITMENT_FINALIZED.to_string(),
+                        min_context_slot: 0,
+                        data_slice_offset: 0,
+                        data_slice_length: 0,
+                        accounts: vec![*account],
+                    }),
+                    SlotKind::SolanaAccount,
+                ),
+            };
+            slots.push(merge_query(&mut requests, label, chain, query, kind));
+        }
+
+        Ok(CompiledPlan {
+            request: QueryRequest {
+                version: QueryRequest::REQUEST_VERSION,
+                nonce: self.nonce,
+                requests,
+            },
+            slots,
+        })
+    }
+
+    /// Compiles the plan, submits it through `client` and resolves the results.
+    ///
+    /// Guardian signatures are not checked here; verify the returned signed response
+    /// before trusting the results.
+    #[cfg(feature = "client")]
+    pub fn execute(
+        &self,
+        client: &crate::client::QueryProxyClient,
+    ) -> std::result::Result<(crate::client::SignedQueryResponse, PlanResults), std::io::Error>
+    {
+        let compiled = self.compile()?;
+        let signed = client.query(&compiled.request)?;
+        let results = compiled.resolve(&signed.parse()?)?;
+        Ok((signed, results))
+    }
+}
+
+/// Appends `query` to the per chain request for the same chain and block, or starts a
+/// new one if there is none or it is full, returning where its result will be found.
+fn merge_query(
+    requests: &mut Vec<PerChainQueryRequest>,
+    label: &str,
+    chain: ChainId,
+    query: ChainSpecificQuery,
+    kind: SlotKind,
+) -> Slot {
+    for (request_index, existing) in requests.iter_mut().enumerate() {
+        if existing.chain() != chain {
+            continue;
+        }
+        let result_index = match (&mut existing.query, &query) {
+            (
+                ChainSpecificQuery::EthCallQueryRequest(existing),
+                ChainSpecificQuery::EthCallQueryRequest(new),
+            ) if existing.block_tag == new.block_tag && existing.call_data.len() < MAX_ENTRIES => {
+                existing.call_data.extend(new.call_data.iter().cloned());
+                existing.call_data.len() - 1
+            }
+            (
+                ChainSpecificQuery::EthCallWithFinalityQueryRequest(existing),
+                ChainSpecificQuery::EthCallWithFinalityQueryRequest(new),
+            ) if existing.block_tag == new.block_tag
+                && existing.finality == new.finality
+                && existing.call_data.len() < MAX_ENTRIES =>
+            {
+                existing.call_data.extend(new.call_data.iter().cloned());
+                existing.call_data.len() - 1
+            }
+            (
+                ChainSpecificQuery::SolanaAccountQueryRequest(existing),
+                ChainSpecificQuery::SolanaAccountQueryRequest(new),
+            ) if existing.accounts.len() < MAX_ENTRIES => {
+                existing.accounts.extend(new.accounts.iter().copied());
+                existing.accounts.len() - 1
+            }
+            _ => continue,
+        };
+        return Slot {
+            label: label.to_string(),
+            request_index,
+            result_index,
+            kind,
+        };
+    }
+    requests.push(PerChainQueryRequest::new(chain.into(), query));
+    Slot {
+        label: label.to_string(),
+        request_index: requests.len() - 1,
+        result_index: 0,
+        kind,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SlotKind {
+    Call,
+    Erc20Balance,
+    SolanaAccount,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Slot {
+    label: String,
+    request_index: usize,
+    result_index: usize,
+    kind: SlotKind,
+}
+
+/// A compiled [`QueryPlan`]: the request to submit and where each label's result lives.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompiledPlan {
+    pub request: QueryRequest,
+    slots: Vec<Slot>,
+}
+
+impl CompiledPlan {
+    /// Extracts the result for every label from a response to [`CompiledPlan::request`].
+    pub fn resolve(
+        &self,
+        response: &QueryResponse,
+    ) -> std::result::Result<PlanResults, std::io::Error> {
+        if response.request != self.request {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "RequestMismatch",
+            ));
+        }
+        let missing = || std::io::Error::new(std::io::ErrorKind::InvalidData, "MissingResult");
+
+        let mut values = HashMap::with_capacity(self.slots.len());
+        for slot in &self.slots {
+            let per_chain = response
+                .responses
+                .get(slot.request_index)
+                .ok_or_else(missing)?;
+            let value = match (&per_chain.response, slot.kind) {
+                (ChainSpecificResponse::SolanaAccountQueryResponse(r), SlotKind::SolanaAccount) => {
+                    PlanValue::SolanaAccount(
+                        r.results
+                            .get(slot.result_index)
+                            .ok_or_else(missing)?
+                            .clone(),
+                    )
+                }
+                (ChainSpecificResponse::EthCallQueryResponse(r), kind) => {
+                    call_value(&r.results, slot.result_index, kind)?
+                }
+                (ChainSpecificResponse::EthCallWithFinalityQueryResponse(r), kind) => {
+                    call_value(&r.results, slot.result_index, kind)?
+                }
+                _ => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "UnexpectedResponseType",
+                    ))
+                }
+            };
+            values.insert(slot.label.clone(), value);
+        }
+        Ok(PlanResults { values })
+    }
+}
+
+fn call_value(
+    results: &[Vec<u8>],
+    index: usize,
+    kind: SlotKind,
+) -> std::result::Result<PlanValue, std::io::Error> {
+    let result = results
+        .get(index)
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "MissingResult"))?;
+    match kind {
+        SlotKind::Erc20Balance => Ok(PlanValue::Erc20Balance(
+            result.as_slice().try_into().map_err(|_| {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, "InvalidErc20Balance")
+            })?,
+        )),
+        _ => Ok(PlanValue::Call(result.clone())),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PlanValue {
+    /// The raw return data of an `eth_call`.
+    Call(Vec<u8>),
+    /// A `uint256` balance, big endian.
+    Erc20Balance([u8; 32]),
+    SolanaAccount(SolanaAccountResult),
+}
+
+/// The results of a plan, keyed by label.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlanResults {
+    pub values: HashMap<String, PlanValue>,
+}
+
+impl PlanResults {
+    pub fn get(&self, label: &str) -> Option<&PlanValue> {
+        self.values.get(label)
+    }
+}
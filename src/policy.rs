@@ -0,0 +1,248 @@
+//! Local checking of requests against query server permissions.
+//!
+//! [`Policy`] reads the permissions file of the guardian query server (`ccqPermissions`
+//! JSON) so a request can be checked before it is submitted. As on the server, EVM calls
+//! are allowed per chain, contract and four byte selector, and Solana accounts per chain
+//! and account.
+//!
+//! Only JSON is read. The query server itself only loads JSON permissions, so a YAML
+//! copy would have to be converted before the server could use it anyway.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use serde::Deserialize;
+
+use crate::chain::ChainId;
+use crate::structs::{ChainSpecificQuery, QueryRequest};
+
+#[derive(Deserialize)]
+struct PermissionsFile {
+    #[serde(rename = "Permissions")]
+    permissions: Vec<PermissionEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PermissionEntry {
+    #[serde(default)]
+    user_name: String,
+    api_key: String,
+    #[serde(default)]
+    allow_anything: bool,
+    #[serde(default)]
+    allowed_calls: Vec<AllowedCallEntry>,
+}
+
+/// Exactly one field is set per entry. Entry types this SDK has no query for are ignored.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AllowedCallEntry {
+    eth_call: Option<EthCallEntry>,
+    eth_call_by_timestamp: Option<EthCallEntry>,
+    eth_call_with_finality: Option<EthCallEntry>,
+    sol_account: Option<SolAccountEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EthCallEntry {
+    chain: u16,
+    contract_address: String,
+    call: String,
+}
+
+#[derive(Deserialize)]
+struct SolAccountEntry {
+    chain: u16,
+    account: String,
+}
+
+/// What a single allowed call entry permits.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Allowed {
+    EthCall {
+        query_type: u8,
+        chain_id: u16,
+        contract: [u8; 20],
+        selector: [u8; 4],
+    },
+    SolanaAccount {
+        chain_id: u16,
+        account: [u8; 32],
+    },
+}
+
+/// The permissions of one API key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Policy {
+    pub user_name: String,
+    pub allow_anything: bool,
+    allowed: HashSet<Allowed>,
+}
+
+/// The first part of a request a [`Policy`] does not allow.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PolicyViolation {
+    /// Index of the per chain request.
+    pub request_index: usize,
+    /// Index of the call or account within it, if the query type itself is allowed.
+    pub item_index: Option<usize>,
+    pub chain_id: ChainId,
+    pub query_type: u8,
+    /// `0x` hex of the contract and selector, or of the Solana account.
+    pub target: Option<String>,
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "request {} on chain {}: ",
+            self.request_index, self.chain_id
+        )?;
+        match (self.item_index, &self.target) {
+            (Some(item_index), Some(target)) => write!(
+                f,
+                "item {} ({}) not allowed for query type {}",
+                item_index, target, self.query_type
+            ),
+            _ => write!(f, "query type {} not allowed", self.query_type),
+        }
+    }
+}
+
+impl std::error::Error for PolicyViolation {}
+
+impl From<PolicyViolation> for std::io::Error {
+    fn from(violation: PolicyViolation) -> std::io::Error {
+        std::io::Error::new(std::io::ErrorKind::PermissionDenied, violation)
+    }
+}
+
+impl Policy {
+    /// Loads the permissions of `api_key` from a server permissions file. There is no
+    /// YAML counterpart, see the [module documentation](self).
+    pub fn from_json(json: &str, api_key: &str) -> std::result::Result<Policy, std::io::Error> {
+        let invalid = || std::io::Error::new(std::io::ErrorKind::InvalidData, "InvalidPermissions");
+        let file: PermissionsFile = serde_json::from_str(json).map_err(|_| invalid())?;
+        let entry = file
+            .permissions
+            .into_iter()
+            .find(|entry| entry.api_key == api_key)
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "UnknownApiKey"))?;
+
+        let mut allowed = HashSet::new();
+        for call in entry.allowed_calls {
+            let eth_calls = [
+                (1, call.eth_call),
+                (2, call.eth_call_by_timestamp),
+                (3, call.eth_call_with_finality),
+            ];
+            for (query_type, eth_call) in eth_calls {
+                if let Some(eth_call) = eth_call {
+                    allowed.insert(Allowed::EthCall {
+                        query_type,
+                        chain_id: eth_call.chain,
+                        contract: decode_hex_array(&eth_call.contract_address)
+                            .ok_or_else(invalid)?,
+                        selector: decode_hex_array(&eth_call.call).ok_or_else(invalid)?,
+                    });
+                }
+            }
+            if let Some(sol_account) = call.sol_account {
+                allowed.insert(Allowed::SolanaAccount {
+                    chain_id: sol_account.chain,
+                    account: decode_account(&sol_account.account).ok_or_else(invalid)?,
+                });
+            }
+        }
+
+        Ok(Policy {
+            user_name: entry.user_name,
+            allow_anything: entry.allow_anything,
+            allowed,
+        })
+    }
+
+    /// Fails with the first per chain request, call or account which is not allowed.
+    pub fn check(&self, request: &QueryRequest) -> std::result::Result<(), PolicyViolation> {
+        if self.allow_anything {
+            return Ok(());
+        }
+        for (request_index, per_chain) in request.requests.iter().enumerate() {
+            let chain_id = per_chain.chain_id;
+            let query_type = per_chain.query.query_type();
+            let violation = |item_index: Option<usize>, target: Option<String>| PolicyViolation {
+                request_index,
+                item_index,
+                chain_id: per_chain.chain(),
+                query_type,
+                target,
+            };
+            let call_data = match &per_chain.query {
+                ChainSpecificQuery::EthCallQueryRequest(query) => &query.call_data,
+                ChainSpecificQuery::EthCallByTimestampQueryRequest(query) => &query.call_data,
+                ChainSpecificQuery::EthCallWithFinalityQueryRequest(query) => &query.call_data,
+                ChainSpecificQuery::SolanaAccountQueryRequest(query) => {
+                    for (i, account) in query.accounts.iter().enumerate() {
+                        let key = Allowed::SolanaAccount {
+                            chain_id,
+                            account: *account,
+                        };
+                        if !self.allowed.contains(&key) {
+                            return Err(violation(
+                                Some(i),
+                                Some(format!("0x{}", hex::encode(account))),
+                            ));
+                        }
+                    }
+                    continue;
+                }
+                #[cfg(feature = "experimental")]
+                _ => return Err(violation(None, None)),
+            };
+            for (i, call) in call_data.iter().enumerate() {
+                let mut selector = [0u8; 4];
+                let len = call.data.len().min(4);
+                selector[..len].copy_from_slice(&call.data[..len]);
+                let key = Allowed::EthCall {
+                    query_type,
+                    chain_id,
+                    contract: call.to,
+                    selector,
+                };
+                if !self.allowed.contains(&key) {
+                    return Err(violation(
+                        Some(i),
+                        Some(format!(
+                            "0x{}:0x{}",
+                            hex::encode(call.to),
+                            hex::encode(selector)
+                        )),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn decode_hex_array<const N: usize>(s: &str) -> Option<[u8; N]> {
+    hex::decode(s.strip_prefix("0x").unwrap_or(s))
+        .ok()?
+        .try_into()
+        .ok()
+}
+
+/// Solana accounts are given in base58, or in hex with a `0x` prefix.
+fn decode_account(s: &str) -> Option<[u8; 32]> {
+    if s.starts_with("0x") {
+        return decode_hex_array(s);
+    }
+    let mut account = [0u8; 32];
+    match bs58::decode(s).onto(&mut account) {
+        Ok(32) => Some(account),
+        _ => None,
+    }
+}
@@ -0,0 +1,431 @@
+//! Signing and verification of query responses by guardians.
+//!
+//! Guardians sign `keccak256(MESSAGE_PREFIX || keccak256(response))`. Signatures travel
+//! as 66 bytes: the 65 byte `r || s || v` secp256k1 signature followed by the index of the
+//! signing guardian in the guardian set.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime};
+
+use k256::ecdsa::{RecoveryId, Signature, SigningKey, VerifyingKey};
+use sha3::{Digest, Keccak256};
+
+use crate::structs::QueryResponse;
+use crate::MESSAGE_PREFIX;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GuardianSet {
+    pub index: u32,
+    pub keys: Vec<[u8; 20]>,
+}
+
+impl GuardianSet {
+    /// The number of valid signatures required, i.e. more than two thirds of the set.
+    pub fn quorum(&self) -> usize {
+        self.keys.len() * 2 / 3 + 1
+    }
+}
+
+/// Known guardian sets, each valid until its expiration time, as tracked by the core
+/// bridge. The current set never expires; on an upgrade the previous set stays valid for
+/// a transition window so responses signed just before it can still be verified.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GuardianSetRegistry {
+    sets: BTreeMap<u32, (GuardianSet, Option<SystemTime>)>,
+}
+
+impl GuardianSetRegistry {
+    pub fn new(current: GuardianSet) -> GuardianSetRegistry {
+        let mut registry = GuardianSetRegistry::default();
+        registry.insert(current, None);
+        registry
+    }
+
+    /// Adds or replaces a set. `expiration_time` is `None` for a set which does not expire.
+    pub fn insert(&mut self, guardian_set: GuardianSet, expiration_time: Option<SystemTime>) {
+        self.sets
+            .insert(guardian_set.index, (guardian_set, expiration_time));
+    }
+
+    /// Makes `guardian_set` current, expiring the previous current set `transition` after
+    /// `now`. The core bridge uses a 24 hour transition.
+    pub fn upgrade(&mut self, guardian_set: GuardianSet, transition: Duration, now: SystemTime) {
+        if let Some((_, expiration_time)) = self.sets.values_mut().next_back() {
+            expiration_time.get_or_insert(now + transition);
+        }
+        self.insert(guardian_set, None);
+    }
+
+    /// The set with the highest index.
+    pub fn current(&self) -> Option<&GuardianSet> {
+        self.sets.values().next_back().map(|(set, _)| set)
+    }
+
+    /// The set with `index`, if it is known and has not expired at `now`.
+    pub fn get(
+        &self,
+        index: u32,
+        now: SystemTime,
+    ) -> std::result::Result<&GuardianSet, std::io::Error> {
+        let (guardian_set, expiration_time) = self.sets.get(&index).ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, "UnknownGuardianSetIndex")
+        })?;
+        if expiration_time.is_some_and(|expiration_time| now >= expiration_time) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "GuardianSetExpired",
+            ));
+        }
+        Ok(guardian_set)
+    }
+
+    /// [`verify_signatures`] against the set the submitter claims signed the response,
+    /// failing if that set is unknown or expired at `now`.
+    pub fn verify(
+        &self,
+        response: &[u8],
+        signatures: &[[u8; 66]],
+        guardian_set_index: u32,
+        now: SystemTime,
+    ) -> std::result::Result<(), std::io::Error> {
+        verify_signatures(response, signatures, self.get(guardian_set_index, now)?)
+    }
+}
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/// The digest guardians sign for a serialized query response.
+pub fn query_response_digest(response: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(MESSAGE_PREFIX);
+    hasher.update(keccak256(response));
+    hasher.finalize().into()
+}
+
+/// Signs `response` with a raw secp256k1 private key, tagging the signature with
+/// `guardian_index`.
+pub fn sign_response(
+    response: &[u8],
+    private_key: &[u8; 32],
+    guardian_index: u8,
+) -> std::result::Result<[u8; 66], std::io::Error> {
+    let signing_key = SigningKey::from_slice(private_key)
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, "InvalidPrivateKey"))?;
+    let (signature, recovery_id) = signing_key
+        .sign_prehash_recoverable(&query_response_digest(response))
+        .map_err(std::io::Error::other)?;
+    let mut out = [0u8; 66];
+    out[..64].copy_from_slice(&signature.to_bytes());
+    out[64] = recovery_id.to_byte();
+    out[65] = guardian_index;
+    Ok(out)
+}
+
+/// Recovers the Ethereum style address which produced a 65 byte signature over `digest`.
+pub fn recover_address(
+    digest: &[u8; 32],
+    signature: &[u8],
+) -> std::result::Result<[u8; 20], std::io::Error> {
+    let invalid_signature =
+        || std::io::Error::new(std::io::ErrorKind::InvalidData, "InvalidSignature");
+    if signature.len() != 65 {
+        return Err(invalid_signature());
+    }
+    let sig = Signature::from_slice(&signature[..64]).map_err(|_| invalid_signature())?;
+    // Accept both raw (0/1) and Ethereum style (27/28) recovery ids.
+    let v = if signature[64] >= 27 {
+        signature[64] - 27
+    } else {
+        signature[64]
+    };
+    let recovery_id = RecoveryId::from_byte(v).ok_or_else(invalid_signature)?;
+    let key = VerifyingKey::recover_from_prehash(digest, &sig, recovery_id)
+        .map_err(|_| invalid_signature())?;
+    Ok(public_key_to_address(&key))
+}
+
+pub(crate) fn public_key_to_address(key: &VerifyingKey) -> [u8; 20] {
+    let point = key.to_encoded_point(false);
+    let hash = keccak256(&point.as_bytes()[1..]);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    address
+}
+
+/// Verifies that `signatures` over `response` reach quorum for `guardian_set`.
+///
+/// As on chain, signatures must be ordered by strictly ascending guardian index and every
+/// signature must be valid, not just a quorum of them.
+pub fn verify_signatures(
+    response: &[u8],
+    signatures: &[[u8; 66]],
+    guardian_set: &GuardianSet,
+) -> std::result::Result<(), std::io::Error> {
+    check_signatures(
+        &query_response_digest(response),
+        signatures,
+        guardian_set,
+        guardian_set.quorum(),
+    )
+}
+
+fn check_signatures(
+    digest: &[u8; 32],
+    signatures: &[[u8; 66]],
+    guardian_set: &GuardianSet,
+    quorum: usize,
+) -> std::result::Result<(), std::io::Error> {
+    if signatures.len() < quorum {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "NoQuorum",
+        ));
+    }
+
+    let mut last_index: Option<u8> = None;
+    for signature in signatures {
+        let guardian_index = signature[65];
+        if last_index.is_some_and(|last| guardian_index <= last) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "SignatureIndicesNotAscending",
+            ));
+        }
+        last_index = Some(guardian_index);
+
+        let key = guardian_set
+            .keys
+            .get(usize::from(guardian_index))
+            .ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, "InvalidGuardianIndex")
+            })?;
+        if recover_address(digest, &signature[..65])? != *key {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "GuardianSignatureMismatch",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Verifies many responses against one guardian set.
+///
+/// The message prefix is absorbed into the hasher once, and the quorum computed once,
+/// rather than per response. With the `rayon` feature, [`Verifier::verify_many`] checks
+/// responses in parallel.
+#[derive(Clone, Debug)]
+pub struct Verifier {
+    guardian_set: GuardianSet,
+    quorum: usize,
+    prefixed: Keccak256,
+}
+
+impl Verifier {
+    pub fn new(guardian_set: GuardianSet) -> Verifier {
+        let mut prefixed = Keccak256::new();
+        prefixed.update(MESSAGE_PREFIX);
+        Verifier {
+            quorum: guardian_set.quorum(),
+            guardian_set,
+            prefixed,
+        }
+    }
+
+    pub fn guardian_set(&self) -> &GuardianSet {
+        &self.guardian_set
+    }
+
+    /// Same as [`query_response_digest`].
+    pub fn digest(&self, response: &[u8]) -> [u8; 32] {
+        let mut hasher = self.prefixed.clone();
+        hasher.update(keccak256(response));
+        hasher.finalize().into()
+    }
+
+    /// Same as [`verify_signatures`] with this verifier's guardian set.
+    pub fn verify(
+        &self,
+        response: &[u8],
+        signatures: &[[u8; 66]],
+    ) -> std::result::Result<(), std::io::Error> {
+        check_signatures(
+            &self.digest(response),
+            signatures,
+            &self.guardian_set,
+            self.quorum,
+        )
+    }
+
+    /// Verifies each `(response, signatures)` pair, returning the results in order.
+    pub fn verify_many(
+        &self,
+        items: &[(&[u8], &[[u8; 66]])],
+    ) -> Vec<std::result::Result<(), std::io::Error>> {
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            items
+                .par_iter()
+                .map(|(response, signatures)| self.verify(response, signatures))
+                .collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            items
+                .iter()
+                .map(|(response, signatures)| self.verify(response, signatures))
+                .collect()
+        }
+    }
+}
+
+/// A response whose signatures reached quorum. Only constructed by
+/// [`VerifiedResponse::verify`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifiedResponse {
+    response: QueryResponse,
+    bytes: Vec<u8>,
+    signatures: Vec<[u8; 66]>,
+}
+
+impl VerifiedResponse {
+    /// Verifies `signatures` over `bytes` with [`verify_signatures`], then parses them.
+    pub fn verify(
+        bytes: Vec<u8>,
+        signatures: Vec<[u8; 66]>,
+        guardian_set: &GuardianSet,
+    ) -> std::result::Result<VerifiedResponse, std::io::Error> {
+        verify_signatures(&bytes, &signatures, guardian_set)?;
+        Ok(VerifiedResponse {
+            response: QueryResponse::deserialize(&bytes)?,
+            bytes,
+            signatures,
+        })
+    }
+
+    /// Like [`VerifiedResponse::verify`], checking against the registry's set with
+    /// `guardian_set_index` as of `now`.
+    pub fn verify_with_registry(
+        bytes: Vec<u8>,
+        signatures: Vec<[u8; 66]>,
+        registry: &GuardianSetRegistry,
+        guardian_set_index: u32,
+        now: SystemTime,
+    ) -> std::result::Result<VerifiedResponse, std::io::Error> {
+        Self::verify(bytes, signatures, registry.get(guardian_set_index, now)?)
+    }
+
+    pub fn response(&self) -> &QueryResponse {
+        &self.response
+    }
+
+    /// The signed bytes, e.g. for submission on chain.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn signatures(&self) -> &[[u8; 66]] {
+        &self.signatures
+    }
+
+    pub fn into_response(self) -> QueryResponse {
+        self.response
+    }
+}
+
+/// The outcome of checking one signature, as reported by [`verify_with_report`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignatureCheck {
+    pub guardian_index: u8,
+    /// `None` if no key could be recovered from the signature.
+    pub recovered: Option<[u8; 20]>,
+    /// `None` if the index is outside the guardian set.
+    pub expected: Option<[u8; 20]>,
+    pub valid: bool,
+}
+
+/// How the request embedded in a response compares to the request the caller sent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RequestMatch {
+    NotChecked,
+    Matches,
+    Differs(crate::diff::Difference),
+    /// The request could not be located inside the response.
+    Unreadable,
+}
+
+/// A record of everything [`verify_with_report`] checked, for audit logs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerificationReport {
+    pub digest: [u8; 32],
+    pub guardian_set_index: u32,
+    pub signatures: Vec<SignatureCheck>,
+    pub valid_signatures: usize,
+    pub quorum: usize,
+    pub indices_ascending: bool,
+    pub request_match: RequestMatch,
+}
+
+impl VerificationReport {
+    /// True under the same rules as [`verify_signatures`], and if checked, the embedded
+    /// request matches.
+    pub fn is_verified(&self) -> bool {
+        self.indices_ascending
+            && self.signatures.iter().all(|check| check.valid)
+            && self.valid_signatures >= self.quorum
+            && matches!(
+                self.request_match,
+                RequestMatch::NotChecked | RequestMatch::Matches
+            )
+    }
+}
+
+/// Like [`verify_signatures`], but checks everything rather than stopping at the first
+/// failure and reports the result of each check. If `expected_request` is given, it is
+/// compared against the request embedded in `response`.
+pub fn verify_with_report(
+    response: &[u8],
+    signatures: &[[u8; 66]],
+    guardian_set: &GuardianSet,
+    expected_request: Option<&[u8]>,
+) -> VerificationReport {
+    let digest = query_response_digest(response);
+    let checks: Vec<SignatureCheck> = signatures
+        .iter()
+        .map(|signature| {
+            let guardian_index = signature[65];
+            let recovered = recover_address(&digest, &signature[..65]).ok();
+            let expected = guardian_set.keys.get(usize::from(guardian_index)).copied();
+            SignatureCheck {
+                guardian_index,
+                recovered,
+                expected,
+                valid: recovered.is_some() && recovered == expected,
+            }
+        })
+        .collect();
+    let indices_ascending = checks
+        .windows(2)
+        .all(|pair| pair[0].guardian_index < pair[1].guardian_index);
+    let request_match = match expected_request {
+        None => RequestMatch::NotChecked,
+        Some(request) => match crate::diff::diff_request_with_response(request, response) {
+            Ok(None) => RequestMatch::Matches,
+            Ok(Some(difference)) => RequestMatch::Differs(difference),
+            Err(_) => RequestMatch::Unreadable,
+        },
+    };
+
+    VerificationReport {
+        digest,
+        guardian_set_index: guardian_set.index,
+        valid_signatures: checks.iter().filter(|check| check.valid).count(),
+        signatures: checks,
+        quorum: guardian_set.quorum(),
+        indices_ascending,
+        request_match,
+    }
+}
@@ -0,0 +1,157 @@
+//! Instructions for verifying a response on Solana with the reference query verification
+//! program.
+//!
+//! Guardian signatures do not fit in a single transaction alongside a response, so they
+//! are first written to a fresh signatures account with one or more `post_signatures`
+//! instructions, after which `verify_query` checks the response against them and closes
+//! the account. The program is an Anchor program: instruction data is the eight byte
+//! discriminator followed by the Borsh encoded arguments.
+//!
+//! Only instruction construction is provided, without depending on the Solana SDK:
+//! convert [`Instruction`] into the type of whichever client library is in use.
+
+/// Seed of the core bridge guardian set accounts, followed by the big endian index.
+pub const GUARDIAN_SET_SEED: &[u8] = b"GuardianSet";
+
+/// The system program, `11111111111111111111111111111111`.
+pub const SYSTEM_PROGRAM_ID: [u8; 32] = [0; 32];
+
+/// Signatures which fit in one `post_signatures` instruction within a transaction.
+pub const MAX_SIGNATURES_PER_INSTRUCTION: usize = 7;
+
+const POST_SIGNATURES_DISCRIMINATOR: [u8; 8] = [0x8a, 0x02, 0x35, 0xa6, 0x2d, 0x4d, 0x89, 0x33];
+const VERIFY_QUERY_DISCRIMINATOR: [u8; 8] = [0x16, 0xc8, 0xea, 0x50, 0x7c, 0xf0, 0x86, 0x53];
+const CLOSE_SIGNATURES_DISCRIMINATOR: [u8; 8] = [0xc0, 0x41, 0x3f, 0x75, 0xd5, 0x8a, 0xb3, 0xbe];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountMeta {
+    pub pubkey: [u8; 32],
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Instruction {
+    pub program_id: [u8; 32],
+    pub accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+}
+
+/// Seeds of the guardian set account with the given index, to derive its address
+/// against the core bridge program.
+pub fn guardian_set_seeds(index: u32) -> [Vec<u8>; 2] {
+    [GUARDIAN_SET_SEED.to_vec(), index.to_be_bytes().to_vec()]
+}
+
+/// Appends `signatures` to the signatures account, creating it on the first call.
+/// `guardian_signatures` must be a new keypair, signing along with `payer`.
+/// `total_signatures` is the number of signatures across all calls.
+pub fn post_signatures(
+    program_id: [u8; 32],
+    payer: [u8; 32],
+    guardian_signatures: [u8; 32],
+    signatures: &[[u8; 66]],
+    total_signatures: u8,
+) -> Instruction {
+    let mut data = POST_SIGNATURES_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&(signatures.len() as u32).to_le_bytes());
+    for signature in signatures {
+        data.extend_from_slice(signature);
+    }
+    data.push(total_signatures);
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::writable(payer, true),
+            AccountMeta::writable(guardian_signatures, true),
+            AccountMeta::readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
+
+/// Splits `signatures` into as many `post_signatures` instructions as needed, each to
+/// be sent in its own transaction, in order.
+pub fn post_signatures_chunked(
+    program_id: [u8; 32],
+    payer: [u8; 32],
+    guardian_signatures: [u8; 32],
+    signatures: &[[u8; 66]],
+) -> std::result::Result<Vec<Instruction>, std::io::Error> {
+    let total_signatures: u8 = signatures
+        .len()
+        .try_into()
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, "TooManySignatures"))?;
+    Ok(signatures
+        .chunks(MAX_SIGNATURES_PER_INSTRUCTION)
+        .map(|chunk| {
+            post_signatures(
+                program_id,
+                payer,
+                guardian_signatures,
+                chunk,
+                total_signatures,
+            )
+        })
+        .collect())
+}
+
+/// Verifies `response` (the serialized response) against the posted signatures and the
+/// guardian set account, then closes the signatures account to `refund_recipient`.
+pub fn verify_query(
+    program_id: [u8; 32],
+    guardian_set: [u8; 32],
+    guardian_set_index: u32,
+    guardian_signatures: [u8; 32],
+    refund_recipient: [u8; 32],
+    response: &[u8],
+) -> Instruction {
+    let mut data = VERIFY_QUERY_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&(response.len() as u32).to_le_bytes());
+    data.extend_from_slice(response);
+    data.extend_from_slice(&guardian_set_index.to_le_bytes());
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::readonly(guardian_set, false),
+            AccountMeta::writable(guardian_signatures, false),
+            AccountMeta::writable(refund_recipient, false),
+        ],
+        data,
+    }
+}
+
+/// Closes a signatures account without verifying, e.g. after a failed submission.
+/// `refund_recipient` must be the payer of the original `post_signatures`.
+pub fn close_signatures(
+    program_id: [u8; 32],
+    guardian_signatures: [u8; 32],
+    refund_recipient: [u8; 32],
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::writable(guardian_signatures, false),
+            AccountMeta::writable(refund_recipient, true),
+        ],
+        data: CLOSE_SIGNATURES_DISCRIMINATOR.to_vec(),
+    }
+}
+
+impl AccountMeta {
+    pub fn writable(pubkey: [u8; 32], is_signer: bool) -> AccountMeta {
+        AccountMeta {
+            pubkey,
+            is_signer,
+            is_writable: true,
+        }
+    }
+
+    pub fn readonly(pubkey: [u8; 32], is_signer: bool) -> AccountMeta {
+        AccountMeta {
+            pubkey,
+            is_signer,
+            is_writable: false,
+        }
+    }
+}
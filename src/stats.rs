@@ -0,0 +1,211 @@
+//! Per chain summary statistics over parsed responses, for monitoring services.
+//!
+//! [`ResponseStats::gather`] renders everything in the Prometheus text exposition format,
+//! ready to be served from a `/metrics` endpoint.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::chain::ChainId;
+#[cfg(feature = "experimental")]
+use crate::structs::NearAccountResult;
+use crate::structs::{ChainSpecificResponse, QueryResponse};
+
+const RESULT_BYTES_BUCKETS: &[f64] = &[32.0, 64.0, 256.0, 1024.0, 4096.0, 16384.0, 65536.0];
+const BLOCK_LAG_SECONDS_BUCKETS: &[f64] = &[1.0, 2.0, 5.0, 10.0, 30.0, 60.0, 300.0, 900.0];
+
+/// Cumulative histogram with fixed upper bounds.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Histogram {
+    bounds: &'static [f64],
+    /// Observations at or below each bound.
+    counts: Vec<u64>,
+    pub count: u64,
+    pub sum: f64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Histogram {
+        Histogram {
+            bounds,
+            counts: vec![0; bounds.len()],
+            count: 0,
+            sum: 0.0,
+        }
+    }
+
+    pub fn observe(&mut self, value: f64) {
+        for (bound, count) in self.bounds.iter().zip(&mut self.counts) {
+            if value <= *bound {
+                *count += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += value;
+    }
+
+    /// `(upper bound, cumulative count)` pairs, excluding the implicit `+Inf` bucket.
+    pub fn buckets(&self) -> impl Iterator<Item = (f64, u64)> + '_ {
+        self.bounds.iter().copied().zip(self.counts.iter().copied())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChainStats {
+    pub responses: u64,
+    pub results: u64,
+    /// Size of each call result or account data.
+    pub result_bytes: Histogram,
+    /// Seconds between the request time passed to [`ResponseStats::ingest`] and the
+    /// block time of the response.
+    pub block_lag_seconds: Histogram,
+}
+
+impl Default for ChainStats {
+    fn default() -> ChainStats {
+        ChainStats {
+            responses: 0,
+            results: 0,
+            result_bytes: Histogram::new(RESULT_BYTES_BUCKETS),
+            block_lag_seconds: Histogram::new(BLOCK_LAG_SECONDS_BUCKETS),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ResponseStats {
+    pub chains: BTreeMap<ChainId, ChainStats>,
+}
+
+impl ResponseStats {
+    pub fn new() -> ResponseStats {
+        ResponseStats::default()
+    }
+
+    /// Records every per chain response of `response`, for a request sent at
+    /// `requested_at`. Blocks newer than the request count as zero lag.
+    pub fn ingest(&mut self, response: &QueryResponse, requested_at: SystemTime) {
+        let requested_at = requested_at
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since_epoch| since_epoch.as_micros() as u64);
+        for per_chain in &response.responses {
+            let stats = self.chains.entry(per_chain.chain()).or_default();
+            stats.responses += 1;
+            let lag = requested_at.saturating_sub(per_chain.response.block_time());
+            stats.block_lag_seconds.observe(lag as f64 / 1_000_000.0);
+            let sizes: Vec<usize> = match &per_chain.response {
+                ChainSpecificResponse::EthCallQueryResponse(r) => {
+                    r.results.iter().map(Vec::len).collect()
+                }
+                ChainSpecificResponse::EthCallByTimestampQueryResponse(r) => {
+                    r.results.iter().map(Vec::len).collect()
+                }
+                ChainSpecificResponse::EthCallWithFinalityQueryResponse(r) => {
+                    r.results.iter().map(Vec::len).collect()
+                }
+                #[cfg(feature = "experimental")]
+                ChainSpecificResponse::EthCallWithOverridesQueryResponse(r) => {
+                    r.results.iter().map(Vec::len).collect()
+                }
+                #[cfg(feature = "experimental")]
+                ChainSpecificResponse::NearViewCallQueryResponse(r) => {
+                    r.results.iter().map(Vec::len).collect()
+                }
+                ChainSpecificResponse::SolanaAccountQueryResponse(r) => {
+                    r.results.iter().map(|result| result.data.len()).collect()
+                }
+                #[cfg(feature = "experimental")]
+                ChainSpecificResponse::NearViewAccountQueryResponse(r) => {
+                    vec![NearAccountResult::LEN; r.results.len()]
+                }
+            };
+            stats.results += sizes.len() as u64;
+            for size in sizes {
+                stats.result_bytes.observe(size as f64);
+            }
+        }
+    }
+
+    /// Renders all metrics in the Prometheus text format, labelled by numeric chain id.
+    pub fn gather(&self) -> String {
+        let mut out = String::new();
+        let _ = self.write_metrics(&mut out);
+        out
+    }
+
+    fn write_metrics(&self, out: &mut String) -> std::fmt::Result {
+        writeln!(out, "# HELP ccq_responses_total Per chain responses seen.")?;
+        writeln!(out, "# TYPE ccq_responses_total counter")?;
+        for (chain_id, stats) in &self.chains {
+            writeln!(
+                out,
+                "ccq_responses_total{{chain=\"{}\"}} {}",
+                chain_id.0, stats.responses
+            )?;
+        }
+        writeln!(
+            out,
+            "# HELP ccq_results_total Call results and accounts seen."
+        )?;
+        writeln!(out, "# TYPE ccq_results_total counter")?;
+        for (chain_id, stats) in &self.chains {
+            writeln!(
+                out,
+                "ccq_results_total{{chain=\"{}\"}} {}",
+                chain_id.0, stats.results
+            )?;
+        }
+        write_histogram(
+            out,
+            "ccq_result_bytes",
+            "Size of call results and account data.",
+            self.chains
+                .iter()
+                .map(|(chain_id, stats)| (*chain_id, &stats.result_bytes)),
+        )?;
+        write_histogram(
+            out,
+            "ccq_block_lag_seconds",
+            "Time between the request and the block read.",
+            self.chains
+                .iter()
+                .map(|(chain_id, stats)| (*chain_id, &stats.block_lag_seconds)),
+        )
+    }
+}
+
+fn write_histogram<'a>(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    histograms: impl Iterator<Item = (ChainId, &'a Histogram)>,
+) -> std::fmt::Result {
+    writeln!(out, "# HELP {} {}", name, help)?;
+    writeln!(out, "# TYPE {} histogram", name)?;
+    for (chain_id, histogram) in histograms {
+        for (bound, count) in histogram.buckets() {
+            writeln!(
+                out,
+                "{}_bucket{{chain=\"{}\",le=\"{}\"}} {}",
+                name, chain_id.0, bound, count
+            )?;
+        }
+        writeln!(
+            out,
+            "{}_bucket{{chain=\"{}\",le=\"+Inf\"}} {}",
+            name, chain_id.0, histogram.count
+        )?;
+        writeln!(
+            out,
+            "{}_sum{{chain=\"{}\"}} {}",
+            name, chain_id.0, histogram.sum
+        )?;
+        writeln!(
+            out,
+            "{}_count{{chain=\"{}\"}} {}",
+            name, chain_id.0, histogram.count
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,152 @@
+//! Length delimited streams of serialized [`QueryResponse`]s.
+//!
+//! Each record is a four byte big endian length followed by that many bytes of a
+//! serialized response. Records are parsed lazily, one at a time, so arbitrarily large
+//! archives can be replayed in constant memory.
+
+use byteorder::{BigEndian, WriteBytesExt};
+use std::io::{Read, Write};
+
+use crate::structs::QueryResponse;
+
+/// Appends one framed record holding `response` to `wtr`.
+pub fn write_framed<W: Write>(
+    wtr: &mut W,
+    response: &[u8],
+) -> std::result::Result<(), std::io::Error> {
+    let len: u32 = response
+        .len()
+        .try_into()
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, "RecordTooLong"))?;
+    wtr.write_u32::<BigEndian>(len)?;
+    wtr.write_all(response)
+}
+
+/// Decodes framed [`QueryResponse`] records from a reader.
+///
+/// A record which fails to parse is yielded as an error and the stream moves on to the
+/// next record. A truncated or unreadable frame ends the stream after its error.
+pub struct ResponseStream<R> {
+    rdr: R,
+    done: bool,
+}
+
+impl<R> ResponseStream<R> {
+    pub fn new(rdr: R) -> ResponseStream<R> {
+        ResponseStream { rdr, done: false }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.rdr
+    }
+
+    fn settle(
+        &mut self,
+        result: std::result::Result<Option<Vec<u8>>, std::io::Error>,
+    ) -> Option<std::result::Result<Vec<u8>, std::io::Error>> {
+        let record = result.transpose();
+        if !matches!(record, Some(Ok(_))) {
+            self.done = true;
+        }
+        record
+    }
+}
+
+impl<R: Read> ResponseStream<R> {
+    /// Returns the raw bytes of the next record, or `None` at a clean end of stream.
+    pub fn next_record(&mut self) -> Option<std::result::Result<Vec<u8>, std::io::Error>> {
+        if self.done {
+            return None;
+        }
+        let result = read_record(&mut self.rdr);
+        self.settle(result)
+    }
+}
+
+impl<R: Read> Iterator for ResponseStream<R> {
+    type Item = std::result::Result<QueryResponse, std::io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_record()
+            .map(|record| record.and_then(|bytes| QueryResponse::deserialize(&bytes)))
+    }
+}
+
+pub(crate) fn read_record<R: Read>(
+    rdr: &mut R,
+) -> std::result::Result<Option<Vec<u8>>, std::io::Error> {
+    let mut len_buf = [0u8; 4];
+    let mut filled = 0;
+    while filled < len_buf.len() {
+        match rdr.read(&mut len_buf[filled..]) {
+            Ok(0) if filled == 0 => return Ok(None),
+            Ok(0) => return Err(truncated_record()),
+            Ok(n) => filled += n,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    let len = u32::from_be_bytes(len_buf);
+
+    let mut record = Vec::new();
+    rdr.by_ref().take(len.into()).read_to_end(&mut record)?;
+    if record.len() != len as usize {
+        return Err(truncated_record());
+    }
+    Ok(Some(record))
+}
+
+fn truncated_record() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "TruncatedRecord")
+}
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncRead + Unpin> ResponseStream<R> {
+    /// Async counterpart of [`ResponseStream::next_record`].
+    pub async fn next_record_async(
+        &mut self,
+    ) -> Option<std::result::Result<Vec<u8>, std::io::Error>> {
+        if self.done {
+            return None;
+        }
+        let result = read_record_async(&mut self.rdr).await;
+        self.settle(result)
+    }
+
+    /// Async counterpart of [`Iterator::next`].
+    pub async fn next_async(
+        &mut self,
+    ) -> Option<std::result::Result<QueryResponse, std::io::Error>> {
+        self.next_record_async()
+            .await
+            .map(|record| record.and_then(|bytes| QueryResponse::deserialize(&bytes)))
+    }
+}
+
+#[cfg(feature = "tokio")]
+async fn read_record_async<R: tokio::io::AsyncRead + Unpin>(
+    rdr: &mut R,
+) -> std::result::Result<Option<Vec<u8>>, std::io::Error> {
+    use tokio::io::AsyncReadExt;
+
+    let mut len_buf = [0u8; 4];
+    let mut filled = 0;
+    while filled < len_buf.len() {
+        match rdr.read(&mut len_buf[filled..]).await? {
+            0 if filled == 0 => return Ok(None),
+            0 => return Err(truncated_record()),
+            n => filled += n,
+        }
+    }
+    let len = u32::from_be_bytes(len_buf);
+
+    let mut record = Vec::new();
+    (&mut *rdr)
+        .take(len.into())
+        .read_to_end(&mut record)
+        .await?;
+    if record.len() != len as usize {
+        return Err(truncated_record());
+    }
+    Ok(Some(record))
+}
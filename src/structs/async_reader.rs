@@ -0,0 +1,157 @@
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use super::{
+    ChainSpecificQuery, ChainSpecificResponse, PerChainQueryRequest, PerChainQueryResponse,
+    QueryRequest, QueryResponse,
+};
+
+// Every variable sized body on the wire is length prefixed, so the async readers only
+// walk the envelope and buffer one body at a time before handing it to the sync parser.
+
+/// Reads exactly `len` bytes without trusting `len` for the up front allocation.
+async fn read_body<R: AsyncRead + Unpin>(
+    rdr: &mut R,
+    len: u32,
+) -> std::result::Result<Vec<u8>, std::io::Error> {
+    let mut body = Vec::new();
+    (&mut *rdr).take(len.into()).read_to_end(&mut body).await?;
+    if body.len() != len as usize {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            "UnexpectedEof",
+        ));
+    }
+    Ok(body)
+}
+
+impl QueryRequest {
+    pub async fn deserialize_from_async_reader<R: AsyncRead + Unpin>(
+        rdr: &mut R,
+    ) -> std::result::Result<QueryRequest, std::io::Error> {
+        let version = rdr.read_u8().await?;
+        if version != Self::REQUEST_VERSION {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "VersionMismatch",
+            ));
+        }
+
+        let nonce = rdr.read_u32().await?;
+
+        let num_per_chain_queries = rdr.read_u8().await?;
+
+        // A valid query request has at least one per chain query
+        if num_per_chain_queries == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "ZeroQueries",
+            ));
+        }
+
+        let mut requests: Vec<PerChainQueryRequest> =
+            Vec::with_capacity(num_per_chain_queries.into());
+        for _idx in 0..num_per_chain_queries {
+            requests.push(PerChainQueryRequest::deserialize_from_async_reader(rdr).await?)
+        }
+
+        Ok(QueryRequest {
+            version,
+            nonce,
+            requests,
+        })
+    }
+}
+
+impl PerChainQueryRequest {
+    pub async fn deserialize_from_async_reader<R: AsyncRead + Unpin>(
+        rdr: &mut R,
+    ) -> std::result::Result<PerChainQueryRequest, std::io::Error> {
+        let chain_id = rdr.read_u16().await?;
+        let query_type = rdr.read_u8().await?;
+        let query_len = rdr.read_u32().await?;
+        let body = read_body(rdr, query_len).await?;
+
+        let mut body_rdr = std::io::Cursor::new(body.as_slice());
+        let query = ChainSpecificQuery::deserialize_from_reader(query_type, &mut body_rdr)?;
+        if body_rdr.position() != body.len() as u64 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "InvalidQueryLength",
+            ));
+        }
+
+        Ok(PerChainQueryRequest { chain_id, query })
+    }
+}
+
+impl QueryResponse {
+    /// Unlike [`QueryResponse::deserialize`], this stops after the last per chain response
+    /// and leaves any further bytes in `rdr` unread.
+    pub async fn deserialize_from_async_reader<R: AsyncRead + Unpin>(
+        rdr: &mut R,
+    ) -> std::result::Result<QueryResponse, std::io::Error> {
+        let version = rdr.read_u8().await?;
+        if version != Self::RESPONSE_VERSION {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "InvalidResponseVersion",
+            ));
+        }
+
+        // For off chain requests (chainID zero), the requestId is the 65 byte signature. For on chain requests, it is the 32 byte VAA hash.
+        let request_chain_id = rdr.read_u16().await?;
+        let request_id_len = if request_chain_id == 0 { 65 } else { 32 };
+        let mut request_id = vec![0u8; request_id_len];
+        rdr.read_exact(&mut request_id).await?;
+
+        let request_len = rdr.read_u32().await?;
+        let body = read_body(rdr, request_len).await?;
+
+        let mut body_rdr = std::io::Cursor::new(body.as_slice());
+        let request = QueryRequest::deserialize_from_reader(&mut body_rdr)?;
+        if body_rdr.position() != body.len() as u64 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "InvalidRequestLength",
+            ));
+        }
+
+        let num_per_chain_responses = rdr.read_u8().await?;
+
+        let mut responses: Vec<PerChainQueryResponse> =
+            Vec::with_capacity(num_per_chain_responses.into());
+        for _idx in 0..num_per_chain_responses {
+            responses.push(PerChainQueryResponse::deserialize_from_async_reader(rdr).await?)
+        }
+
+        Ok(QueryResponse {
+            version,
+            request_chain_id,
+            request_id,
+            request,
+            responses,
+        })
+    }
+}
+
+impl PerChainQueryResponse {
+    pub async fn deserialize_from_async_reader<R: AsyncRead + Unpin>(
+        rdr: &mut R,
+    ) -> std::result::Result<PerChainQueryResponse, std::io::Error> {
+        let chain_id = rdr.read_u16().await?;
+        let query_type = rdr.read_u8().await?;
+        let response_len = rdr.read_u32().await?;
+        let body = read_body(rdr, response_len).await?;
+
+        let mut body_rdr = std::io::Cursor::new(body.as_slice());
+        let response = ChainSpecificResponse::deserialize_from_reader(query_type, &mut body_rdr)?;
+        if body_rdr.position() != body.len() as u64 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "InvalidResponseLength",
+            ));
+        }
+
+        Ok(PerChainQueryResponse { chain_id, response })
+    }
+}
@@ -0,0 +1,205 @@
+use std::str::FromStr;
+
+use super::{
+    EthCallByTimestampQueryRequest, EthCallByTimestampQueryResponse, EthCallQueryRequest,
+    EthCallQueryResponse, EthCallWithFinalityQueryRequest, EthCallWithFinalityQueryResponse,
+    PerChainQueryRequest, PerChainQueryResponse, QueryRequest, QueryResponse, QueryResponseLite,
+    SolanaAccountQueryRequest, SolanaAccountQueryResponse,
+};
+#[cfg(feature = "experimental")]
+use super::{
+    EthCallWithOverridesQueryRequest, EthCallWithOverridesQueryResponse,
+    NearViewAccountQueryRequest, NearViewAccountQueryResponse, NearViewCallQueryRequest,
+    NearViewCallQueryResponse,
+};
+
+// The chain specific enums are not covered, as their layout depends on a query type byte
+// which is not part of their own encoding.
+
+impl TryFrom<&[u8]> for QueryRequest {
+    type Error = std::io::Error;
+
+    fn try_from(data: &[u8]) -> std::result::Result<QueryRequest, std::io::Error> {
+        QueryRequest::deserialize(data)
+    }
+}
+
+impl TryFrom<&[u8]> for PerChainQueryRequest {
+    type Error = std::io::Error;
+
+    fn try_from(data: &[u8]) -> std::result::Result<PerChainQueryRequest, std::io::Error> {
+        PerChainQueryRequest::deserialize(data)
+    }
+}
+
+impl TryFrom<&[u8]> for EthCallQueryRequest {
+    type Error = std::io::Error;
+
+    fn try_from(data: &[u8]) -> std::result::Result<EthCallQueryRequest, std::io::Error> {
+        EthCallQueryRequest::deserialize(data)
+    }
+}
+
+impl TryFrom<&[u8]> for EthCallByTimestampQueryRequest {
+    type Error = std::io::Error;
+
+    fn try_from(
+        data: &[u8],
+    ) -> std::result::Result<EthCallByTimestampQueryRequest, std::io::Error> {
+        EthCallByTimestampQueryRequest::deserialize(data)
+    }
+}
+
+impl TryFrom<&[u8]> for EthCallWithFinalityQueryRequest {
+    type Error = std::io::Error;
+
+    fn try_from(
+        data: &[u8],
+    ) -> std::result::Result<EthCallWithFinalityQueryRequest, std::io::Error> {
+        EthCallWithFinalityQueryRequest::deserialize(data)
+    }
+}
+
+impl TryFrom<&[u8]> for SolanaAccountQueryRequest {
+    type Error = std::io::Error;
+
+    fn try_from(data: &[u8]) -> std::result::Result<SolanaAccountQueryRequest, std::io::Error> {
+        SolanaAccountQueryRequest::deserialize(data)
+    }
+}
+
+#[cfg(feature = "experimental")]
+impl TryFrom<&[u8]> for EthCallWithOverridesQueryRequest {
+    type Error = std::io::Error;
+
+    fn try_from(
+        data: &[u8],
+    ) -> std::result::Result<EthCallWithOverridesQueryRequest, std::io::Error> {
+        EthCallWithOverridesQueryRequest::deserialize(data)
+    }
+}
+
+#[cfg(feature = "experimental")]
+impl TryFrom<&[u8]> for NearViewCallQueryRequest {
+    type Error = std::io::Error;
+
+    fn try_from(data: &[u8]) -> std::result::Result<NearViewCallQueryRequest, std::io::Error> {
+        NearViewCallQueryRequest::deserialize(data)
+    }
+}
+
+#[cfg(feature = "experimental")]
+impl TryFrom<&[u8]> for NearViewAccountQueryRequest {
+    type Error = std::io::Error;
+
+    fn try_from(data: &[u8]) -> std::result::Result<NearViewAccountQueryRequest, std::io::Error> {
+        NearViewAccountQueryRequest::deserialize(data)
+    }
+}
+
+impl TryFrom<&[u8]> for QueryResponse {
+    type Error = std::io::Error;
+
+    fn try_from(data: &[u8]) -> std::result::Result<QueryResponse, std::io::Error> {
+        QueryResponse::deserialize(data)
+    }
+}
+
+impl TryFrom<&[u8]> for QueryResponseLite {
+    type Error = std::io::Error;
+
+    fn try_from(data: &[u8]) -> std::result::Result<QueryResponseLite, std::io::Error> {
+        QueryResponseLite::deserialize(data)
+    }
+}
+
+impl TryFrom<&[u8]> for PerChainQueryResponse {
+    type Error = std::io::Error;
+
+    fn try_from(data: &[u8]) -> std::result::Result<PerChainQueryResponse, std::io::Error> {
+        PerChainQueryResponse::deserialize(data)
+    }
+}
+
+impl TryFrom<&[u8]> for EthCallQueryResponse {
+    type Error = std::io::Error;
+
+    fn try_from(data: &[u8]) -> std::result::Result<EthCallQueryResponse, std::io::Error> {
+        EthCallQueryResponse::deserialize(data)
+    }
+}
+
+impl TryFrom<&[u8]> for EthCallByTimestampQueryResponse {
+    type Error = std::io::Error;
+
+    fn try_from(
+        data: &[u8],
+    ) -> std::result::Result<EthCallByTimestampQueryResponse, std::io::Error> {
+        EthCallByTimestampQueryResponse::deserialize(data)
+    }
+}
+
+impl TryFrom<&[u8]> for EthCallWithFinalityQueryResponse {
+    type Error = std::io::Error;
+
+    fn try_from(
+        data: &[u8],
+    ) -> std::result::Result<EthCallWithFinalityQueryResponse, std::io::Error> {
+        EthCallWithFinalityQueryResponse::deserialize(data)
+    }
+}
+
+impl TryFrom<&[u8]> for SolanaAccountQueryResponse {
+    type Error = std::io::Error;
+
+    fn try_from(data: &[u8]) -> std::result::Result<SolanaAccountQueryResponse, std::io::Error> {
+        SolanaAccountQueryResponse::deserialize(data)
+    }
+}
+
+#[cfg(feature = "experimental")]
+impl TryFrom<&[u8]> for EthCallWithOverridesQueryResponse {
+    type Error = std::io::Error;
+
+    fn try_from(
+        data: &[u8],
+    ) -> std::result::Result<EthCallWithOverridesQueryResponse, std::io::Error> {
+        EthCallWithOverridesQueryResponse::deserialize(data)
+    }
+}
+
+#[cfg(feature = "experimental")]
+impl TryFrom<&[u8]> for NearViewCallQueryResponse {
+    type Error = std::io::Error;
+
+    fn try_from(data: &[u8]) -> std::result::Result<NearViewCallQueryResponse, std::io::Error> {
+        NearViewCallQueryResponse::deserialize(data)
+    }
+}
+
+#[cfg(feature = "experimental")]
+impl TryFrom<&[u8]> for NearViewAccountQueryResponse {
+    type Error = std::io::Error;
+
+    fn try_from(data: &[u8]) -> std::result::Result<NearViewAccountQueryResponse, std::io::Error> {
+        NearViewAccountQueryResponse::deserialize(data)
+    }
+}
+
+/// Parses hex, as produced by [`QueryRequest::to_hex`].
+impl FromStr for QueryRequest {
+    type Err = std::io::Error;
+
+    fn from_str(s: &str) -> std::result::Result<QueryRequest, std::io::Error> {
+        QueryRequest::from_hex(s)
+    }
+}
+
+/// Parses hex, as produced by [`QueryResponse::to_hex`].
+impl FromStr for QueryResponse {
+    type Err = std::io::Error;
+
+    fn from_str(s: &str) -> std::result::Result<QueryResponse, std::io::Error> {
+        QueryResponse::from_hex(s)
+    }
+}
@@ -0,0 +1,130 @@
+use byteorder::ReadBytesExt;
+use std::io::Cursor;
+
+use super::{QueryRequest, QueryResponse};
+
+/// Bounds on what [`QueryRequest::deserialize_with_limits`] and
+/// [`QueryResponse::deserialize_with_limits`] accept, for services and programs which
+/// cannot afford everything the wire format allows.
+///
+/// `max_len` is checked before parsing and `max_entries` while parsing, before the
+/// entries of each list are allocated. Byte fields are bounded by the input itself, as
+/// a length prefix longer than the remaining input fails before anything is allocated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeserializeLimits {
+    /// Largest input, in bytes.
+    pub max_len: usize,
+    /// Most list entries across all lists, counting the embedded request of a response.
+    pub max_entries: usize,
+}
+
+/// One MiB of input and 4096 entries.
+impl Default for DeserializeLimits {
+    fn default() -> DeserializeLimits {
+        DeserializeLimits {
+            max_len: 1 << 20,
+            max_entries: 4096,
+        }
+    }
+}
+
+impl DeserializeLimits {
+    fn check_len(&self, len: usize) -> std::result::Result<(), std::io::Error> {
+        if len > self.max_len {
+            return Err(limit_exceeded("PayloadTooLong"));
+        }
+        Ok(())
+    }
+}
+
+/// Number of list entries a parse may still read. Every reader takes one, so the limit
+/// applies however a value is nested.
+pub(crate) struct Budget {
+    entries_left: usize,
+}
+
+impl Budget {
+    pub(crate) fn unlimited() -> Budget {
+        Budget {
+            entries_left: usize::MAX,
+        }
+    }
+
+    fn new(limits: &DeserializeLimits) -> Budget {
+        Budget {
+            entries_left: limits.max_entries,
+        }
+    }
+
+    fn take(&mut self, count: usize) -> std::result::Result<(), std::io::Error> {
+        self.entries_left = self
+            .entries_left
+            .checked_sub(count)
+            .ok_or_else(|| limit_exceeded("TooManyEntries"))?;
+        Ok(())
+    }
+
+    /// Reads a one byte list count, failing if the budget cannot cover it.
+    pub(crate) fn read_count(
+        &mut self,
+        rdr: &mut Cursor<&[u8]>,
+    ) -> std::result::Result<u8, std::io::Error> {
+        let count = rdr.read_u8()?;
+        self.take(count.into())?;
+        Ok(count)
+    }
+}
+
+fn limit_exceeded(err: &'static str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+}
+
+impl QueryRequest {
+    /// Like [`QueryRequest::deserialize`], but fails as soon as `data` or the request
+    /// being parsed exceeds `limits`.
+    pub fn deserialize_with_limits(
+        data: &[u8],
+        limits: &DeserializeLimits,
+    ) -> std::result::Result<QueryRequest, std::io::Error> {
+        limits.check_len(data.len())?;
+        Self::read_from(&mut Cursor::new(data), &mut Budget::new(limits))
+    }
+
+    /// Checks the serialized length and entry count of an already built request against
+    /// `limits`.
+    pub fn check_limits(
+        &self,
+        limits: &DeserializeLimits,
+    ) -> std::result::Result<(), std::io::Error> {
+        Self::deserialize_with_limits(&self.serialize()?, limits).map(|_| ())
+    }
+}
+
+impl QueryResponse {
+    /// Like [`QueryResponse::deserialize`], but fails as soon as `data` or the response
+    /// being parsed exceeds `limits`.
+    pub fn deserialize_with_limits(
+        data: &[u8],
+        limits: &DeserializeLimits,
+    ) -> std::result::Result<QueryResponse, std::io::Error> {
+        limits.check_len(data.len())?;
+        let mut rdr = Cursor::new(data);
+        let response = Self::read_from(&mut rdr, &mut Budget::new(limits))?;
+        if rdr.position() != data.len() as u64 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "InvalidPayloadLength",
+            ));
+        }
+        Ok(response)
+    }
+
+    /// Checks the serialized length and entry count of an already built response against
+    /// `limits`, including those of the embedded request.
+    pub fn check_limits(
+        &self,
+        limits: &DeserializeLimits,
+    ) -> std::result::Result<(), std::io::Error> {
+        Self::deserialize_with_limits(&self.serialize()?, limits).map(|_| ())
+    }
+}
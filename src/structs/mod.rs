@@ -3,3 +3,14 @@ pub use query_request::*;
 
 mod query_response;
 pub use query_response::*;
+
+mod convert;
+
+mod limits;
+pub use limits::DeserializeLimits;
+
+pub(crate) mod wire;
+pub use wire::TrailingBytes;
+
+#[cfg(feature = "tokio")]
+mod async_reader;
@@ -1,6 +1,17 @@
-use byteorder::{BigEndian, ReadBytesExt};
-use std::io::{Cursor, Read};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::io::{Cursor, Read, Write};
 
+use crate::chain::ChainId;
+
+use super::limits::Budget;
+use super::wire::{
+    check_body_len, check_string_field, decode_hex, read_bytes, write_bytes_u32, write_u8_len,
+    TrailingBytes,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct QueryRequest {
     pub version: u8,
     pub nonce: u32,
@@ -15,8 +26,34 @@ impl QueryRequest {
         Self::deserialize_from_reader(&mut rdr)
     }
 
+    /// Like [`QueryRequest::deserialize`], but also returns the number of bytes consumed,
+    /// so a payload embedded in a larger buffer can be parsed in place.
+    pub fn deserialize_with_len(
+        data: &[u8],
+    ) -> std::result::Result<(QueryRequest, usize), std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        let value = Self::deserialize_from_reader(&mut rdr)?;
+        Ok((value, rdr.position() as usize))
+    }
+
+    /// Parses `data` under the given trailing bytes policy, also returning the number of
+    /// bytes consumed.
+    pub fn deserialize_with(
+        data: &[u8],
+        trailing: TrailingBytes,
+    ) -> std::result::Result<(QueryRequest, usize), std::io::Error> {
+        trailing.check(Self::deserialize_with_len(data), data.len())
+    }
+
     pub fn deserialize_from_reader(
         rdr: &mut Cursor<&[u8]>,
+    ) -> std::result::Result<QueryRequest, std::io::Error> {
+        Self::read_from(rdr, &mut Budget::unlimited())
+    }
+
+    pub(crate) fn read_from(
+        rdr: &mut Cursor<&[u8]>,
+        budget: &mut Budget,
     ) -> std::result::Result<QueryRequest, std::io::Error> {
         let version = rdr.read_u8()?;
         if version != Self::REQUEST_VERSION {
@@ -28,7 +65,7 @@ impl QueryRequest {
 
         let nonce = rdr.read_u32::<BigEndian>()?;
 
-        let num_per_chain_queries = rdr.read_u8()?;
+        let num_per_chain_queries = budget.read_count(rdr)?;
 
         // A valid query request has at least one per chain query
         if num_per_chain_queries == 0 {
@@ -41,7 +78,7 @@ impl QueryRequest {
         let mut requests: Vec<PerChainQueryRequest> =
             Vec::with_capacity(num_per_chain_queries.into());
         for _idx in 0..num_per_chain_queries {
-            requests.push(PerChainQueryRequest::deserialize_from_reader(rdr)?)
+            requests.push(PerChainQueryRequest::read_from(rdr, budget)?)
         }
 
         Ok(QueryRequest {
@@ -50,42 +87,263 @@ impl QueryRequest {
             requests,
         })
     }
+
+    pub fn serialize(&self) -> std::result::Result<Vec<u8>, std::io::Error> {
+        let mut wtr = Vec::new();
+        self.serialize_to_writer(&mut wtr)?;
+        Ok(wtr)
+    }
+
+    pub fn serialize_to_writer<W: Write>(
+        &self,
+        wtr: &mut W,
+    ) -> std::result::Result<(), std::io::Error> {
+        wtr.write_u8(self.version)?;
+        wtr.write_u32::<BigEndian>(self.nonce)?;
+        write_u8_len(wtr, self.requests.len())?;
+        for request in &self.requests {
+            request.serialize_to_writer(wtr)?;
+        }
+        Ok(())
+    }
+
+    /// Like [`QueryRequest::deserialize`], but also runs [`QueryRequest::validate`].
+    pub fn deserialize_strict(data: &[u8]) -> std::result::Result<QueryRequest, std::io::Error> {
+        let request = Self::deserialize(data)?;
+        request.validate()?;
+        Ok(request)
+    }
+
+    /// Parses `data` and checks that re-serializing it gives back the same bytes, catching
+    /// encodings which parse to the same request but hash differently.
+    pub fn verify_canonical(data: &[u8]) -> std::result::Result<QueryRequest, std::io::Error> {
+        let value = Self::deserialize(data)?;
+        if value.serialize()? != data {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "NonCanonicalEncoding",
+            ));
+        }
+        Ok(value)
+    }
+
+    /// Checks the semantic constraints guardians enforce beyond the wire format.
+    pub fn validate(&self) -> std::result::Result<(), std::io::Error> {
+        for request in &self.requests {
+            request.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Parses a hex encoded request. A leading `0x` is optional.
+    pub fn from_hex(s: &str) -> std::result::Result<QueryRequest, std::io::Error> {
+        Self::deserialize(&decode_hex(s)?)
+    }
+
+    /// Serializes the request as unprefixed lowercase hex, as used by the query proxy.
+    pub fn to_hex(&self) -> std::result::Result<String, std::io::Error> {
+        Ok(hex::encode(self.serialize()?))
+    }
+
+    /// The chains with a per chain request, in order of first appearance.
+    pub fn chain_ids(&self) -> Vec<ChainId> {
+        let mut chain_ids = Vec::new();
+        for per_chain in &self.requests {
+            if !chain_ids.contains(&per_chain.chain()) {
+                chain_ids.push(per_chain.chain());
+            }
+        }
+        chain_ids
+    }
+
+    pub fn contains_chain(&self, chain: ChainId) -> bool {
+        self.requests
+            .iter()
+            .any(|per_chain| per_chain.chain() == chain)
+    }
+
+    /// Keccak256 of the request serialized with a zero nonce. Requests which differ only
+    /// in their nonce, such as retries, share a canonical hash.
+    #[cfg(feature = "crypto")]
+    pub fn canonical_hash(&self) -> std::result::Result<[u8; 32], std::io::Error> {
+        let mut bytes = self.serialize()?;
+        // The nonce follows the one byte version.
+        bytes[1..5].fill(0);
+        Ok(crate::signature::keccak256(&bytes))
+    }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct PerChainQueryRequest {
     pub chain_id: u16,
     pub query: ChainSpecificQuery,
 }
 
 impl PerChainQueryRequest {
+    /// The query type and length prefix are derived from `query` when serializing.
+    pub fn new(chain_id: u16, query: ChainSpecificQuery) -> PerChainQueryRequest {
+        PerChainQueryRequest { chain_id, query }
+    }
+
+    pub fn chain(&self) -> ChainId {
+        ChainId(self.chain_id)
+    }
+
     pub fn deserialize(data: &[u8]) -> std::result::Result<PerChainQueryRequest, std::io::Error> {
         let mut rdr = Cursor::new(data);
         Self::deserialize_from_reader(&mut rdr)
     }
 
+    /// Also returns the number of bytes consumed.
+    pub fn deserialize_with_len(
+        data: &[u8],
+    ) -> std::result::Result<(PerChainQueryRequest, usize), std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        let value = Self::deserialize_from_reader(&mut rdr)?;
+        Ok((value, rdr.position() as usize))
+    }
+
+    /// Parses `data` under the given trailing bytes policy, also returning the number of
+    /// bytes consumed.
+    pub fn deserialize_with(
+        data: &[u8],
+        trailing: TrailingBytes,
+    ) -> std::result::Result<(PerChainQueryRequest, usize), std::io::Error> {
+        trailing.check(Self::deserialize_with_len(data), data.len())
+    }
+
     pub fn deserialize_from_reader(
         rdr: &mut Cursor<&[u8]>,
+    ) -> std::result::Result<PerChainQueryRequest, std::io::Error> {
+        Self::read_from(rdr, &mut Budget::unlimited())
+    }
+
+    pub(crate) fn read_from(
+        rdr: &mut Cursor<&[u8]>,
+        budget: &mut Budget,
     ) -> std::result::Result<PerChainQueryRequest, std::io::Error> {
         let chain_id = rdr.read_u16::<BigEndian>()?;
         let query_type = rdr.read_u8()?;
-        rdr.read_u32::<BigEndian>()?; // skip the query length
+        let query_len = rdr.read_u32::<BigEndian>()?;
+        let start = rdr.position();
+        let query = ChainSpecificQuery::read_from(query_type, rdr, budget)?;
+        check_body_len(rdr, start, query_len, "InvalidQueryLength")?;
+
+        Ok(PerChainQueryRequest { chain_id, query })
+    }
+
+    /// Like [`PerChainQueryRequest::deserialize`], but also runs
+    /// [`PerChainQueryRequest::validate`].
+    pub fn deserialize_strict(
+        data: &[u8],
+    ) -> std::result::Result<PerChainQueryRequest, std::io::Error> {
+        let request = Self::deserialize(data)?;
+        request.validate()?;
+        Ok(request)
+    }
+
+    pub fn validate(&self) -> std::result::Result<(), std::io::Error> {
+        self.query.validate()
+    }
+
+    pub fn serialize(&self) -> std::result::Result<Vec<u8>, std::io::Error> {
+        let mut wtr = Vec::new();
+        self.serialize_to_writer(&mut wtr)?;
+        Ok(wtr)
+    }
+
+    pub fn serialize_to_writer<W: Write>(
+        &self,
+        wtr: &mut W,
+    ) -> std::result::Result<(), std::io::Error> {
+        wtr.write_u16::<BigEndian>(self.chain_id)?;
+        wtr.write_u8(self.query.query_type())?;
+        write_bytes_u32(wtr, &self.query.serialize()?)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum ChainSpecificQuery {
+    EthCallQueryRequest(EthCallQueryRequest),
+    EthCallByTimestampQueryRequest(EthCallByTimestampQueryRequest),
+    EthCallWithFinalityQueryRequest(EthCallWithFinalityQueryRequest),
+    SolanaAccountQueryRequest(SolanaAccountQueryRequest),
+    #[cfg(feature = "experimental")]
+    EthCallWithOverridesQueryRequest(EthCallWithOverridesQueryRequest),
+    #[cfg(feature = "experimental")]
+    NearViewCallQueryRequest(NearViewCallQueryRequest),
+    #[cfg(feature = "experimental")]
+    NearViewAccountQueryRequest(NearViewAccountQueryRequest),
+}
+
+impl ChainSpecificQuery {
+    pub fn deserialize(
+        query_type: u8,
+        data: &[u8],
+    ) -> std::result::Result<ChainSpecificQuery, std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        Self::deserialize_from_reader(query_type, &mut rdr)
+    }
+
+    /// Also returns the number of bytes consumed.
+    pub fn deserialize_with_len(
+        query_type: u8,
+        data: &[u8],
+    ) -> std::result::Result<(ChainSpecificQuery, usize), std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        let value = Self::deserialize_from_reader(query_type, &mut rdr)?;
+        Ok((value, rdr.position() as usize))
+    }
+
+    /// Parses the body of a per chain query, whose layout is selected by `query_type`.
+    pub fn deserialize_from_reader(
+        query_type: u8,
+        rdr: &mut Cursor<&[u8]>,
+    ) -> std::result::Result<ChainSpecificQuery, std::io::Error> {
+        Self::read_from(query_type, rdr, &mut Budget::unlimited())
+    }
+
+    pub(crate) fn read_from(
+        query_type: u8,
+        rdr: &mut Cursor<&[u8]>,
+        budget: &mut Budget,
+    ) -> std::result::Result<ChainSpecificQuery, std::io::Error> {
+        // Types this crate assigned itself, which the guardian does not serve.
+        #[cfg(feature = "experimental")]
+        if query_type == 6 {
+            return Ok(ChainSpecificQuery::EthCallWithOverridesQueryRequest(
+                EthCallWithOverridesQueryRequest::read_from(rdr, budget)?,
+            ));
+        } else if query_type == 7 {
+            return Ok(ChainSpecificQuery::NearViewCallQueryRequest(
+                NearViewCallQueryRequest::read_from(rdr, budget)?,
+            ));
+        } else if query_type == 8 {
+            return Ok(ChainSpecificQuery::NearViewAccountQueryRequest(
+                NearViewAccountQueryRequest::read_from(rdr, budget)?,
+            ));
+        }
 
         let query: ChainSpecificQuery;
         if query_type == 1 {
-            query = ChainSpecificQuery::EthCallQueryRequest(
-                EthCallQueryRequest::deserialize_from_reader(rdr)?,
-            );
+            query = ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest::read_from(
+                rdr, budget,
+            )?);
         } else if query_type == 2 {
             query = ChainSpecificQuery::EthCallByTimestampQueryRequest(
-                EthCallByTimestampQueryRequest::deserialize_from_reader(rdr)?,
+                EthCallByTimestampQueryRequest::read_from(rdr, budget)?,
             );
         } else if query_type == 3 {
             query = ChainSpecificQuery::EthCallWithFinalityQueryRequest(
-                EthCallWithFinalityQueryRequest::deserialize_from_reader(rdr)?,
+                EthCallWithFinalityQueryRequest::read_from(rdr, budget)?,
             );
         } else if query_type == 4 {
             query = ChainSpecificQuery::SolanaAccountQueryRequest(
-                SolanaAccountQueryRequest::deserialize_from_reader(rdr)?,
+                SolanaAccountQueryRequest::read_from(rdr, budget)?,
             );
         } else {
             return Err(std::io::Error::new(
@@ -94,49 +352,136 @@ impl PerChainQueryRequest {
             ));
         }
 
-        Ok(PerChainQueryRequest { chain_id, query })
+        Ok(query)
     }
-}
 
-pub enum ChainSpecificQuery {
-    EthCallQueryRequest(EthCallQueryRequest),
-    EthCallByTimestampQueryRequest(EthCallByTimestampQueryRequest),
-    EthCallWithFinalityQueryRequest(EthCallWithFinalityQueryRequest),
-    SolanaAccountQueryRequest(SolanaAccountQueryRequest),
+    pub fn validate(&self) -> std::result::Result<(), std::io::Error> {
+        match self {
+            ChainSpecificQuery::EthCallQueryRequest(query) => query.validate(),
+            ChainSpecificQuery::EthCallByTimestampQueryRequest(query) => query.validate(),
+            ChainSpecificQuery::EthCallWithFinalityQueryRequest(query) => query.validate(),
+            ChainSpecificQuery::SolanaAccountQueryRequest(query) => query.validate(),
+            #[cfg(feature = "experimental")]
+            ChainSpecificQuery::EthCallWithOverridesQueryRequest(query) => query.validate(),
+            #[cfg(feature = "experimental")]
+            ChainSpecificQuery::NearViewCallQueryRequest(query) => query.validate(),
+            #[cfg(feature = "experimental")]
+            ChainSpecificQuery::NearViewAccountQueryRequest(query) => query.validate(),
+        }
+    }
+
+    /// The query type byte used on the wire.
+    pub fn query_type(&self) -> u8 {
+        // 1 to 5 are the types of the guardian's query package (node/pkg/query). 5 is
+        // sol_pda, which this crate does not support. eth_call_with_overrides (6),
+        // near_view_call (7) and near_view_account (8) have no upstream id yet; they take
+        // the next free ids and must be renumbered if the guardian assigns different ones.
+        match self {
+            ChainSpecificQuery::EthCallQueryRequest(_) => 1,
+            ChainSpecificQuery::EthCallByTimestampQueryRequest(_) => 2,
+            ChainSpecificQuery::EthCallWithFinalityQueryRequest(_) => 3,
+            ChainSpecificQuery::SolanaAccountQueryRequest(_) => 4,
+            #[cfg(feature = "experimental")]
+            ChainSpecificQuery::EthCallWithOverridesQueryRequest(_) => 6,
+            #[cfg(feature = "experimental")]
+            ChainSpecificQuery::NearViewCallQueryRequest(_) => 7,
+            #[cfg(feature = "experimental")]
+            ChainSpecificQuery::NearViewAccountQueryRequest(_) => 8,
+        }
+    }
+
+    pub fn serialize(&self) -> std::result::Result<Vec<u8>, std::io::Error> {
+        match self {
+            ChainSpecificQuery::EthCallQueryRequest(query) => query.serialize(),
+            ChainSpecificQuery::EthCallByTimestampQueryRequest(query) => query.serialize(),
+            ChainSpecificQuery::EthCallWithFinalityQueryRequest(query) => query.serialize(),
+            ChainSpecificQuery::SolanaAccountQueryRequest(query) => query.serialize(),
+            #[cfg(feature = "experimental")]
+            ChainSpecificQuery::EthCallWithOverridesQueryRequest(query) => query.serialize(),
+            #[cfg(feature = "experimental")]
+            ChainSpecificQuery::NearViewCallQueryRequest(query) => query.serialize(),
+            #[cfg(feature = "experimental")]
+            ChainSpecificQuery::NearViewAccountQueryRequest(query) => query.serialize(),
+        }
+    }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct EthCallQueryRequest {
     pub block_tag: String,
     pub call_data: Vec<EthCallData>,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct EthCallData {
     pub to: [u8; 20],
     pub data: Vec<u8>,
 }
 
+impl EthCallData {
+    pub fn serialize_to_writer<W: Write>(
+        &self,
+        wtr: &mut W,
+    ) -> std::result::Result<(), std::io::Error> {
+        wtr.write_all(&self.to)?;
+        write_bytes_u32(wtr, &self.data)
+    }
+}
+
 impl EthCallQueryRequest {
     pub fn deserialize(data: &[u8]) -> std::result::Result<EthCallQueryRequest, std::io::Error> {
         let mut rdr = Cursor::new(data);
         Self::deserialize_from_reader(&mut rdr)
     }
 
+    /// Also returns the number of bytes consumed.
+    pub fn deserialize_with_len(
+        data: &[u8],
+    ) -> std::result::Result<(EthCallQueryRequest, usize), std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        let value = Self::deserialize_from_reader(&mut rdr)?;
+        Ok((value, rdr.position() as usize))
+    }
+
+    /// Like [`EthCallQueryRequest::deserialize`], but also runs [`EthCallQueryRequest::validate`].
+    pub fn deserialize_strict(
+        data: &[u8],
+    ) -> std::result::Result<EthCallQueryRequest, std::io::Error> {
+        let request = Self::deserialize(data)?;
+        request.validate()?;
+        Ok(request)
+    }
+
+    /// Rejects block tags containing non-ASCII characters or NUL bytes.
+    pub fn validate(&self) -> std::result::Result<(), std::io::Error> {
+        check_string_field(&self.block_tag, "InvalidBlockTag")
+    }
+
     pub fn deserialize_from_reader(
         rdr: &mut Cursor<&[u8]>,
+    ) -> std::result::Result<EthCallQueryRequest, std::io::Error> {
+        Self::read_from(rdr, &mut Budget::unlimited())
+    }
+
+    pub(crate) fn read_from(
+        rdr: &mut Cursor<&[u8]>,
+        budget: &mut Budget,
     ) -> std::result::Result<EthCallQueryRequest, std::io::Error> {
         let block_tag_len = rdr.read_u32::<BigEndian>()?;
-        let mut buf = vec![0u8; block_tag_len.try_into().unwrap()];
-        rdr.read_exact(&mut buf)?;
+        let buf = read_bytes(rdr, block_tag_len)?;
         let block_tag = String::from_utf8(buf.clone())
             .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "InvalidBlockTag"))?;
-        let call_data_len = rdr.read_u8()?;
+        let call_data_len = budget.read_count(rdr)?;
         let mut call_data = Vec::with_capacity(call_data_len.into());
         for _ in 0..call_data_len {
             let mut to = [0u8; 20];
             rdr.read_exact(&mut to)?;
             let data_len = rdr.read_u32::<BigEndian>()?;
-            let mut data = vec![0u8; data_len.try_into().unwrap()];
-            rdr.read_exact(&mut data)?;
+            let data = read_bytes(rdr, data_len)?;
             call_data.push(EthCallData { to, data })
         }
         Ok(EthCallQueryRequest {
@@ -144,8 +489,29 @@ impl EthCallQueryRequest {
             call_data,
         })
     }
+
+    pub fn serialize(&self) -> std::result::Result<Vec<u8>, std::io::Error> {
+        let mut wtr = Vec::new();
+        self.serialize_to_writer(&mut wtr)?;
+        Ok(wtr)
+    }
+
+    pub fn serialize_to_writer<W: Write>(
+        &self,
+        wtr: &mut W,
+    ) -> std::result::Result<(), std::io::Error> {
+        write_bytes_u32(wtr, self.block_tag.as_bytes())?;
+        write_u8_len(wtr, self.call_data.len())?;
+        for call in &self.call_data {
+            call.serialize_to_writer(wtr)?;
+        }
+        Ok(())
+    }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct EthCallByTimestampQueryRequest {
     pub target_timestamp: u64,
     pub target_block_hint: String,
@@ -161,28 +527,56 @@ impl EthCallByTimestampQueryRequest {
         Self::deserialize_from_reader(&mut rdr)
     }
 
+    /// Also returns the number of bytes consumed.
+    pub fn deserialize_with_len(
+        data: &[u8],
+    ) -> std::result::Result<(EthCallByTimestampQueryRequest, usize), std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        let value = Self::deserialize_from_reader(&mut rdr)?;
+        Ok((value, rdr.position() as usize))
+    }
+
+    /// Like [`EthCallByTimestampQueryRequest::deserialize`], but also runs [`EthCallByTimestampQueryRequest::validate`].
+    pub fn deserialize_strict(
+        data: &[u8],
+    ) -> std::result::Result<EthCallByTimestampQueryRequest, std::io::Error> {
+        let request = Self::deserialize(data)?;
+        request.validate()?;
+        Ok(request)
+    }
+
+    /// Rejects block hints containing non-ASCII characters or NUL bytes.
+    pub fn validate(&self) -> std::result::Result<(), std::io::Error> {
+        check_string_field(&self.target_block_hint, "InvalidBlockTag")?;
+        check_string_field(&self.following_block_hint, "InvalidBlockTag")
+    }
+
     pub fn deserialize_from_reader(
         rdr: &mut Cursor<&[u8]>,
+    ) -> std::result::Result<EthCallByTimestampQueryRequest, std::io::Error> {
+        Self::read_from(rdr, &mut Budget::unlimited())
+    }
+
+    pub(crate) fn read_from(
+        rdr: &mut Cursor<&[u8]>,
+        budget: &mut Budget,
     ) -> std::result::Result<EthCallByTimestampQueryRequest, std::io::Error> {
         let target_timestamp = rdr.read_u64::<BigEndian>()?;
         let target_block_hint_len = rdr.read_u32::<BigEndian>()?;
-        let mut buf = vec![0u8; target_block_hint_len.try_into().unwrap()];
-        rdr.read_exact(&mut buf)?;
+        let buf = read_bytes(rdr, target_block_hint_len)?;
         let target_block_hint = String::from_utf8(buf.clone())
             .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "InvalidBlockTag"))?;
         let following_block_hint_len = rdr.read_u32::<BigEndian>()?;
-        let mut buf = vec![0u8; following_block_hint_len.try_into().unwrap()];
-        rdr.read_exact(&mut buf)?;
+        let buf = read_bytes(rdr, following_block_hint_len)?;
         let following_block_hint = String::from_utf8(buf.clone())
             .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "InvalidBlockTag"))?;
-        let call_data_len = rdr.read_u8()?;
+        let call_data_len = budget.read_count(rdr)?;
         let mut call_data = Vec::with_capacity(call_data_len.into());
         for _ in 0..call_data_len {
             let mut to = [0u8; 20];
             rdr.read_exact(&mut to)?;
             let data_len = rdr.read_u32::<BigEndian>()?;
-            let mut data = vec![0u8; data_len.try_into().unwrap()];
-            rdr.read_exact(&mut data)?;
+            let data = read_bytes(rdr, data_len)?;
             call_data.push(EthCallData { to, data })
         }
         Ok(EthCallByTimestampQueryRequest {
@@ -192,8 +586,31 @@ impl EthCallByTimestampQueryRequest {
             call_data,
         })
     }
+
+    pub fn serialize(&self) -> std::result::Result<Vec<u8>, std::io::Error> {
+        let mut wtr = Vec::new();
+        self.serialize_to_writer(&mut wtr)?;
+        Ok(wtr)
+    }
+
+    pub fn serialize_to_writer<W: Write>(
+        &self,
+        wtr: &mut W,
+    ) -> std::result::Result<(), std::io::Error> {
+        wtr.write_u64::<BigEndian>(self.target_timestamp)?;
+        write_bytes_u32(wtr, self.target_block_hint.as_bytes())?;
+        write_bytes_u32(wtr, self.following_block_hint.as_bytes())?;
+        write_u8_len(wtr, self.call_data.len())?;
+        for call in &self.call_data {
+            call.serialize_to_writer(wtr)?;
+        }
+        Ok(())
+    }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct EthCallWithFinalityQueryRequest {
     pub block_tag: String,
     pub finality: String,
@@ -201,6 +618,24 @@ pub struct EthCallWithFinalityQueryRequest {
 }
 
 impl EthCallWithFinalityQueryRequest {
+    pub const FINALITY_FINALIZED: &'static str = "finalized";
+    pub const FINALITY_SAFE: &'static str = "safe";
+
+    /// Builds the request, rejecting any finality other than `finalized` or `safe`.
+    pub fn new(
+        block_tag: String,
+        finality: String,
+        call_data: Vec<EthCallData>,
+    ) -> std::result::Result<EthCallWithFinalityQueryRequest, std::io::Error> {
+        let request = EthCallWithFinalityQueryRequest {
+            block_tag,
+            finality,
+            call_data,
+        };
+        request.validate()?;
+        Ok(request)
+    }
+
     pub fn deserialize(
         data: &[u8],
     ) -> std::result::Result<EthCallWithFinalityQueryRequest, std::io::Error> {
@@ -208,27 +643,63 @@ impl EthCallWithFinalityQueryRequest {
         Self::deserialize_from_reader(&mut rdr)
     }
 
+    /// Also returns the number of bytes consumed.
+    pub fn deserialize_with_len(
+        data: &[u8],
+    ) -> std::result::Result<(EthCallWithFinalityQueryRequest, usize), std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        let value = Self::deserialize_from_reader(&mut rdr)?;
+        Ok((value, rdr.position() as usize))
+    }
+
+    /// Like [`EthCallWithFinalityQueryRequest::deserialize`], but also runs
+    /// [`EthCallWithFinalityQueryRequest::validate`].
+    pub fn deserialize_strict(
+        data: &[u8],
+    ) -> std::result::Result<EthCallWithFinalityQueryRequest, std::io::Error> {
+        let request = Self::deserialize(data)?;
+        request.validate()?;
+        Ok(request)
+    }
+
+    /// Guardians only answer `finalized` and `safe`, matched case sensitively. The block
+    /// tag may not contain non-ASCII characters or NUL bytes.
+    pub fn validate(&self) -> std::result::Result<(), std::io::Error> {
+        check_string_field(&self.block_tag, "InvalidBlockTag")?;
+        if self.finality != Self::FINALITY_FINALIZED && self.finality != Self::FINALITY_SAFE {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "InvalidFinality",
+            ));
+        }
+        Ok(())
+    }
+
     pub fn deserialize_from_reader(
         rdr: &mut Cursor<&[u8]>,
+    ) -> std::result::Result<EthCallWithFinalityQueryRequest, std::io::Error> {
+        Self::read_from(rdr, &mut Budget::unlimited())
+    }
+
+    pub(crate) fn read_from(
+        rdr: &mut Cursor<&[u8]>,
+        budget: &mut Budget,
     ) -> std::result::Result<EthCallWithFinalityQueryRequest, std::io::Error> {
         let block_tag_len = rdr.read_u32::<BigEndian>()?;
-        let mut buf = vec![0u8; block_tag_len.try_into().unwrap()];
-        rdr.read_exact(&mut buf)?;
+        let buf = read_bytes(rdr, block_tag_len)?;
         let block_tag = String::from_utf8(buf.clone())
             .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "InvalidBlockTag"))?;
         let finality_len = rdr.read_u32::<BigEndian>()?;
-        let mut buf = vec![0u8; finality_len.try_into().unwrap()];
-        rdr.read_exact(&mut buf)?;
+        let buf = read_bytes(rdr, finality_len)?;
         let finality = String::from_utf8(buf.clone())
             .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "InvalidFinality"))?;
-        let call_data_len = rdr.read_u8()?;
+        let call_data_len = budget.read_count(rdr)?;
         let mut call_data = Vec::with_capacity(call_data_len.into());
         for _ in 0..call_data_len {
             let mut to = [0u8; 20];
             rdr.read_exact(&mut to)?;
             let data_len = rdr.read_u32::<BigEndian>()?;
-            let mut data = vec![0u8; data_len.try_into().unwrap()];
-            rdr.read_exact(&mut data)?;
+            let data = read_bytes(rdr, data_len)?;
             call_data.push(EthCallData { to, data })
         }
         Ok(EthCallWithFinalityQueryRequest {
@@ -237,8 +708,258 @@ impl EthCallWithFinalityQueryRequest {
             call_data,
         })
     }
+
+    pub fn serialize(&self) -> std::result::Result<Vec<u8>, std::io::Error> {
+        let mut wtr = Vec::new();
+        self.serialize_to_writer(&mut wtr)?;
+        Ok(wtr)
+    }
+
+    pub fn serialize_to_writer<W: Write>(
+        &self,
+        wtr: &mut W,
+    ) -> std::result::Result<(), std::io::Error> {
+        write_bytes_u32(wtr, self.block_tag.as_bytes())?;
+        write_bytes_u32(wtr, self.finality.as_bytes())?;
+        write_u8_len(wtr, self.call_data.len())?;
+        for call in &self.call_data {
+            call.serialize_to_writer(wtr)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "experimental")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct EthCallWithOverridesQueryRequest {
+    pub block_tag: String,
+    pub overrides: Vec<StateOverride>,
+    pub call_data: Vec<EthCallData>,
+}
+
+/// State applied to one account before the calls are made, as in the third parameter of
+/// geth's `eth_call`. Unset fields leave the account as it is.
+#[cfg(feature = "experimental")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct StateOverride {
+    pub address: [u8; 20],
+    pub balance: Option<[u8; 32]>,
+    pub nonce: Option<u64>,
+    pub code: Option<Vec<u8>>,
+    /// Storage slots to replace, leaving all other slots untouched.
+    pub state_diff: Vec<StorageOverride>,
+}
+
+#[cfg(feature = "experimental")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct StorageOverride {
+    pub slot: [u8; 32],
+    pub value: [u8; 32],
+}
+
+#[cfg(feature = "experimental")]
+impl EthCallWithOverridesQueryRequest {
+    pub fn deserialize(
+        data: &[u8],
+    ) -> std::result::Result<EthCallWithOverridesQueryRequest, std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        Self::deserialize_from_reader(&mut rdr)
+    }
+
+    /// Also returns the number of bytes consumed.
+    pub fn deserialize_with_len(
+        data: &[u8],
+    ) -> std::result::Result<(EthCallWithOverridesQueryRequest, usize), std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        let value = Self::deserialize_from_reader(&mut rdr)?;
+        Ok((value, rdr.position() as usize))
+    }
+
+    /// Like [`EthCallWithOverridesQueryRequest::deserialize`], but also runs
+    /// [`EthCallWithOverridesQueryRequest::validate`].
+    pub fn deserialize_strict(
+        data: &[u8],
+    ) -> std::result::Result<EthCallWithOverridesQueryRequest, std::io::Error> {
+        let request = Self::deserialize(data)?;
+        request.validate()?;
+        Ok(request)
+    }
+
+    /// Rejects block tags containing non-ASCII characters or NUL bytes, and more than one
+    /// override for the same address.
+    pub fn validate(&self) -> std::result::Result<(), std::io::Error> {
+        check_string_field(&self.block_tag, "InvalidBlockTag")?;
+        for (i, entry) in self.overrides.iter().enumerate() {
+            if self.overrides[..i]
+                .iter()
+                .any(|other| other.address == entry.address)
+            {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "DuplicateOverride",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn deserialize_from_reader(
+        rdr: &mut Cursor<&[u8]>,
+    ) -> std::result::Result<EthCallWithOverridesQueryRequest, std::io::Error> {
+        Self::read_from(rdr, &mut Budget::unlimited())
+    }
+
+    pub(crate) fn read_from(
+        rdr: &mut Cursor<&[u8]>,
+        budget: &mut Budget,
+    ) -> std::result::Result<EthCallWithOverridesQueryRequest, std::io::Error> {
+        let block_tag_len = rdr.read_u32::<BigEndian>()?;
+        let buf = read_bytes(rdr, block_tag_len)?;
+        let block_tag = String::from_utf8(buf.clone())
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "InvalidBlockTag"))?;
+        let overrides_len = budget.read_count(rdr)?;
+        let mut overrides = Vec::with_capacity(overrides_len.into());
+        for _ in 0..overrides_len {
+            overrides.push(StateOverride::read_from(rdr, budget)?);
+        }
+        let call_data_len = budget.read_count(rdr)?;
+        let mut call_data = Vec::with_capacity(call_data_len.into());
+        for _ in 0..call_data_len {
+            let mut to = [0u8; 20];
+            rdr.read_exact(&mut to)?;
+            let data_len = rdr.read_u32::<BigEndian>()?;
+            let data = read_bytes(rdr, data_len)?;
+            call_data.push(EthCallData { to, data })
+        }
+        Ok(EthCallWithOverridesQueryRequest {
+            block_tag,
+            overrides,
+            call_data,
+        })
+    }
+
+    pub fn serialize(&self) -> std::result::Result<Vec<u8>, std::io::Error> {
+        let mut wtr = Vec::new();
+        self.serialize_to_writer(&mut wtr)?;
+        Ok(wtr)
+    }
+
+    pub fn serialize_to_writer<W: Write>(
+        &self,
+        wtr: &mut W,
+    ) -> std::result::Result<(), std::io::Error> {
+        write_bytes_u32(wtr, self.block_tag.as_bytes())?;
+        write_u8_len(wtr, self.overrides.len())?;
+        for entry in &self.overrides {
+            entry.serialize_to_writer(wtr)?;
+        }
+        write_u8_len(wtr, self.call_data.len())?;
+        for call in &self.call_data {
+            call.serialize_to_writer(wtr)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "experimental")]
+impl StateOverride {
+    /// Optional fields are preceded by a one byte presence flag.
+    pub fn deserialize_from_reader(
+        rdr: &mut Cursor<&[u8]>,
+    ) -> std::result::Result<StateOverride, std::io::Error> {
+        Self::read_from(rdr, &mut Budget::unlimited())
+    }
+
+    pub(crate) fn read_from(
+        rdr: &mut Cursor<&[u8]>,
+        budget: &mut Budget,
+    ) -> std::result::Result<StateOverride, std::io::Error> {
+        let mut address = [0u8; 20];
+        rdr.read_exact(&mut address)?;
+        let balance = if read_flag(rdr)? {
+            let mut balance = [0u8; 32];
+            rdr.read_exact(&mut balance)?;
+            Some(balance)
+        } else {
+            None
+        };
+        let nonce = if read_flag(rdr)? {
+            Some(rdr.read_u64::<BigEndian>()?)
+        } else {
+            None
+        };
+        let code = if read_flag(rdr)? {
+            let code_len = rdr.read_u32::<BigEndian>()?;
+            let code = read_bytes(rdr, code_len)?;
+            Some(code)
+        } else {
+            None
+        };
+        let state_diff_len = budget.read_count(rdr)?;
+        let mut state_diff = Vec::with_capacity(state_diff_len.into());
+        for _ in 0..state_diff_len {
+            let mut slot = [0u8; 32];
+            rdr.read_exact(&mut slot)?;
+            let mut value = [0u8; 32];
+            rdr.read_exact(&mut value)?;
+            state_diff.push(StorageOverride { slot, value });
+        }
+        Ok(StateOverride {
+            address,
+            balance,
+            nonce,
+            code,
+            state_diff,
+        })
+    }
+
+    pub fn serialize_to_writer<W: Write>(
+        &self,
+        wtr: &mut W,
+    ) -> std::result::Result<(), std::io::Error> {
+        wtr.write_all(&self.address)?;
+        wtr.write_u8(self.balance.is_some().into())?;
+        if let Some(balance) = &self.balance {
+            wtr.write_all(balance)?;
+        }
+        wtr.write_u8(self.nonce.is_some().into())?;
+        if let Some(nonce) = self.nonce {
+            wtr.write_u64::<BigEndian>(nonce)?;
+        }
+        wtr.write_u8(self.code.is_some().into())?;
+        if let Some(code) = &self.code {
+            write_bytes_u32(wtr, code)?;
+        }
+        write_u8_len(wtr, self.state_diff.len())?;
+        for entry in &self.state_diff {
+            wtr.write_all(&entry.slot)?;
+            wtr.write_all(&entry.value)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "experimental")]
+fn read_flag(rdr: &mut Cursor<&[u8]>) -> std::result::Result<bool, std::io::Error> {
+    match rdr.read_u8()? {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "InvalidOverrideFlag",
+        )),
+    }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SolanaAccountQueryRequest {
     pub commitment: String,
     pub min_context_slot: u64,
@@ -248,6 +969,68 @@ pub struct SolanaAccountQueryRequest {
 }
 
 impl SolanaAccountQueryRequest {
+    pub const COMMITMENT_FINALIZED: &'static str = "finalized";
+
+    /// Requests `length` bytes of each account's data starting at `offset`.
+    ///
+    /// A zero `length` is rejected, since on the wire it means "no slice".
+    pub fn with_data_slice(
+        mut self,
+        offset: u64,
+        length: u64,
+    ) -> std::result::Result<SolanaAccountQueryRequest, std::io::Error> {
+        if length == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "InvalidDataSlice",
+            ));
+        }
+        self.data_slice_offset = offset;
+        self.data_slice_length = length;
+        Ok(self)
+    }
+
+    /// Requests the full account data, encoded on the wire as a zero offset and length.
+    pub fn without_data_slice(mut self) -> SolanaAccountQueryRequest {
+        self.data_slice_offset = 0;
+        self.data_slice_length = 0;
+        self
+    }
+
+    /// Whether only a slice of each account's data is requested.
+    pub fn has_data_slice(&self) -> bool {
+        self.data_slice_length != 0
+    }
+
+    /// Derives a `min_context_slot` from a recently observed slot, trailing it by `lag`
+    /// slots so that RPC nodes which are slightly behind can still answer.
+    pub fn min_context_slot_from_observed(observed_slot: u64, lag: u64) -> u64 {
+        observed_slot.saturating_sub(lag)
+    }
+
+    pub fn with_min_context_slot(mut self, min_context_slot: u64) -> SolanaAccountQueryRequest {
+        self.min_context_slot = min_context_slot;
+        self
+    }
+
+    /// Guardians only answer the `finalized` commitment, and reject an offset without a
+    /// length, as the offset would be ignored.
+    pub fn validate(&self) -> std::result::Result<(), std::io::Error> {
+        if self.commitment != Self::COMMITMENT_FINALIZED {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "InvalidCommitment",
+            ));
+        }
+        if self.data_slice_length == 0 && self.data_slice_offset != 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "InvalidDataSlice",
+            ));
+        }
+        Ok(())
+    }
+
     pub fn deserialize(
         data: &[u8],
     ) -> std::result::Result<SolanaAccountQueryRequest, std::io::Error> {
@@ -255,18 +1038,42 @@ impl SolanaAccountQueryRequest {
         Self::deserialize_from_reader(&mut rdr)
     }
 
+    /// Also returns the number of bytes consumed.
+    pub fn deserialize_with_len(
+        data: &[u8],
+    ) -> std::result::Result<(SolanaAccountQueryRequest, usize), std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        let value = Self::deserialize_from_reader(&mut rdr)?;
+        Ok((value, rdr.position() as usize))
+    }
+
+    /// Like [`SolanaAccountQueryRequest::deserialize`], but also runs [`SolanaAccountQueryRequest::validate`].
+    pub fn deserialize_strict(
+        data: &[u8],
+    ) -> std::result::Result<SolanaAccountQueryRequest, std::io::Error> {
+        let request = Self::deserialize(data)?;
+        request.validate()?;
+        Ok(request)
+    }
+
     pub fn deserialize_from_reader(
         rdr: &mut Cursor<&[u8]>,
+    ) -> std::result::Result<SolanaAccountQueryRequest, std::io::Error> {
+        Self::read_from(rdr, &mut Budget::unlimited())
+    }
+
+    pub(crate) fn read_from(
+        rdr: &mut Cursor<&[u8]>,
+        budget: &mut Budget,
     ) -> std::result::Result<SolanaAccountQueryRequest, std::io::Error> {
         let commitment_len = rdr.read_u32::<BigEndian>()?;
-        let mut buf = vec![0u8; commitment_len.try_into().unwrap()];
-        rdr.read_exact(&mut buf)?;
+        let buf = read_bytes(rdr, commitment_len)?;
         let commitment = String::from_utf8(buf.clone())
             .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "InvalidBlockTag"))?;
         let min_context_slot = rdr.read_u64::<BigEndian>()?;
         let data_slice_offset = rdr.read_u64::<BigEndian>()?;
         let data_slice_length = rdr.read_u64::<BigEndian>()?;
-        let accounts_len = rdr.read_u8()?;
+        let accounts_len = budget.read_count(rdr)?;
         let mut accounts = Vec::with_capacity(accounts_len.into());
         for _ in 0..accounts_len {
             let mut account = [0u8; 32];
@@ -281,4 +1088,232 @@ impl SolanaAccountQueryRequest {
             accounts,
         })
     }
+
+    pub fn serialize(&self) -> std::result::Result<Vec<u8>, std::io::Error> {
+        let mut wtr = Vec::new();
+        self.serialize_to_writer(&mut wtr)?;
+        Ok(wtr)
+    }
+
+    pub fn serialize_to_writer<W: Write>(
+        &self,
+        wtr: &mut W,
+    ) -> std::result::Result<(), std::io::Error> {
+        write_bytes_u32(wtr, self.commitment.as_bytes())?;
+        wtr.write_u64::<BigEndian>(self.min_context_slot)?;
+        wtr.write_u64::<BigEndian>(self.data_slice_offset)?;
+        wtr.write_u64::<BigEndian>(self.data_slice_length)?;
+        write_u8_len(wtr, self.accounts.len())?;
+        for account in &self.accounts {
+            wtr.write_all(account)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "experimental")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct NearViewCallQueryRequest {
+    /// A block height, a block hash, or the finality `final` or `optimistic`.
+    pub block_id: String,
+    pub calls: Vec<NearViewCall>,
+}
+
+/// A call of a view method on a NEAR contract.
+#[cfg(feature = "experimental")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct NearViewCall {
+    pub account_id: String,
+    pub method_name: String,
+    pub args: Vec<u8>,
+}
+
+#[cfg(feature = "experimental")]
+impl NearViewCallQueryRequest {
+    pub fn deserialize(
+        data: &[u8],
+    ) -> std::result::Result<NearViewCallQueryRequest, std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        Self::deserialize_from_reader(&mut rdr)
+    }
+
+    /// Also returns the number of bytes consumed.
+    pub fn deserialize_with_len(
+        data: &[u8],
+    ) -> std::result::Result<(NearViewCallQueryRequest, usize), std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        let value = Self::deserialize_from_reader(&mut rdr)?;
+        Ok((value, rdr.position() as usize))
+    }
+
+    /// Like [`NearViewCallQueryRequest::deserialize`], but also runs
+    /// [`NearViewCallQueryRequest::validate`].
+    pub fn deserialize_strict(
+        data: &[u8],
+    ) -> std::result::Result<NearViewCallQueryRequest, std::io::Error> {
+        let request = Self::deserialize(data)?;
+        request.validate()?;
+        Ok(request)
+    }
+
+    /// Rejects block ids, account ids and method names containing non-ASCII characters or
+    /// NUL bytes.
+    pub fn validate(&self) -> std::result::Result<(), std::io::Error> {
+        check_string_field(&self.block_id, "InvalidBlockId")?;
+        for call in &self.calls {
+            check_string_field(&call.account_id, "InvalidAccountId")?;
+            check_string_field(&call.method_name, "InvalidMethodName")?;
+        }
+        Ok(())
+    }
+
+    pub fn deserialize_from_reader(
+        rdr: &mut Cursor<&[u8]>,
+    ) -> std::result::Result<NearViewCallQueryRequest, std::io::Error> {
+        Self::read_from(rdr, &mut Budget::unlimited())
+    }
+
+    pub(crate) fn read_from(
+        rdr: &mut Cursor<&[u8]>,
+        budget: &mut Budget,
+    ) -> std::result::Result<NearViewCallQueryRequest, std::io::Error> {
+        let block_id = read_string(rdr, "InvalidBlockId")?;
+        let calls_len = budget.read_count(rdr)?;
+        let mut calls = Vec::with_capacity(calls_len.into());
+        for _ in 0..calls_len {
+            let account_id = read_string(rdr, "InvalidAccountId")?;
+            let method_name = read_string(rdr, "InvalidMethodName")?;
+            let args_len = rdr.read_u32::<BigEndian>()?;
+            let args = read_bytes(rdr, args_len)?;
+            calls.push(NearViewCall {
+                account_id,
+                method_name,
+                args,
+            });
+        }
+        Ok(NearViewCallQueryRequest { block_id, calls })
+    }
+
+    pub fn serialize(&self) -> std::result::Result<Vec<u8>, std::io::Error> {
+        let mut wtr = Vec::new();
+        self.serialize_to_writer(&mut wtr)?;
+        Ok(wtr)
+    }
+
+    pub fn serialize_to_writer<W: Write>(
+        &self,
+        wtr: &mut W,
+    ) -> std::result::Result<(), std::io::Error> {
+        write_bytes_u32(wtr, self.block_id.as_bytes())?;
+        write_u8_len(wtr, self.calls.len())?;
+        for call in &self.calls {
+            write_bytes_u32(wtr, call.account_id.as_bytes())?;
+            write_bytes_u32(wtr, call.method_name.as_bytes())?;
+            write_bytes_u32(wtr, &call.args)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads the balance and storage of NEAR accounts, as NEAR's `view_account` query.
+#[cfg(feature = "experimental")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct NearViewAccountQueryRequest {
+    /// A block height, a block hash, or the finality `final` or `optimistic`.
+    pub block_id: String,
+    pub account_ids: Vec<String>,
+}
+
+#[cfg(feature = "experimental")]
+impl NearViewAccountQueryRequest {
+    pub fn deserialize(
+        data: &[u8],
+    ) -> std::result::Result<NearViewAccountQueryRequest, std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        Self::deserialize_from_reader(&mut rdr)
+    }
+
+    /// Also returns the number of bytes consumed.
+    pub fn deserialize_with_len(
+        data: &[u8],
+    ) -> std::result::Result<(NearViewAccountQueryRequest, usize), std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        let value = Self::deserialize_from_reader(&mut rdr)?;
+        Ok((value, rdr.position() as usize))
+    }
+
+    /// Like [`NearViewAccountQueryRequest::deserialize`], but also runs
+    /// [`NearViewAccountQueryRequest::validate`].
+    pub fn deserialize_strict(
+        data: &[u8],
+    ) -> std::result::Result<NearViewAccountQueryRequest, std::io::Error> {
+        let request = Self::deserialize(data)?;
+        request.validate()?;
+        Ok(request)
+    }
+
+    /// Rejects block ids and account ids containing non-ASCII characters or NUL bytes.
+    pub fn validate(&self) -> std::result::Result<(), std::io::Error> {
+        check_string_field(&self.block_id, "InvalidBlockId")?;
+        for account_id in &self.account_ids {
+            check_string_field(account_id, "InvalidAccountId")?;
+        }
+        Ok(())
+    }
+
+    pub fn deserialize_from_reader(
+        rdr: &mut Cursor<&[u8]>,
+    ) -> std::result::Result<NearViewAccountQueryRequest, std::io::Error> {
+        Self::read_from(rdr, &mut Budget::unlimited())
+    }
+
+    pub(crate) fn read_from(
+        rdr: &mut Cursor<&[u8]>,
+        budget: &mut Budget,
+    ) -> std::result::Result<NearViewAccountQueryRequest, std::io::Error> {
+        let block_id = read_string(rdr, "InvalidBlockId")?;
+        let account_ids_len = budget.read_count(rdr)?;
+        let mut account_ids = Vec::with_capacity(account_ids_len.into());
+        for _ in 0..account_ids_len {
+            account_ids.push(read_string(rdr, "InvalidAccountId")?);
+        }
+        Ok(NearViewAccountQueryRequest {
+            block_id,
+            account_ids,
+        })
+    }
+
+    pub fn serialize(&self) -> std::result::Result<Vec<u8>, std::io::Error> {
+        let mut wtr = Vec::new();
+        self.serialize_to_writer(&mut wtr)?;
+        Ok(wtr)
+    }
+
+    pub fn serialize_to_writer<W: Write>(
+        &self,
+        wtr: &mut W,
+    ) -> std::result::Result<(), std::io::Error> {
+        write_bytes_u32(wtr, self.block_id.as_bytes())?;
+        write_u8_len(wtr, self.account_ids.len())?;
+        for account_id in &self.account_ids {
+            write_bytes_u32(wtr, account_id.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "experimental")]
+fn read_string(
+    rdr: &mut Cursor<&[u8]>,
+    err: &'static str,
+) -> std::result::Result<String, std::io::Error> {
+    let len = rdr.read_u32::<BigEndian>()?;
+    let buf = read_bytes(rdr, len)?;
+    String::from_utf8(buf).map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
 }
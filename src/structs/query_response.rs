@@ -1,8 +1,27 @@
-use byteorder::{BigEndian, ReadBytesExt};
-use std::io::{Cursor, Read};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::io::{Cursor, Read, Write};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use super::QueryRequest;
+use crate::chain::ChainId;
 
+use super::limits::Budget;
+use super::wire::{
+    check_body_len, decode_hex, read_bytes, write_bytes_u32, write_u8_len, RequestIdDebug,
+    TrailingBytes,
+};
+use super::{
+    ChainSpecificQuery, EthCallByTimestampQueryRequest, EthCallData, EthCallQueryRequest,
+    EthCallWithFinalityQueryRequest, PerChainQueryRequest, QueryRequest, SolanaAccountQueryRequest,
+};
+#[cfg(feature = "experimental")]
+use super::{
+    EthCallWithOverridesQueryRequest, NearViewAccountQueryRequest, NearViewCall,
+    NearViewCallQueryRequest,
+};
+
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct QueryResponse {
     pub version: u8,
     pub request_chain_id: u16,
@@ -11,16 +30,173 @@ pub struct QueryResponse {
     pub responses: Vec<PerChainQueryResponse>,
 }
 
+/// The request id is redacted when it is a request signature.
+impl std::fmt::Debug for QueryResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueryResponse")
+            .field("version", &self.version)
+            .field("request_chain_id", &self.request_chain_id)
+            .field(
+                "request_id",
+                &RequestIdDebug {
+                    request_chain_id: self.request_chain_id,
+                    request_id: &self.request_id,
+                },
+            )
+            .field("request", &self.request)
+            .field("responses", &self.responses)
+            .finish()
+    }
+}
+
 impl QueryResponse {
     pub const RESPONSE_VERSION: u8 = 1;
 
+    /// Finds the first per chain response for `chain` in a serialized response, parsing
+    /// only that one. The request and other chains' responses are skipped using their
+    /// length prefixes, so they are not validated.
+    pub fn find_chain(
+        data: &[u8],
+        chain: ChainId,
+    ) -> std::result::Result<Option<PerChainQueryResponse>, std::io::Error> {
+        let chain_id = u16::from(chain);
+        let mut rdr = Cursor::new(data);
+        let version = rdr.read_u8()?;
+        if version != Self::RESPONSE_VERSION {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "InvalidResponseVersion",
+            ));
+        }
+        let request_chain_id = rdr.read_u16::<BigEndian>()?;
+        let request_id_len: u64 = if request_chain_id == 0 { 65 } else { 32 };
+        skip(&mut rdr, request_id_len)?;
+        let request_len = rdr.read_u32::<BigEndian>()?;
+        skip(&mut rdr, request_len.into())?;
+
+        let num_per_chain_responses = rdr.read_u8()?;
+        for _ in 0..num_per_chain_responses {
+            let response_chain_id = rdr.read_u16::<BigEndian>()?;
+            let query_type = rdr.read_u8()?;
+            let response_len = rdr.read_u32::<BigEndian>()?;
+            if response_chain_id != chain_id {
+                skip(&mut rdr, response_len.into())?;
+                continue;
+            }
+            let start = rdr.position() as usize;
+            let body = data
+                .get(start..start + response_len as usize)
+                .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::UnexpectedEof))?;
+            let (response, len) = ChainSpecificResponse::deserialize_with_len(query_type, body)?;
+            if len != body.len() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "InvalidResponseLength",
+                ));
+            }
+            return Ok(Some(PerChainQueryResponse { chain_id, response }));
+        }
+        Ok(None)
+    }
+
+    /// Builds an off chain response to `request`, for tests. Chain ids are taken from the
+    /// request and the request id is left zeroed. Fails if `responses` does not line up
+    /// with the request: a different number of per chain responses, a different query
+    /// type, or a different number of results.
+    pub fn from_parts(
+        request: QueryRequest,
+        responses: Vec<ChainSpecificResponse>,
+    ) -> std::result::Result<QueryResponse, std::io::Error> {
+        let mismatch =
+            || std::io::Error::new(std::io::ErrorKind::InvalidInput, "RequestResponseMismatch");
+        if request.requests.len() != responses.len() {
+            return Err(mismatch());
+        }
+        let mut per_chain_responses = Vec::with_capacity(responses.len());
+        for (per_chain_request, response) in request.requests.iter().zip(responses) {
+            if per_chain_request.query.query_type() != response.query_type() {
+                return Err(mismatch());
+            }
+            if expected_result_count(&per_chain_request.query) != result_count(&response) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "ResultCountMismatch",
+                ));
+            }
+            per_chain_responses.push(PerChainQueryResponse::new(
+                per_chain_request.chain_id,
+                response,
+            ));
+        }
+        Ok(QueryResponse {
+            version: Self::RESPONSE_VERSION,
+            request_chain_id: 0,
+            request_id: vec![0u8; 65],
+            request,
+            responses: per_chain_responses,
+        })
+    }
+
     pub fn deserialize(data: &[u8]) -> std::result::Result<QueryResponse, std::io::Error> {
         let mut rdr = Cursor::new(data);
         Self::deserialize_from_reader(&mut rdr)
     }
 
+    /// Like [`QueryResponse::deserialize`], but allows trailing bytes and also returns the
+    /// number of bytes consumed, so a payload embedded in a larger buffer can be parsed in
+    /// place.
+    pub fn deserialize_with_len(
+        data: &[u8],
+    ) -> std::result::Result<(QueryResponse, usize), std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        let value = Self::read_from(&mut rdr, &mut Budget::unlimited())?;
+        Ok((value, rdr.position() as usize))
+    }
+
+    /// Parses `data` under the given trailing bytes policy, also returning the number of
+    /// bytes consumed.
+    pub fn deserialize_with(
+        data: &[u8],
+        trailing: TrailingBytes,
+    ) -> std::result::Result<(QueryResponse, usize), std::io::Error> {
+        trailing.check(Self::deserialize_with_len(data), data.len())
+    }
+
+    /// Parses responses serialized back to back, splitting them by their internal
+    /// lengths. Fails with the offset of the response which could not be parsed.
+    pub fn deserialize_many(
+        data: &[u8],
+    ) -> std::result::Result<Vec<QueryResponse>, (usize, std::io::Error)> {
+        let mut responses = Vec::new();
+        let mut offset = 0;
+        while offset < data.len() {
+            let (response, len) =
+                Self::deserialize_with_len(&data[offset..]).map_err(|err| (offset, err))?;
+            responses.push(response);
+            offset += len;
+        }
+        Ok(responses)
+    }
+
     pub fn deserialize_from_reader(
         rdr: &mut Cursor<&[u8]>,
+    ) -> std::result::Result<QueryResponse, std::io::Error> {
+        let response = Self::read_from(rdr, &mut Budget::unlimited())?;
+
+        if rdr.position() != rdr.get_ref().len() as u64 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "InvalidPayloadLength",
+            ));
+        }
+
+        Ok(response)
+    }
+
+    /// Parses a response without requiring it to end the buffer.
+    pub(crate) fn read_from(
+        rdr: &mut Cursor<&[u8]>,
+        budget: &mut Budget,
     ) -> std::result::Result<QueryResponse, std::io::Error> {
         let version = rdr.read_u8()?;
         if version != Self::RESPONSE_VERSION {
@@ -36,17 +212,393 @@ impl QueryResponse {
         let mut request_id = vec![0u8; request_id_len];
         rdr.read_exact(&mut request_id)?;
 
-        rdr.read_u32::<BigEndian>()?; // skip the request length
+        let request_len = rdr.read_u32::<BigEndian>()?;
+        let start = rdr.position();
+        let request = QueryRequest::read_from(rdr, budget)?;
+        check_body_len(rdr, start, request_len, "InvalidRequestLength")?;
 
-        let request = QueryRequest::deserialize_from_reader(rdr)?;
-
-        let num_per_chain_responses = rdr.read_u8()?;
+        let num_per_chain_responses = budget.read_count(rdr)?;
 
         let mut responses: Vec<PerChainQueryResponse> =
             Vec::with_capacity(num_per_chain_responses.into());
         for _idx in 0..num_per_chain_responses {
-            responses.push(PerChainQueryResponse::deserialize_from_reader(rdr)?)
+            responses.push(PerChainQueryResponse::read_from(rdr, budget)?)
+        }
+
+        Ok(QueryResponse {
+            version,
+            request_chain_id,
+            request_id,
+            request,
+            responses,
+        })
+    }
+
+    pub fn serialize(&self) -> std::result::Result<Vec<u8>, std::io::Error> {
+        let mut wtr = Vec::new();
+        self.serialize_to_writer(&mut wtr)?;
+        Ok(wtr)
+    }
+
+    pub fn serialize_to_writer<W: Write>(
+        &self,
+        wtr: &mut W,
+    ) -> std::result::Result<(), std::io::Error> {
+        wtr.write_u8(self.version)?;
+        wtr.write_u16::<BigEndian>(self.request_chain_id)?;
+        let request_id_len = if self.request_chain_id == 0 { 65 } else { 32 };
+        if self.request_id.len() != request_id_len {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "InvalidRequestIdLength",
+            ));
+        }
+        wtr.write_all(&self.request_id)?;
+        write_bytes_u32(wtr, &self.request.serialize()?)?;
+        write_u8_len(wtr, self.responses.len())?;
+        for response in &self.responses {
+            response.serialize_to_writer(wtr)?;
+        }
+        Ok(())
+    }
+
+    /// Like [`QueryResponse::deserialize`], but also runs [`QueryRequest::validate`] on the
+    /// embedded request.
+    pub fn deserialize_strict(data: &[u8]) -> std::result::Result<QueryResponse, std::io::Error> {
+        let response = Self::deserialize(data)?;
+        response.request.validate()?;
+        Ok(response)
+    }
+
+    /// Parses `data` and checks that re-serializing it gives back the same bytes, catching
+    /// encodings which parse to the same response but hash differently.
+    pub fn verify_canonical(data: &[u8]) -> std::result::Result<QueryResponse, std::io::Error> {
+        let value = Self::deserialize(data)?;
+        if value.serialize()? != data {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "NonCanonicalEncoding",
+            ));
+        }
+        Ok(value)
+    }
+
+    /// Parses a hex encoded response. A leading `0x` is optional.
+    pub fn from_hex(s: &str) -> std::result::Result<QueryResponse, std::io::Error> {
+        Self::deserialize(&decode_hex(s)?)
+    }
+
+    /// Serializes the response as unprefixed lowercase hex, as used by the query proxy.
+    pub fn to_hex(&self) -> std::result::Result<String, std::io::Error> {
+        Ok(hex::encode(self.serialize()?))
+    }
+
+    /// The chains with a per chain response, in order of first appearance.
+    pub fn chain_ids(&self) -> Vec<ChainId> {
+        let mut chain_ids = Vec::new();
+        for per_chain in &self.responses {
+            if !chain_ids.contains(&per_chain.chain()) {
+                chain_ids.push(per_chain.chain());
+            }
+        }
+        chain_ids
+    }
+
+    pub fn contains_chain(&self, chain: ChainId) -> bool {
+        self.responses
+            .iter()
+            .any(|per_chain| per_chain.chain() == chain)
+    }
+
+    /// Fails with `StaleBlock` if any per chain response was read from a block older than
+    /// `max_age` at `now`.
+    pub fn check_max_block_age(
+        &self,
+        max_age: Duration,
+        now: SystemTime,
+    ) -> std::result::Result<(), std::io::Error> {
+        let now_micros = now
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, "InvalidTime"))?
+            .as_micros();
+        for response in &self.responses {
+            let age = now_micros.saturating_sub(response.response.block_time().into());
+            if age > max_age.as_micros() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "StaleBlock",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs [`PerChainQueryResponse::check_continuity`] against the matching per chain
+    /// response of an earlier response to the same query.
+    pub fn check_continuity(
+        &self,
+        previous: &QueryResponse,
+    ) -> std::result::Result<(), std::io::Error> {
+        if self.responses.len() != previous.responses.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "ResponseCountMismatch",
+            ));
+        }
+        for (response, previous_response) in self.responses.iter().zip(&previous.responses) {
+            response.check_continuity(previous_response)?;
+        }
+        Ok(())
+    }
+
+    /// Fails with `BlockNumberTooLow` if any response for `chain` was read from a block
+    /// (or slot, on Solana) below `min_block_number`, and with `ChainNotFound` if there is
+    /// no response for `chain` at all.
+    pub fn check_min_block_number(
+        &self,
+        chain: ChainId,
+        min_block_number: u64,
+    ) -> std::result::Result<(), std::io::Error> {
+        let mut found = false;
+        for response in self
+            .responses
+            .iter()
+            .filter(|response| response.chain() == chain)
+        {
+            if response.response.block_number() < min_block_number {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "BlockNumberTooLow",
+                ));
+            }
+            found = true;
         }
+        if !found {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "ChainNotFound",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks each per chain response has the shape its request implies: the same chain,
+    /// query type and number of results, Solana account data no longer than the requested
+    /// slice, and a following block directly after the target for eth_call_by_timestamp.
+    /// With `abi_words`, EVM call results must also be whole 32 byte words.
+    pub fn check_result_shapes(&self, abi_words: bool) -> std::result::Result<(), std::io::Error> {
+        let invalid = |err| std::io::Error::new(std::io::ErrorKind::InvalidData, err);
+        for (request, response) in self.aligned_pairs()? {
+            if expected_result_count(&request.query) != result_count(&response.response) {
+                return Err(invalid("ResultCountMismatch"));
+            }
+            let evm_results = match (&request.query, &response.response) {
+                (
+                    ChainSpecificQuery::SolanaAccountQueryRequest(query),
+                    ChainSpecificResponse::SolanaAccountQueryResponse(response),
+                ) => {
+                    response.check_data_slice(query)?;
+                    continue;
+                }
+                #[cfg(feature = "experimental")]
+                (_, ChainSpecificResponse::NearViewCallQueryResponse(_)) => continue,
+                #[cfg(feature = "experimental")]
+                (_, ChainSpecificResponse::NearViewAccountQueryResponse(_)) => continue,
+                (_, ChainSpecificResponse::EthCallByTimestampQueryResponse(response)) => {
+                    if response.following_block_number
+                        != response.target_block_number.wrapping_add(1)
+                    {
+                        return Err(invalid("InvalidFollowingBlock"));
+                    }
+                    &response.results
+                }
+                (_, ChainSpecificResponse::EthCallQueryResponse(response)) => &response.results,
+                (_, ChainSpecificResponse::EthCallWithFinalityQueryResponse(response)) => {
+                    &response.results
+                }
+                #[cfg(feature = "experimental")]
+                (_, ChainSpecificResponse::EthCallWithOverridesQueryResponse(response)) => {
+                    &response.results
+                }
+                (_, ChainSpecificResponse::SolanaAccountQueryResponse(_)) => continue,
+            };
+            if abi_words && evm_results.iter().any(|result| result.len() % 32 != 0) {
+                return Err(invalid("UnalignedResult"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Pairs each per chain request with its response, failing with
+    /// `RequestResponseMismatch` unless there is one response per request, with the same
+    /// chain id and query type.
+    pub(crate) fn aligned_pairs(
+        &self,
+    ) -> std::result::Result<Vec<(&PerChainQueryRequest, &PerChainQueryResponse)>, std::io::Error>
+    {
+        let mismatch =
+            || std::io::Error::new(std::io::ErrorKind::InvalidData, "RequestResponseMismatch");
+        if self.request.requests.len() != self.responses.len() {
+            return Err(mismatch());
+        }
+        self.request
+            .requests
+            .iter()
+            .zip(&self.responses)
+            .map(|(request, response)| {
+                if request.chain_id != response.chain_id
+                    || request.query.query_type() != response.response.query_type()
+                {
+                    return Err(mismatch());
+                }
+                Ok((request, response))
+            })
+            .collect()
+    }
+
+    /// Pairs each per chain request with what came back for it. Responses are matched in
+    /// order, so a request whose chain id or query type does not match the next response
+    /// is [`PerChainOutcome::Missing`], and one whose response has the wrong number of
+    /// results is [`PerChainOutcome::Error`]. Fails with `UnexpectedResponse` if any
+    /// responses are left over.
+    pub fn outcomes(&self) -> std::result::Result<Vec<ChainOutcome<'_>>, std::io::Error> {
+        let mut responses = self.responses.iter().peekable();
+        let mut outcomes = Vec::with_capacity(self.request.requests.len());
+        for request in &self.request.requests {
+            let outcome = match responses.next_if(|response| {
+                request.chain_id == response.chain_id
+                    && request.query.query_type() == response.response.query_type()
+            }) {
+                None => PerChainOutcome::Missing,
+                Some(response)
+                    if expected_result_count(&request.query)
+                        != result_count(&response.response) =>
+                {
+                    PerChainOutcome::Error(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "ResultCountMismatch",
+                    ))
+                }
+                Some(response) => PerChainOutcome::Response(response),
+            };
+            outcomes.push(ChainOutcome { request, outcome });
+        }
+        if responses.next().is_some() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "UnexpectedResponse",
+            ));
+        }
+        Ok(outcomes)
+    }
+
+    /// Fails with `MissingResponse`, or the error of the first failed chain, unless every
+    /// request has a response.
+    pub fn check_complete(&self) -> std::result::Result<(), std::io::Error> {
+        for outcome in self.outcomes()? {
+            match outcome.outcome {
+                PerChainOutcome::Response(_) => {}
+                PerChainOutcome::Missing => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "MissingResponse",
+                    ))
+                }
+                PerChainOutcome::Error(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A per chain request and what came back for it, from [`QueryResponse::outcomes`].
+#[derive(Debug)]
+pub struct ChainOutcome<'a> {
+    pub request: &'a PerChainQueryRequest,
+    pub outcome: PerChainOutcome<'a>,
+}
+
+#[derive(Debug)]
+pub enum PerChainOutcome<'a> {
+    Response(&'a PerChainQueryResponse),
+    /// No response was returned for the request.
+    Missing,
+    /// The chain failed, or its response does not answer the request.
+    Error(std::io::Error),
+}
+
+impl PerChainOutcome<'_> {
+    pub fn response(&self) -> Option<&PerChainQueryResponse> {
+        match self {
+            PerChainOutcome::Response(response) => Some(response),
+            _ => None,
+        }
+    }
+}
+
+/// A [`QueryResponse`] whose embedded request is kept as raw bytes.
+///
+/// Verifiers which only hash the request can use this to skip parsing it entirely.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct QueryResponseLite {
+    pub version: u8,
+    pub request_chain_id: u16,
+    pub request_id: Vec<u8>,
+    pub request: Vec<u8>,
+    pub responses: Vec<PerChainQueryResponse>,
+}
+
+/// The request id is redacted when it is a request signature.
+impl std::fmt::Debug for QueryResponseLite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueryResponseLite")
+            .field("version", &self.version)
+            .field("request_chain_id", &self.request_chain_id)
+            .field(
+                "request_id",
+                &RequestIdDebug {
+                    request_chain_id: self.request_chain_id,
+                    request_id: &self.request_id,
+                },
+            )
+            .field("request", &self.request)
+            .field("responses", &self.responses)
+            .finish()
+    }
+}
+
+impl QueryResponseLite {
+    pub fn deserialize(data: &[u8]) -> std::result::Result<QueryResponseLite, std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        Self::deserialize_from_reader(&mut rdr)
+    }
+
+    /// Like [`QueryResponseLite::deserialize`], but allows trailing bytes and also returns the
+    /// number of bytes consumed, so a payload embedded in a larger buffer can be parsed in
+    /// place.
+    pub fn deserialize_with_len(
+        data: &[u8],
+    ) -> std::result::Result<(QueryResponseLite, usize), std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        let value = Self::read_from(&mut rdr, &mut Budget::unlimited())?;
+        Ok((value, rdr.position() as usize))
+    }
+
+    /// Parses `data` under the given trailing bytes policy, also returning the number of
+    /// bytes consumed.
+    pub fn deserialize_with(
+        data: &[u8],
+        trailing: TrailingBytes,
+    ) -> std::result::Result<(QueryResponseLite, usize), std::io::Error> {
+        trailing.check(Self::deserialize_with_len(data), data.len())
+    }
+
+    pub fn deserialize_from_reader(
+        rdr: &mut Cursor<&[u8]>,
+    ) -> std::result::Result<QueryResponseLite, std::io::Error> {
+        let response = Self::read_from(rdr, &mut Budget::unlimited())?;
 
         if rdr.position() != rdr.get_ref().len() as u64 {
             return Err(std::io::Error::new(
@@ -55,7 +607,40 @@ impl QueryResponse {
             ));
         }
 
-        Ok(QueryResponse {
+        Ok(response)
+    }
+
+    /// Parses a response without requiring it to end the buffer.
+    fn read_from(
+        rdr: &mut Cursor<&[u8]>,
+        budget: &mut Budget,
+    ) -> std::result::Result<QueryResponseLite, std::io::Error> {
+        let version = rdr.read_u8()?;
+        if version != QueryResponse::RESPONSE_VERSION {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "InvalidResponseVersion",
+            ));
+        }
+
+        // For off chain requests (chainID zero), the requestId is the 65 byte signature. For on chain requests, it is the 32 byte VAA hash.
+        let request_chain_id = rdr.read_u16::<BigEndian>()?;
+        let request_id_len = if request_chain_id == 0 { 65 } else { 32 };
+        let mut request_id = vec![0u8; request_id_len];
+        rdr.read_exact(&mut request_id)?;
+
+        let request_len = rdr.read_u32::<BigEndian>()?;
+        let request = read_bytes(rdr, request_len)?;
+
+        let num_per_chain_responses = budget.read_count(rdr)?;
+
+        let mut responses: Vec<PerChainQueryResponse> =
+            Vec::with_capacity(num_per_chain_responses.into());
+        for _idx in 0..num_per_chain_responses {
+            responses.push(PerChainQueryResponse::read_from(rdr, budget)?)
+        }
+
+        Ok(QueryResponseLite {
             version,
             request_chain_id,
             request_id,
@@ -63,42 +648,233 @@ impl QueryResponse {
             responses,
         })
     }
+
+    /// Serializes the response with the request bytes exactly as they were parsed, giving
+    /// back the bytes the guardians signed.
+    pub fn serialize(&self) -> std::result::Result<Vec<u8>, std::io::Error> {
+        let mut wtr = Vec::new();
+        self.serialize_to_writer(&mut wtr)?;
+        Ok(wtr)
+    }
+
+    pub fn serialize_to_writer<W: Write>(
+        &self,
+        wtr: &mut W,
+    ) -> std::result::Result<(), std::io::Error> {
+        wtr.write_u8(self.version)?;
+        wtr.write_u16::<BigEndian>(self.request_chain_id)?;
+        let request_id_len = if self.request_chain_id == 0 { 65 } else { 32 };
+        if self.request_id.len() != request_id_len {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "InvalidRequestIdLength",
+            ));
+        }
+        wtr.write_all(&self.request_id)?;
+        write_bytes_u32(wtr, &self.request)?;
+        write_u8_len(wtr, self.responses.len())?;
+        for response in &self.responses {
+            response.serialize_to_writer(wtr)?;
+        }
+        Ok(())
+    }
+
+    /// Parses the raw request bytes on demand.
+    pub fn parse_request(&self) -> std::result::Result<QueryRequest, std::io::Error> {
+        QueryRequest::deserialize(&self.request)
+    }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct PerChainQueryResponse {
     pub chain_id: u16,
     pub response: ChainSpecificResponse,
 }
 
 impl PerChainQueryResponse {
+    /// The query type and length prefix are derived from `response` when serializing.
+    pub fn new(chain_id: u16, response: ChainSpecificResponse) -> PerChainQueryResponse {
+        PerChainQueryResponse { chain_id, response }
+    }
+
+    pub fn chain(&self) -> ChainId {
+        ChainId(self.chain_id)
+    }
+
     pub fn deserialize(data: &[u8]) -> std::result::Result<PerChainQueryResponse, std::io::Error> {
         let mut rdr = Cursor::new(data);
         Self::deserialize_from_reader(&mut rdr)
     }
 
+    /// Also returns the number of bytes consumed.
+    pub fn deserialize_with_len(
+        data: &[u8],
+    ) -> std::result::Result<(PerChainQueryResponse, usize), std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        let value = Self::deserialize_from_reader(&mut rdr)?;
+        Ok((value, rdr.position() as usize))
+    }
+
+    /// Parses `data` under the given trailing bytes policy, also returning the number of
+    /// bytes consumed.
+    pub fn deserialize_with(
+        data: &[u8],
+        trailing: TrailingBytes,
+    ) -> std::result::Result<(PerChainQueryResponse, usize), std::io::Error> {
+        trailing.check(Self::deserialize_with_len(data), data.len())
+    }
+
     pub fn deserialize_from_reader(
         rdr: &mut Cursor<&[u8]>,
+    ) -> std::result::Result<PerChainQueryResponse, std::io::Error> {
+        Self::read_from(rdr, &mut Budget::unlimited())
+    }
+
+    pub(crate) fn read_from(
+        rdr: &mut Cursor<&[u8]>,
+        budget: &mut Budget,
     ) -> std::result::Result<PerChainQueryResponse, std::io::Error> {
         let chain_id = rdr.read_u16::<BigEndian>()?;
         let query_type = rdr.read_u8()?;
-        rdr.read_u32::<BigEndian>()?; // skip the response length
+        let response_len = rdr.read_u32::<BigEndian>()?;
+        let start = rdr.position();
+        let response = ChainSpecificResponse::read_from(query_type, rdr, budget)?;
+        check_body_len(rdr, start, response_len, "InvalidResponseLength")?;
+
+        Ok(PerChainQueryResponse { chain_id, response })
+    }
+
+    pub fn serialize(&self) -> std::result::Result<Vec<u8>, std::io::Error> {
+        let mut wtr = Vec::new();
+        self.serialize_to_writer(&mut wtr)?;
+        Ok(wtr)
+    }
+
+    pub fn serialize_to_writer<W: Write>(
+        &self,
+        wtr: &mut W,
+    ) -> std::result::Result<(), std::io::Error> {
+        wtr.write_u16::<BigEndian>(self.chain_id)?;
+        wtr.write_u8(self.response.query_type())?;
+        write_bytes_u32(wtr, &self.response.serialize()?)
+    }
+
+    /// Compares this response with an earlier one for the same chain, failing with
+    /// `BlockNumberRegressed` if the block went backwards or `BlockHashMismatch` if the
+    /// same block number came back with a different hash. Either indicates a reorg or a
+    /// misbehaving guardian.
+    pub fn check_continuity(
+        &self,
+        previous: &PerChainQueryResponse,
+    ) -> std::result::Result<(), std::io::Error> {
+        if self.chain_id != previous.chain_id {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "ChainIdMismatch",
+            ));
+        }
+        let block_number = self.response.block_number();
+        let previous_block_number = previous.response.block_number();
+        if block_number < previous_block_number {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "BlockNumberRegressed",
+            ));
+        }
+        if block_number == previous_block_number
+            && self.response.block_hash() != previous.response.block_hash()
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "BlockHashMismatch",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum ChainSpecificResponse {
+    EthCallQueryResponse(EthCallQueryResponse),
+    EthCallByTimestampQueryResponse(EthCallByTimestampQueryResponse),
+    EthCallWithFinalityQueryResponse(EthCallWithFinalityQueryResponse),
+    SolanaAccountQueryResponse(SolanaAccountQueryResponse),
+    #[cfg(feature = "experimental")]
+    EthCallWithOverridesQueryResponse(EthCallWithOverridesQueryResponse),
+    #[cfg(feature = "experimental")]
+    NearViewCallQueryResponse(NearViewCallQueryResponse),
+    #[cfg(feature = "experimental")]
+    NearViewAccountQueryResponse(NearViewAccountQueryResponse),
+}
+
+impl ChainSpecificResponse {
+    pub fn deserialize(
+        query_type: u8,
+        data: &[u8],
+    ) -> std::result::Result<ChainSpecificResponse, std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        Self::deserialize_from_reader(query_type, &mut rdr)
+    }
+
+    /// Also returns the number of bytes consumed.
+    pub fn deserialize_with_len(
+        query_type: u8,
+        data: &[u8],
+    ) -> std::result::Result<(ChainSpecificResponse, usize), std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        let value = Self::deserialize_from_reader(query_type, &mut rdr)?;
+        Ok((value, rdr.position() as usize))
+    }
+
+    /// Parses the body of a per chain response, whose layout is selected by `query_type`.
+    pub fn deserialize_from_reader(
+        query_type: u8,
+        rdr: &mut Cursor<&[u8]>,
+    ) -> std::result::Result<ChainSpecificResponse, std::io::Error> {
+        Self::read_from(query_type, rdr, &mut Budget::unlimited())
+    }
+
+    pub(crate) fn read_from(
+        query_type: u8,
+        rdr: &mut Cursor<&[u8]>,
+        budget: &mut Budget,
+    ) -> std::result::Result<ChainSpecificResponse, std::io::Error> {
+        // Types this crate assigned itself, which the guardian does not serve.
+        #[cfg(feature = "experimental")]
+        if query_type == 6 {
+            return Ok(ChainSpecificResponse::EthCallWithOverridesQueryResponse(
+                EthCallWithOverridesQueryResponse::read_from(rdr, budget)?,
+            ));
+        } else if query_type == 7 {
+            return Ok(ChainSpecificResponse::NearViewCallQueryResponse(
+                NearViewCallQueryResponse::read_from(rdr, budget)?,
+            ));
+        } else if query_type == 8 {
+            return Ok(ChainSpecificResponse::NearViewAccountQueryResponse(
+                NearViewAccountQueryResponse::read_from(rdr, budget)?,
+            ));
+        }
 
         let response: ChainSpecificResponse;
         if query_type == 1 {
             response = ChainSpecificResponse::EthCallQueryResponse(
-                EthCallQueryResponse::deserialize_from_reader(rdr)?,
+                EthCallQueryResponse::read_from(rdr, budget)?,
             );
         } else if query_type == 2 {
             response = ChainSpecificResponse::EthCallByTimestampQueryResponse(
-                EthCallByTimestampQueryResponse::deserialize_from_reader(rdr)?,
+                EthCallByTimestampQueryResponse::read_from(rdr, budget)?,
             );
         } else if query_type == 3 {
             response = ChainSpecificResponse::EthCallWithFinalityQueryResponse(
-                EthCallWithFinalityQueryResponse::deserialize_from_reader(rdr)?,
+                EthCallWithFinalityQueryResponse::read_from(rdr, budget)?,
             );
         } else if query_type == 4 {
             response = ChainSpecificResponse::SolanaAccountQueryResponse(
-                SolanaAccountQueryResponse::deserialize_from_reader(rdr)?,
+                SolanaAccountQueryResponse::read_from(rdr, budget)?,
             );
         } else {
             return Err(std::io::Error::new(
@@ -107,17 +883,103 @@ impl PerChainQueryResponse {
             ));
         }
 
-        Ok(PerChainQueryResponse { chain_id, response })
+        Ok(response)
+    }
+
+    /// The block the results were read at. For Solana the number is the slot, and for
+    /// eth_call_by_timestamp it is the target block.
+    pub fn block_info(&self) -> BlockInfo {
+        match self {
+            ChainSpecificResponse::EthCallQueryResponse(response) => response.block_info(),
+            ChainSpecificResponse::EthCallByTimestampQueryResponse(response) => {
+                response.target_block_info()
+            }
+            ChainSpecificResponse::EthCallWithFinalityQueryResponse(response) => {
+                response.block_info()
+            }
+            ChainSpecificResponse::SolanaAccountQueryResponse(response) => BlockInfo {
+                number: response.slot_number,
+                hash: response.block_hash,
+                time: response.block_time,
+            },
+            #[cfg(feature = "experimental")]
+            ChainSpecificResponse::EthCallWithOverridesQueryResponse(response) => {
+                response.block_info()
+            }
+            #[cfg(feature = "experimental")]
+            ChainSpecificResponse::NearViewCallQueryResponse(response) => response.block_info(),
+            #[cfg(feature = "experimental")]
+            ChainSpecificResponse::NearViewAccountQueryResponse(response) => response.block_info(),
+        }
+    }
+
+    /// The block number the results were read at. For Solana this is the slot, and for
+    /// eth_call_by_timestamp it is the target block.
+    pub fn block_number(&self) -> u64 {
+        self.block_info().number
+    }
+
+    /// The time of the block the results were read at, in microseconds since the epoch.
+    pub fn block_time(&self) -> u64 {
+        self.block_info().time
+    }
+
+    /// The hash of the block the results were read at.
+    pub fn block_hash(&self) -> [u8; 32] {
+        self.block_info().hash
+    }
+
+    /// The query type byte used on the wire, as in [`ChainSpecificQuery::query_type`].
+    pub fn query_type(&self) -> u8 {
+        match self {
+            ChainSpecificResponse::EthCallQueryResponse(_) => 1,
+            ChainSpecificResponse::EthCallByTimestampQueryResponse(_) => 2,
+            ChainSpecificResponse::EthCallWithFinalityQueryResponse(_) => 3,
+            ChainSpecificResponse::SolanaAccountQueryResponse(_) => 4,
+            #[cfg(feature = "experimental")]
+            ChainSpecificResponse::EthCallWithOverridesQueryResponse(_) => 6,
+            #[cfg(feature = "experimental")]
+            ChainSpecificResponse::NearViewCallQueryResponse(_) => 7,
+            #[cfg(feature = "experimental")]
+            ChainSpecificResponse::NearViewAccountQueryResponse(_) => 8,
+        }
+    }
+
+    pub fn serialize(&self) -> std::result::Result<Vec<u8>, std::io::Error> {
+        match self {
+            ChainSpecificResponse::EthCallQueryResponse(response) => response.serialize(),
+            ChainSpecificResponse::EthCallByTimestampQueryResponse(response) => {
+                response.serialize()
+            }
+            ChainSpecificResponse::EthCallWithFinalityQueryResponse(response) => {
+                response.serialize()
+            }
+            ChainSpecificResponse::SolanaAccountQueryResponse(response) => response.serialize(),
+            #[cfg(feature = "experimental")]
+            ChainSpecificResponse::EthCallWithOverridesQueryResponse(response) => {
+                response.serialize()
+            }
+            #[cfg(feature = "experimental")]
+            ChainSpecificResponse::NearViewCallQueryResponse(response) => response.serialize(),
+            #[cfg(feature = "experimental")]
+            ChainSpecificResponse::NearViewAccountQueryResponse(response) => response.serialize(),
+        }
     }
 }
 
-pub enum ChainSpecificResponse {
-    EthCallQueryResponse(EthCallQueryResponse),
-    EthCallByTimestampQueryResponse(EthCallByTimestampQueryResponse),
-    EthCallWithFinalityQueryResponse(EthCallWithFinalityQueryResponse),
-    SolanaAccountQueryResponse(SolanaAccountQueryResponse),
+/// A block as reported in a response. `time` is in microseconds since the epoch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct BlockInfo {
+    pub number: u64,
+    pub hash: [u8; 32],
+    pub time: u64,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct EthCallQueryResponse {
     pub block_number: u64,
     pub block_hash: [u8; 32],
@@ -126,24 +988,55 @@ pub struct EthCallQueryResponse {
 }
 
 impl EthCallQueryResponse {
+    pub fn block_info(&self) -> BlockInfo {
+        BlockInfo {
+            number: self.block_number,
+            hash: self.block_hash,
+            time: self.block_time,
+        }
+    }
+
+    /// Pairs each call in `request` with its result, failing if the counts differ.
+    pub fn zip<'a>(
+        &'a self,
+        request: &'a EthCallQueryRequest,
+    ) -> std::result::Result<Vec<(&'a EthCallData, &'a [u8])>, std::io::Error> {
+        zip_results(&request.call_data, &self.results)
+    }
+
     pub fn deserialize(data: &[u8]) -> std::result::Result<EthCallQueryResponse, std::io::Error> {
         let mut rdr = Cursor::new(data);
         Self::deserialize_from_reader(&mut rdr)
     }
 
+    /// Also returns the number of bytes consumed.
+    pub fn deserialize_with_len(
+        data: &[u8],
+    ) -> std::result::Result<(EthCallQueryResponse, usize), std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        let value = Self::deserialize_from_reader(&mut rdr)?;
+        Ok((value, rdr.position() as usize))
+    }
+
     pub fn deserialize_from_reader(
         rdr: &mut Cursor<&[u8]>,
+    ) -> std::result::Result<EthCallQueryResponse, std::io::Error> {
+        Self::read_from(rdr, &mut Budget::unlimited())
+    }
+
+    pub(crate) fn read_from(
+        rdr: &mut Cursor<&[u8]>,
+        budget: &mut Budget,
     ) -> std::result::Result<EthCallQueryResponse, std::io::Error> {
         let block_number = rdr.read_u64::<BigEndian>()?;
         let mut block_hash = [0u8; 32];
         rdr.read_exact(&mut block_hash)?;
         let block_time = rdr.read_u64::<BigEndian>()?;
-        let results_len = rdr.read_u8()?;
+        let results_len = budget.read_count(rdr)?;
         let mut results = Vec::with_capacity(results_len.into());
         for _ in 0..results_len {
             let result_len = rdr.read_u32::<BigEndian>()?;
-            let mut result = vec![0u8; result_len.try_into().unwrap()];
-            rdr.read_exact(&mut result)?;
+            let result = read_bytes(rdr, result_len)?;
             results.push(result)
         }
         Ok(EthCallQueryResponse {
@@ -153,8 +1046,31 @@ impl EthCallQueryResponse {
             results,
         })
     }
+
+    pub fn serialize(&self) -> std::result::Result<Vec<u8>, std::io::Error> {
+        let mut wtr = Vec::new();
+        self.serialize_to_writer(&mut wtr)?;
+        Ok(wtr)
+    }
+
+    pub fn serialize_to_writer<W: Write>(
+        &self,
+        wtr: &mut W,
+    ) -> std::result::Result<(), std::io::Error> {
+        wtr.write_u64::<BigEndian>(self.block_number)?;
+        wtr.write_all(&self.block_hash)?;
+        wtr.write_u64::<BigEndian>(self.block_time)?;
+        write_u8_len(wtr, self.results.len())?;
+        for result in &self.results {
+            write_bytes_u32(wtr, result)?;
+        }
+        Ok(())
+    }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct EthCallByTimestampQueryResponse {
     pub target_block_number: u64,
     pub target_block_hash: [u8; 32],
@@ -166,6 +1082,32 @@ pub struct EthCallByTimestampQueryResponse {
 }
 
 impl EthCallByTimestampQueryResponse {
+    /// The last block at or before the target timestamp.
+    pub fn target_block_info(&self) -> BlockInfo {
+        BlockInfo {
+            number: self.target_block_number,
+            hash: self.target_block_hash,
+            time: self.target_block_time,
+        }
+    }
+
+    /// The block immediately after the target block.
+    pub fn following_block_info(&self) -> BlockInfo {
+        BlockInfo {
+            number: self.following_block_number,
+            hash: self.following_block_hash,
+            time: self.following_block_time,
+        }
+    }
+
+    /// Pairs each call in `request` with its result, failing if the counts differ.
+    pub fn zip<'a>(
+        &'a self,
+        request: &'a EthCallByTimestampQueryRequest,
+    ) -> std::result::Result<Vec<(&'a EthCallData, &'a [u8])>, std::io::Error> {
+        zip_results(&request.call_data, &self.results)
+    }
+
     pub fn deserialize(
         data: &[u8],
     ) -> std::result::Result<EthCallByTimestampQueryResponse, std::io::Error> {
@@ -173,8 +1115,24 @@ impl EthCallByTimestampQueryResponse {
         Self::deserialize_from_reader(&mut rdr)
     }
 
+    /// Also returns the number of bytes consumed.
+    pub fn deserialize_with_len(
+        data: &[u8],
+    ) -> std::result::Result<(EthCallByTimestampQueryResponse, usize), std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        let value = Self::deserialize_from_reader(&mut rdr)?;
+        Ok((value, rdr.position() as usize))
+    }
+
     pub fn deserialize_from_reader(
         rdr: &mut Cursor<&[u8]>,
+    ) -> std::result::Result<EthCallByTimestampQueryResponse, std::io::Error> {
+        Self::read_from(rdr, &mut Budget::unlimited())
+    }
+
+    pub(crate) fn read_from(
+        rdr: &mut Cursor<&[u8]>,
+        budget: &mut Budget,
     ) -> std::result::Result<EthCallByTimestampQueryResponse, std::io::Error> {
         let target_block_number = rdr.read_u64::<BigEndian>()?;
         let mut target_block_hash = [0u8; 32];
@@ -184,12 +1142,11 @@ impl EthCallByTimestampQueryResponse {
         let mut following_block_hash = [0u8; 32];
         rdr.read_exact(&mut following_block_hash)?;
         let following_block_time = rdr.read_u64::<BigEndian>()?;
-        let results_len = rdr.read_u8()?;
+        let results_len = budget.read_count(rdr)?;
         let mut results = Vec::with_capacity(results_len.into());
         for _ in 0..results_len {
             let result_len = rdr.read_u32::<BigEndian>()?;
-            let mut result = vec![0u8; result_len.try_into().unwrap()];
-            rdr.read_exact(&mut result)?;
+            let result = read_bytes(rdr, result_len)?;
             results.push(result)
         }
         Ok(EthCallByTimestampQueryResponse {
@@ -202,8 +1159,34 @@ impl EthCallByTimestampQueryResponse {
             results,
         })
     }
+
+    pub fn serialize(&self) -> std::result::Result<Vec<u8>, std::io::Error> {
+        let mut wtr = Vec::new();
+        self.serialize_to_writer(&mut wtr)?;
+        Ok(wtr)
+    }
+
+    pub fn serialize_to_writer<W: Write>(
+        &self,
+        wtr: &mut W,
+    ) -> std::result::Result<(), std::io::Error> {
+        wtr.write_u64::<BigEndian>(self.target_block_number)?;
+        wtr.write_all(&self.target_block_hash)?;
+        wtr.write_u64::<BigEndian>(self.target_block_time)?;
+        wtr.write_u64::<BigEndian>(self.following_block_number)?;
+        wtr.write_all(&self.following_block_hash)?;
+        wtr.write_u64::<BigEndian>(self.following_block_time)?;
+        write_u8_len(wtr, self.results.len())?;
+        for result in &self.results {
+            write_bytes_u32(wtr, result)?;
+        }
+        Ok(())
+    }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct EthCallWithFinalityQueryResponse {
     pub block_number: u64,
     pub block_hash: [u8; 32],
@@ -212,6 +1195,22 @@ pub struct EthCallWithFinalityQueryResponse {
 }
 
 impl EthCallWithFinalityQueryResponse {
+    pub fn block_info(&self) -> BlockInfo {
+        BlockInfo {
+            number: self.block_number,
+            hash: self.block_hash,
+            time: self.block_time,
+        }
+    }
+
+    /// Pairs each call in `request` with its result, failing if the counts differ.
+    pub fn zip<'a>(
+        &'a self,
+        request: &'a EthCallWithFinalityQueryRequest,
+    ) -> std::result::Result<Vec<(&'a EthCallData, &'a [u8])>, std::io::Error> {
+        zip_results(&request.call_data, &self.results)
+    }
+
     pub fn deserialize(
         data: &[u8],
     ) -> std::result::Result<EthCallWithFinalityQueryResponse, std::io::Error> {
@@ -219,15 +1218,31 @@ impl EthCallWithFinalityQueryResponse {
         Self::deserialize_from_reader(&mut rdr)
     }
 
+    /// Also returns the number of bytes consumed.
+    pub fn deserialize_with_len(
+        data: &[u8],
+    ) -> std::result::Result<(EthCallWithFinalityQueryResponse, usize), std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        let value = Self::deserialize_from_reader(&mut rdr)?;
+        Ok((value, rdr.position() as usize))
+    }
+
     pub fn deserialize_from_reader(
         rdr: &mut Cursor<&[u8]>,
+    ) -> std::result::Result<EthCallWithFinalityQueryResponse, std::io::Error> {
+        Self::read_from(rdr, &mut Budget::unlimited())
+    }
+
+    pub(crate) fn read_from(
+        rdr: &mut Cursor<&[u8]>,
+        budget: &mut Budget,
     ) -> std::result::Result<EthCallWithFinalityQueryResponse, std::io::Error> {
         let EthCallQueryResponse {
             block_number,
             block_hash,
             block_time,
             results,
-        } = EthCallQueryResponse::deserialize_from_reader(rdr)?;
+        } = EthCallQueryResponse::read_from(rdr, budget)?;
         Ok(EthCallWithFinalityQueryResponse {
             block_number,
             block_hash,
@@ -235,8 +1250,369 @@ impl EthCallWithFinalityQueryResponse {
             results,
         })
     }
+
+    pub fn serialize(&self) -> std::result::Result<Vec<u8>, std::io::Error> {
+        let mut wtr = Vec::new();
+        self.serialize_to_writer(&mut wtr)?;
+        Ok(wtr)
+    }
+
+    pub fn serialize_to_writer<W: Write>(
+        &self,
+        wtr: &mut W,
+    ) -> std::result::Result<(), std::io::Error> {
+        wtr.write_u64::<BigEndian>(self.block_number)?;
+        wtr.write_all(&self.block_hash)?;
+        wtr.write_u64::<BigEndian>(self.block_time)?;
+        write_u8_len(wtr, self.results.len())?;
+        for result in &self.results {
+            write_bytes_u32(wtr, result)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "experimental")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct EthCallWithOverridesQueryResponse {
+    pub block_number: u64,
+    pub block_hash: [u8; 32],
+    pub block_time: u64,
+    pub results: Vec<Vec<u8>>,
+}
+
+#[cfg(feature = "experimental")]
+impl EthCallWithOverridesQueryResponse {
+    pub fn block_info(&self) -> BlockInfo {
+        BlockInfo {
+            number: self.block_number,
+            hash: self.block_hash,
+            time: self.block_time,
+        }
+    }
+
+    /// Pairs each call in `request` with its result, failing if the counts differ.
+    pub fn zip<'a>(
+        &'a self,
+        request: &'a EthCallWithOverridesQueryRequest,
+    ) -> std::result::Result<Vec<(&'a EthCallData, &'a [u8])>, std::io::Error> {
+        zip_results(&request.call_data, &self.results)
+    }
+
+    pub fn deserialize(
+        data: &[u8],
+    ) -> std::result::Result<EthCallWithOverridesQueryResponse, std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        Self::deserialize_from_reader(&mut rdr)
+    }
+
+    /// Also returns the number of bytes consumed.
+    pub fn deserialize_with_len(
+        data: &[u8],
+    ) -> std::result::Result<(EthCallWithOverridesQueryResponse, usize), std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        let value = Self::deserialize_from_reader(&mut rdr)?;
+        Ok((value, rdr.position() as usize))
+    }
+
+    pub fn deserialize_from_reader(
+        rdr: &mut Cursor<&[u8]>,
+    ) -> std::result::Result<EthCallWithOverridesQueryResponse, std::io::Error> {
+        Self::read_from(rdr, &mut Budget::unlimited())
+    }
+
+    pub(crate) fn read_from(
+        rdr: &mut Cursor<&[u8]>,
+        budget: &mut Budget,
+    ) -> std::result::Result<EthCallWithOverridesQueryResponse, std::io::Error> {
+        let EthCallQueryResponse {
+            block_number,
+            block_hash,
+            block_time,
+            results,
+        } = EthCallQueryResponse::read_from(rdr, budget)?;
+        Ok(EthCallWithOverridesQueryResponse {
+            block_number,
+            block_hash,
+            block_time,
+            results,
+        })
+    }
+
+    pub fn serialize(&self) -> std::result::Result<Vec<u8>, std::io::Error> {
+        let mut wtr = Vec::new();
+        self.serialize_to_writer(&mut wtr)?;
+        Ok(wtr)
+    }
+
+    pub fn serialize_to_writer<W: Write>(
+        &self,
+        wtr: &mut W,
+    ) -> std::result::Result<(), std::io::Error> {
+        wtr.write_u64::<BigEndian>(self.block_number)?;
+        wtr.write_all(&self.block_hash)?;
+        wtr.write_u64::<BigEndian>(self.block_time)?;
+        write_u8_len(wtr, self.results.len())?;
+        for result in &self.results {
+            write_bytes_u32(wtr, result)?;
+        }
+        Ok(())
+    }
+}
+
+/// Has the layout of [`EthCallQueryResponse`]. `block_time` is in microseconds like every
+/// other response, not NEAR's nanoseconds.
+#[cfg(feature = "experimental")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct NearViewCallQueryResponse {
+    pub block_height: u64,
+    pub block_hash: [u8; 32],
+    pub block_time: u64,
+    pub results: Vec<Vec<u8>>,
+}
+
+#[cfg(feature = "experimental")]
+impl NearViewCallQueryResponse {
+    pub fn block_info(&self) -> BlockInfo {
+        BlockInfo {
+            number: self.block_height,
+            hash: self.block_hash,
+            time: self.block_time,
+        }
+    }
+
+    /// Pairs each call in `request` with its result, failing if the counts differ.
+    pub fn zip<'a>(
+        &'a self,
+        request: &'a NearViewCallQueryRequest,
+    ) -> std::result::Result<Vec<(&'a NearViewCall, &'a [u8])>, std::io::Error> {
+        if request.calls.len() != self.results.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "ResultCountMismatch",
+            ));
+        }
+        Ok(request
+            .calls
+            .iter()
+            .zip(&self.results)
+            .map(|(call, result)| (call, result.as_slice()))
+            .collect())
+    }
+
+    pub fn deserialize(
+        data: &[u8],
+    ) -> std::result::Result<NearViewCallQueryResponse, std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        Self::deserialize_from_reader(&mut rdr)
+    }
+
+    /// Also returns the number of bytes consumed.
+    pub fn deserialize_with_len(
+        data: &[u8],
+    ) -> std::result::Result<(NearViewCallQueryResponse, usize), std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        let value = Self::deserialize_from_reader(&mut rdr)?;
+        Ok((value, rdr.position() as usize))
+    }
+
+    pub fn deserialize_from_reader(
+        rdr: &mut Cursor<&[u8]>,
+    ) -> std::result::Result<NearViewCallQueryResponse, std::io::Error> {
+        Self::read_from(rdr, &mut Budget::unlimited())
+    }
+
+    pub(crate) fn read_from(
+        rdr: &mut Cursor<&[u8]>,
+        budget: &mut Budget,
+    ) -> std::result::Result<NearViewCallQueryResponse, std::io::Error> {
+        let EthCallQueryResponse {
+            block_number,
+            block_hash,
+            block_time,
+            results,
+        } = EthCallQueryResponse::read_from(rdr, budget)?;
+        Ok(NearViewCallQueryResponse {
+            block_height: block_number,
+            block_hash,
+            block_time,
+            results,
+        })
+    }
+
+    pub fn serialize(&self) -> std::result::Result<Vec<u8>, std::io::Error> {
+        let mut wtr = Vec::new();
+        self.serialize_to_writer(&mut wtr)?;
+        Ok(wtr)
+    }
+
+    pub fn serialize_to_writer<W: Write>(
+        &self,
+        wtr: &mut W,
+    ) -> std::result::Result<(), std::io::Error> {
+        wtr.write_u64::<BigEndian>(self.block_height)?;
+        wtr.write_all(&self.block_hash)?;
+        wtr.write_u64::<BigEndian>(self.block_time)?;
+        write_u8_len(wtr, self.results.len())?;
+        for result in &self.results {
+            write_bytes_u32(wtr, result)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "experimental")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct NearViewAccountQueryResponse {
+    pub block_height: u64,
+    pub block_hash: [u8; 32],
+    pub block_time: u64,
+    pub results: Vec<NearAccountResult>,
+}
+
+/// The state of one account, as returned by NEAR's `view_account` query.
+#[cfg(feature = "experimental")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct NearAccountResult {
+    /// Liquid balance, in yoctoNEAR.
+    pub amount: u128,
+    /// Balance locked for staking, in yoctoNEAR.
+    pub locked: u128,
+    /// Hash of the deployed contract, or zero if there is none.
+    pub code_hash: [u8; 32],
+    pub storage_usage: u64,
+}
+
+#[cfg(feature = "experimental")]
+impl NearAccountResult {
+    /// Serialized size of a result.
+    pub const LEN: usize = 16 + 16 + 32 + 8;
+
+    /// The result as it appears on the wire.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut wtr = Vec::with_capacity(Self::LEN);
+        wtr.extend_from_slice(&self.amount.to_be_bytes());
+        wtr.extend_from_slice(&self.locked.to_be_bytes());
+        wtr.extend_from_slice(&self.code_hash);
+        wtr.extend_from_slice(&self.storage_usage.to_be_bytes());
+        wtr
+    }
+}
+
+#[cfg(feature = "experimental")]
+impl NearViewAccountQueryResponse {
+    pub fn block_info(&self) -> BlockInfo {
+        BlockInfo {
+            number: self.block_height,
+            hash: self.block_hash,
+            time: self.block_time,
+        }
+    }
+
+    /// Pairs each account id in `request` with its result, failing if the counts differ.
+    pub fn zip<'a>(
+        &'a self,
+        request: &'a NearViewAccountQueryRequest,
+    ) -> std::result::Result<Vec<(&'a str, &'a NearAccountResult)>, std::io::Error> {
+        if request.account_ids.len() != self.results.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "ResultCountMismatch",
+            ));
+        }
+        Ok(request
+            .account_ids
+            .iter()
+            .map(String::as_str)
+            .zip(&self.results)
+            .collect())
+    }
+
+    pub fn deserialize(
+        data: &[u8],
+    ) -> std::result::Result<NearViewAccountQueryResponse, std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        Self::deserialize_from_reader(&mut rdr)
+    }
+
+    /// Also returns the number of bytes consumed.
+    pub fn deserialize_with_len(
+        data: &[u8],
+    ) -> std::result::Result<(NearViewAccountQueryResponse, usize), std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        let value = Self::deserialize_from_reader(&mut rdr)?;
+        Ok((value, rdr.position() as usize))
+    }
+
+    pub fn deserialize_from_reader(
+        rdr: &mut Cursor<&[u8]>,
+    ) -> std::result::Result<NearViewAccountQueryResponse, std::io::Error> {
+        Self::read_from(rdr, &mut Budget::unlimited())
+    }
+
+    pub(crate) fn read_from(
+        rdr: &mut Cursor<&[u8]>,
+        budget: &mut Budget,
+    ) -> std::result::Result<NearViewAccountQueryResponse, std::io::Error> {
+        let block_height = rdr.read_u64::<BigEndian>()?;
+        let mut block_hash = [0u8; 32];
+        rdr.read_exact(&mut block_hash)?;
+        let block_time = rdr.read_u64::<BigEndian>()?;
+        let results_len = budget.read_count(rdr)?;
+        let mut results = Vec::with_capacity(results_len.into());
+        for _ in 0..results_len {
+            let amount = rdr.read_u128::<BigEndian>()?;
+            let locked = rdr.read_u128::<BigEndian>()?;
+            let mut code_hash = [0u8; 32];
+            rdr.read_exact(&mut code_hash)?;
+            let storage_usage = rdr.read_u64::<BigEndian>()?;
+            results.push(NearAccountResult {
+                amount,
+                locked,
+                code_hash,
+                storage_usage,
+            });
+        }
+        Ok(NearViewAccountQueryResponse {
+            block_height,
+            block_hash,
+            block_time,
+            results,
+        })
+    }
+
+    pub fn serialize(&self) -> std::result::Result<Vec<u8>, std::io::Error> {
+        let mut wtr = Vec::new();
+        self.serialize_to_writer(&mut wtr)?;
+        Ok(wtr)
+    }
+
+    pub fn serialize_to_writer<W: Write>(
+        &self,
+        wtr: &mut W,
+    ) -> std::result::Result<(), std::io::Error> {
+        wtr.write_u64::<BigEndian>(self.block_height)?;
+        wtr.write_all(&self.block_hash)?;
+        wtr.write_u64::<BigEndian>(self.block_time)?;
+        write_u8_len(wtr, self.results.len())?;
+        for result in &self.results {
+            wtr.write_all(&result.serialize())?;
+        }
+        Ok(())
+    }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SolanaAccountQueryResponse {
     pub slot_number: u64,
     pub block_time: u64,
@@ -244,6 +1620,9 @@ pub struct SolanaAccountQueryResponse {
     pub results: Vec<SolanaAccountResult>,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SolanaAccountResult {
     pub lamports: u64,
     pub rent_epoch: u64,
@@ -252,7 +1631,69 @@ pub struct SolanaAccountResult {
     pub data: Vec<u8>,
 }
 
+impl SolanaAccountResult {
+    /// The byte range of the full account data that `data` covers, given the request
+    /// this result answers. The end saturates at `u64::MAX` for offsets near it.
+    pub fn data_range(&self, request: &SolanaAccountQueryRequest) -> std::ops::Range<u64> {
+        let start = if request.has_data_slice() {
+            request.data_slice_offset
+        } else {
+            0
+        };
+        start..start.saturating_add(self.data.len() as u64)
+    }
+}
+
 impl SolanaAccountQueryResponse {
+    /// Pairs each account in `request` with its result, failing if the counts differ.
+    pub fn zip<'a>(
+        &'a self,
+        request: &'a SolanaAccountQueryRequest,
+    ) -> std::result::Result<Vec<(&'a [u8; 32], &'a SolanaAccountResult)>, std::io::Error> {
+        if request.accounts.len() != self.results.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "ResultCountMismatch",
+            ));
+        }
+        Ok(request.accounts.iter().zip(&self.results).collect())
+    }
+
+    /// Checks the freshness guarantee of `min_context_slot`: the accounts must have been
+    /// read at or after the slot the request asked for.
+    pub fn check_min_context_slot(
+        &self,
+        request: &SolanaAccountQueryRequest,
+    ) -> std::result::Result<(), std::io::Error> {
+        if self.slot_number < request.min_context_slot {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "SlotBelowMinContextSlot",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Fails with `AccountDataTooLong` if `request` has a data slice and any result holds
+    /// more data than the slice length.
+    pub fn check_data_slice(
+        &self,
+        request: &SolanaAccountQueryRequest,
+    ) -> std::result::Result<(), std::io::Error> {
+        if request.has_data_slice()
+            && self
+                .results
+                .iter()
+                .any(|result| result.data.len() as u64 > request.data_slice_length)
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "AccountDataTooLong",
+            ));
+        }
+        Ok(())
+    }
+
     pub fn deserialize(
         data: &[u8],
     ) -> std::result::Result<SolanaAccountQueryResponse, std::io::Error> {
@@ -260,14 +1701,30 @@ impl SolanaAccountQueryResponse {
         Self::deserialize_from_reader(&mut rdr)
     }
 
+    /// Also returns the number of bytes consumed.
+    pub fn deserialize_with_len(
+        data: &[u8],
+    ) -> std::result::Result<(SolanaAccountQueryResponse, usize), std::io::Error> {
+        let mut rdr = Cursor::new(data);
+        let value = Self::deserialize_from_reader(&mut rdr)?;
+        Ok((value, rdr.position() as usize))
+    }
+
     pub fn deserialize_from_reader(
         rdr: &mut Cursor<&[u8]>,
+    ) -> std::result::Result<SolanaAccountQueryResponse, std::io::Error> {
+        Self::read_from(rdr, &mut Budget::unlimited())
+    }
+
+    pub(crate) fn read_from(
+        rdr: &mut Cursor<&[u8]>,
+        budget: &mut Budget,
     ) -> std::result::Result<SolanaAccountQueryResponse, std::io::Error> {
         let slot_number = rdr.read_u64::<BigEndian>()?;
         let block_time = rdr.read_u64::<BigEndian>()?;
         let mut block_hash = [0u8; 32];
         rdr.read_exact(&mut block_hash)?;
-        let results_len = rdr.read_u8()?;
+        let results_len = budget.read_count(rdr)?;
         let mut results = Vec::with_capacity(results_len.into());
         for _ in 0..results_len {
             let lamports = rdr.read_u64::<BigEndian>()?;
@@ -277,8 +1734,7 @@ impl SolanaAccountQueryResponse {
             let mut owner = [0u8; 32];
             rdr.read_exact(&mut owner)?;
             let data_len = rdr.read_u32::<BigEndian>()?;
-            let mut data = vec![0u8; data_len.try_into().unwrap()];
-            rdr.read_exact(&mut data)?;
+            let data = read_bytes(rdr, data_len)?;
             results.push(SolanaAccountResult {
                 lamports,
                 rent_epoch,
@@ -294,4 +1750,87 @@ impl SolanaAccountQueryResponse {
             results,
         })
     }
+
+    pub fn serialize(&self) -> std::result::Result<Vec<u8>, std::io::Error> {
+        let mut wtr = Vec::new();
+        self.serialize_to_writer(&mut wtr)?;
+        Ok(wtr)
+    }
+
+    pub fn serialize_to_writer<W: Write>(
+        &self,
+        wtr: &mut W,
+    ) -> std::result::Result<(), std::io::Error> {
+        wtr.write_u64::<BigEndian>(self.slot_number)?;
+        wtr.write_u64::<BigEndian>(self.block_time)?;
+        wtr.write_all(&self.block_hash)?;
+        write_u8_len(wtr, self.results.len())?;
+        for result in &self.results {
+            wtr.write_u64::<BigEndian>(result.lamports)?;
+            wtr.write_u64::<BigEndian>(result.rent_epoch)?;
+            wtr.write_u8(result.executable.into())?;
+            wtr.write_all(&result.owner)?;
+            write_bytes_u32(wtr, &result.data)?;
+        }
+        Ok(())
+    }
+}
+
+fn zip_results<'a>(
+    call_data: &'a [EthCallData],
+    results: &'a [Vec<u8>],
+) -> std::result::Result<Vec<(&'a EthCallData, &'a [u8])>, std::io::Error> {
+    if call_data.len() != results.len() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "ResultCountMismatch",
+        ));
+    }
+    Ok(call_data
+        .iter()
+        .zip(results)
+        .map(|(call, result)| (call, result.as_slice()))
+        .collect())
+}
+
+fn expected_result_count(query: &ChainSpecificQuery) -> usize {
+    match query {
+        ChainSpecificQuery::EthCallQueryRequest(query) => query.call_data.len(),
+        ChainSpecificQuery::EthCallByTimestampQueryRequest(query) => query.call_data.len(),
+        ChainSpecificQuery::EthCallWithFinalityQueryRequest(query) => query.call_data.len(),
+        ChainSpecificQuery::SolanaAccountQueryRequest(query) => query.accounts.len(),
+        #[cfg(feature = "experimental")]
+        ChainSpecificQuery::EthCallWithOverridesQueryRequest(query) => query.call_data.len(),
+        #[cfg(feature = "experimental")]
+        ChainSpecificQuery::NearViewCallQueryRequest(query) => query.calls.len(),
+        #[cfg(feature = "experimental")]
+        ChainSpecificQuery::NearViewAccountQueryRequest(query) => query.account_ids.len(),
+    }
+}
+
+fn result_count(response: &ChainSpecificResponse) -> usize {
+    match response {
+        ChainSpecificResponse::EthCallQueryResponse(response) => response.results.len(),
+        ChainSpecificResponse::EthCallByTimestampQueryResponse(response) => response.results.len(),
+        ChainSpecificResponse::EthCallWithFinalityQueryResponse(response) => response.results.len(),
+        ChainSpecificResponse::SolanaAccountQueryResponse(response) => response.results.len(),
+        #[cfg(feature = "experimental")]
+        ChainSpecificResponse::EthCallWithOverridesQueryResponse(response) => {
+            response.results.len()
+        }
+        #[cfg(feature = "experimental")]
+        ChainSpecificResponse::NearViewCallQueryResponse(response) => response.results.len(),
+        #[cfg(feature = "experimental")]
+        ChainSpecificResponse::NearViewAccountQueryResponse(response) => response.results.len(),
+    }
+}
+
+/// Advances past `len` bytes, failing if fewer remain.
+fn skip(rdr: &mut Cursor<&[u8]>, len: u64) -> std::result::Result<(), std::io::Error> {
+    let end = rdr.position() + len;
+    if end > rdr.get_ref().len() as u64 {
+        return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof));
+    }
+    rdr.set_position(end);
+    Ok(())
 }
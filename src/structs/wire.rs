@@ -0,0 +1,121 @@
+use byteorder::{BigEndian, WriteBytesExt};
+use std::io::{Cursor, Read, Write};
+
+/// Writes a one byte element count, failing if `len` does not fit.
+pub(crate) fn write_u8_len<W: Write>(
+    wtr: &mut W,
+    len: usize,
+) -> std::result::Result<(), std::io::Error> {
+    let len: u8 = len
+        .try_into()
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, "TooManyEntries"))?;
+    wtr.write_u8(len)
+}
+
+/// Writes `data` prefixed by its four byte big endian length.
+pub(crate) fn write_bytes_u32<W: Write>(
+    wtr: &mut W,
+    data: &[u8],
+) -> std::result::Result<(), std::io::Error> {
+    let len: u32 = data
+        .len()
+        .try_into()
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, "FieldTooLong"))?;
+    wtr.write_u32::<BigEndian>(len)?;
+    wtr.write_all(data)
+}
+
+/// Reads `len` bytes, failing before allocating if fewer remain, so a corrupt length
+/// prefix cannot force a large allocation.
+pub(crate) fn read_bytes(
+    rdr: &mut Cursor<&[u8]>,
+    len: u32,
+) -> std::result::Result<Vec<u8>, std::io::Error> {
+    let remaining = (rdr.get_ref().len() as u64).saturating_sub(rdr.position());
+    if u64::from(len) > remaining {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            "UnexpectedEof",
+        ));
+    }
+    let mut buf = vec![0u8; len as usize];
+    rdr.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+/// Checks that a body parsed in place from `rdr`, starting at `start`, was exactly as long
+/// as its length prefix `len` said, failing with `err` otherwise.
+pub(crate) fn check_body_len(
+    rdr: &Cursor<&[u8]>,
+    start: u64,
+    len: u32,
+    err: &'static str,
+) -> std::result::Result<(), std::io::Error> {
+    if rdr.position() - start != u64::from(len) {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, err));
+    }
+    Ok(())
+}
+
+/// Decodes a hex string, with or without a leading `0x`.
+pub(crate) fn decode_hex(s: &str) -> std::result::Result<Vec<u8>, std::io::Error> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    hex::decode(s).map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "InvalidHex"))
+}
+
+/// Strict mode check for string fields: ASCII only, with no NUL bytes.
+pub(crate) fn check_string_field(
+    s: &str,
+    err: &'static str,
+) -> std::result::Result<(), std::io::Error> {
+    if !s.is_ascii() || s.contains('\0') {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, err));
+    }
+    Ok(())
+}
+
+/// What to do with bytes left over after a value has been parsed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TrailingBytes {
+    /// Fail with "InvalidPayloadLength", as `deserialize` does.
+    #[default]
+    Reject,
+    /// Ignore them, so a value embedded in a larger buffer can be parsed in place.
+    Allow,
+}
+
+impl TrailingBytes {
+    /// Applies the policy to the result of a `deserialize_with_len` call over `data_len`
+    /// bytes.
+    pub(crate) fn check<T>(
+        self,
+        parsed: std::result::Result<(T, usize), std::io::Error>,
+        data_len: usize,
+    ) -> std::result::Result<(T, usize), std::io::Error> {
+        let (value, len) = parsed?;
+        if self == TrailingBytes::Reject && len != data_len {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "InvalidPayloadLength",
+            ));
+        }
+        Ok((value, len))
+    }
+}
+
+/// Debug output of a request id: transaction hashes as hex, but off chain request
+/// signatures (request chain id 0) only by length.
+pub(crate) struct RequestIdDebug<'a> {
+    pub request_chain_id: u16,
+    pub request_id: &'a [u8],
+}
+
+impl std::fmt::Debug for RequestIdDebug<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.request_chain_id == 0 {
+            write!(f, "<{} byte signature redacted>", self.request_id.len())
+        } else {
+            write!(f, "0x{}", hex::encode(self.request_id))
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Calls for commonly attested Wormhole core bridge and token bridge state, and decoders
+//! for their results.
+//!
+//! Each builder returns an [`EthCallData`] to place in any of the eth_call query types;
+//! the matching `decode_*` function parses the 32 byte result.
+
+use crate::chain::ChainId;
+use crate::structs::EthCallData;
+
+const NEXT_SEQUENCE: [u8; 4] = [0x4c, 0xf8, 0x42, 0xb5];
+const GET_CURRENT_GUARDIAN_SET_INDEX: [u8; 4] = [0x1c, 0xfe, 0x79, 0x51];
+const MESSAGE_FEE: [u8; 4] = [0x1a, 0x90, 0xa2, 0x19];
+const WRAPPED_ASSET: [u8; 4] = [0x1f, 0xf1, 0xe2, 0x86];
+const IS_WRAPPED_ASSET: [u8; 4] = [0x1a, 0x2b, 0xe4, 0xda];
+const BRIDGE_CONTRACTS: [u8; 4] = [0xad, 0x66, 0xa5, 0xf1];
+
+/// Core bridge `nextSequence(address emitter)`; decode with [`decode_u64`].
+pub fn next_sequence(core_bridge: [u8; 20], emitter: [u8; 20]) -> EthCallData {
+    call(core_bridge, NEXT_SEQUENCE, &[address_word(emitter)])
+}
+
+/// Core bridge `getCurrentGuardianSetIndex()`; decode with [`decode_u32`].
+pub fn current_guardian_set_index(core_bridge: [u8; 20]) -> EthCallData {
+    call(core_bridge, GET_CURRENT_GUARDIAN_SET_INDEX, &[])
+}
+
+/// Core bridge `messageFee()`; decode with [`decode_u64`].
+pub fn message_fee(core_bridge: [u8; 20]) -> EthCallData {
+    call(core_bridge, MESSAGE_FEE, &[])
+}
+
+/// Token bridge `wrappedAsset(uint16 tokenChainId, bytes32 tokenAddress)`: the local
+/// wrapped token for a foreign asset, or the zero address if it has not been attested.
+/// Decode with [`decode_address`].
+pub fn wrapped_asset(
+    token_bridge: [u8; 20],
+    token_chain: ChainId,
+    token_address: [u8; 32],
+) -> EthCallData {
+    call(
+        token_bridge,
+        WRAPPED_ASSET,
+        &[uint_word(token_chain.0.into()), token_address],
+    )
+}
+
+/// Token bridge `isWrappedAsset(address token)`; decode with [`decode_bool`].
+pub fn is_wrapped_asset(token_bridge: [u8; 20], token: [u8; 20]) -> EthCallData {
+    call(token_bridge, IS_WRAPPED_ASSET, &[address_word(token)])
+}
+
+/// Token bridge `bridgeContracts(uint16 chainId)`: the registered token bridge emitter
+/// of another chain, as a universal address. Decode with [`decode_bytes32`].
+pub fn bridge_contracts(token_bridge: [u8; 20], chain_id: ChainId) -> EthCallData {
+    call(
+        token_bridge,
+        BRIDGE_CONTRACTS,
+        &[uint_word(chain_id.0.into())],
+    )
+}
+
+pub fn decode_bytes32(result: &[u8]) -> std::result::Result<[u8; 32], std::io::Error> {
+    result
+        .try_into()
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "InvalidResultLength"))
+}
+
+pub fn decode_u64(result: &[u8]) -> std::result::Result<u64, std::io::Error> {
+    let word = decode_padded::<8>(result)?;
+    Ok(u64::from_be_bytes(word))
+}
+
+pub fn decode_u32(result: &[u8]) -> std::result::Result<u32, std::io::Error> {
+    let word = decode_padded::<4>(result)?;
+    Ok(u32::from_be_bytes(word))
+}
+
+pub fn decode_address(result: &[u8]) -> std::result::Result<[u8; 20], std::io::Error> {
+    decode_padded::<20>(result)
+}
+
+pub fn decode_bool(result: &[u8]) -> std::result::Result<bool, std::io::Error> {
+    match decode_padded::<1>(result)? {
+        [0] => Ok(false),
+        [1] => Ok(true),
+        _ => Err(invalid_value()),
+    }
+}
+
+/// The low `N` bytes of a word, requiring the rest to be zero.
+fn decode_padded<const N: usize>(result: &[u8]) -> std::result::Result<[u8; N], std::io::Error> {
+    let word = decode_bytes32(result)?;
+    if word[..32 - N].iter().any(|b| *b != 0) {
+        return Err(invalid_value());
+    }
+    let mut out = [0u8; N];
+    out.copy_from_slice(&word[32 - N..]);
+    Ok(out)
+}
+
+fn invalid_value() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, "InvalidResultValue")
+}
+
+fn call(to: [u8; 20], selector: [u8; 4], args: &[[u8; 32]]) -> EthCallData {
+    let mut data = selector.to_vec();
+    for arg in args {
+        data.extend_from_slice(arg);
+    }
+    EthCallData { to, data }
+}
+
+fn address_word(address: [u8; 20]) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[12..].copy_from_slice(&address);
+    word
+}
+
+fn uint_word(value: u64) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
@@ -0,0 +1,118 @@
+//! Requests made on chain, by publishing a query request as the payload of a Wormhole
+//! message.
+//!
+//! A response to such a request carries the emitter chain as its request chain id and the
+//! 32 byte hash of the request VAA as its request id, where an off chain response has
+//! chain id zero and the requester's signature. Computing the hash needs the `crypto`
+//! feature.
+
+use byteorder::{BigEndian, ReadBytesExt};
+use std::io::{Cursor, Read};
+
+use crate::chain::ChainId;
+use crate::structs::QueryRequest;
+#[cfg(feature = "crypto")]
+use crate::structs::{ChainSpecificResponse, QueryResponse};
+
+const VAA_VERSION: u8 = 1;
+const SIGNATURE_LEN: u64 = 66;
+
+/// A query request parsed out of the payload of a VAA. Signatures on the VAA are not
+/// checked.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OnChainRequest {
+    pub emitter_chain: ChainId,
+    pub emitter_address: [u8; 32],
+    pub sequence: u64,
+    pub request: QueryRequest,
+    /// The signed part of the VAA, from the timestamp to the end of the payload.
+    pub body: Vec<u8>,
+}
+
+impl OnChainRequest {
+    /// Parses a serialized VAA whose payload is a query request.
+    pub fn from_vaa(vaa: &[u8]) -> std::result::Result<OnChainRequest, std::io::Error> {
+        let mut rdr = Cursor::new(vaa);
+        let version = rdr.read_u8()?;
+        if version != VAA_VERSION {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "InvalidVaaVersion",
+            ));
+        }
+        rdr.read_u32::<BigEndian>()?; // guardian set index
+        let num_signatures = rdr.read_u8()?;
+        let body_start = rdr.position() + u64::from(num_signatures) * SIGNATURE_LEN;
+        if body_start > vaa.len() as u64 {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof));
+        }
+        Self::from_body(&vaa[body_start as usize..])
+    }
+
+    /// Parses the body of a VAA, as from [`OnChainRequest::from_vaa`] but without the
+    /// header and signatures.
+    pub fn from_body(body: &[u8]) -> std::result::Result<OnChainRequest, std::io::Error> {
+        let mut rdr = Cursor::new(body);
+        rdr.read_u32::<BigEndian>()?; // timestamp
+        rdr.read_u32::<BigEndian>()?; // nonce
+        let emitter_chain = ChainId(rdr.read_u16::<BigEndian>()?);
+        let mut emitter_address = [0u8; 32];
+        rdr.read_exact(&mut emitter_address)?;
+        let sequence = rdr.read_u64::<BigEndian>()?;
+        rdr.read_u8()?; // consistency level
+        let payload = &body[rdr.position() as usize..];
+        Ok(OnChainRequest {
+            emitter_chain,
+            emitter_address,
+            sequence,
+            request: QueryRequest::deserialize(payload)?,
+            body: body.to_vec(),
+        })
+    }
+}
+
+#[cfg(feature = "crypto")]
+impl OnChainRequest {
+    /// The VAA hash, `keccak256(keccak256(body))`, used as the request id.
+    pub fn request_id(&self) -> [u8; 32] {
+        crate::signature::keccak256(&crate::signature::keccak256(&self.body))
+    }
+
+    /// Builds the response to this request, as [`QueryResponse::from_parts`] but with the
+    /// emitter chain and VAA hash in place of an off chain request id.
+    pub fn response(
+        &self,
+        responses: Vec<ChainSpecificResponse>,
+    ) -> std::result::Result<QueryResponse, std::io::Error> {
+        let mut response = QueryResponse::from_parts(self.request.clone(), responses)?;
+        response.request_chain_id = self.emitter_chain.into();
+        response.request_id = self.request_id().to_vec();
+        Ok(response)
+    }
+}
+
+#[cfg(feature = "crypto")]
+impl QueryResponse {
+    /// Checks that this response answers `request`: same emitter chain, request id and
+    /// request.
+    pub fn check_on_chain_request(
+        &self,
+        request: &OnChainRequest,
+    ) -> std::result::Result<(), std::io::Error> {
+        if self.request_chain_id != u16::from(request.emitter_chain)
+            || self.request_id.as_slice() != request.request_id()
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "RequestIdMismatch",
+            ));
+        }
+        if self.request != request.request {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "RequestMismatch",
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+//! A flat view of the eth_call results in a [`QueryResponse`], independent of which of the
+//! eth_call query types produced them.
+//!
+//! Nothing here checks signatures; call [`QueryResponse::verified_calls`] on a response
+//! which has already been verified.
+
+use crate::chain::ChainId;
+use crate::structs::{BlockInfo, ChainSpecificQuery, ChainSpecificResponse, QueryResponse};
+
+/// One call and its result. For eth_call_by_timestamp `block` is the target block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifiedCallResult {
+    pub chain: ChainId,
+    pub to: [u8; 20],
+    pub calldata: Vec<u8>,
+    pub block: BlockInfo,
+    pub result: Vec<u8>,
+}
+
+impl QueryResponse {
+    /// Pairs every eth_call, eth_call_by_timestamp, eth_call_with_finality and
+    /// eth_call_with_overrides call in the request with its result, in order. Solana and
+    /// NEAR responses are skipped. Fails if the responses do not line up with the
+    /// requests.
+    pub fn verified_calls(&self) -> std::result::Result<Vec<VerifiedCallResult>, std::io::Error> {
+        let mut calls = Vec::new();
+        for (request, response) in self.aligned_pairs()? {
+            let pairs = match (&request.query, &response.response) {
+                (
+                    ChainSpecificQuery::EthCallQueryRequest(request),
+                    ChainSpecificResponse::EthCallQueryResponse(response),
+                ) => response.zip(request)?,
+                (
+                    ChainSpecificQuery::EthCallByTimestampQueryRequest(request),
+                    ChainSpecificResponse::EthCallByTimestampQueryResponse(response),
+                ) => response.zip(request)?,
+                (
+                    ChainSpecificQuery::EthCallWithFinalityQueryRequest(request),
+                    ChainSpecificResponse::EthCallWithFinalityQueryResponse(response),
+                ) => response.zip(request)?,
+                #[cfg(feature = "experimental")]
+                (
+                    ChainSpecificQuery::EthCallWithOverridesQueryRequest(request),
+                    ChainSpecificResponse::EthCallWithOverridesQueryResponse(response),
+                ) => response.zip(request)?,
+                (
+                    ChainSpecificQuery::SolanaAccountQueryRequest(_),
+                    ChainSpecificResponse::SolanaAccountQueryResponse(_),
+                ) => continue,
+                #[cfg(feature = "experimental")]
+                (
+                    ChainSpecificQuery::NearViewCallQueryRequest(_),
+                    ChainSpecificResponse::NearViewCallQueryResponse(_),
+                )
+                | (
+                    ChainSpecificQuery::NearViewAccountQueryRequest(_),
+                    ChainSpecificResponse::NearViewAccountQueryResponse(_),
+                ) => continue,
+                // aligned_pairs checked the query types match.
+                _ => unreachable!(),
+            };
+            let block = response.response.block_info();
+            calls.extend(pairs.into_iter().map(|(call, result)| VerifiedCallResult {
+                chain: response.chain(),
+                to: call.to,
+                calldata: call.data.clone(),
+                block,
+                result: result.to_vec(),
+            }));
+        }
+        Ok(calls)
+    }
+}
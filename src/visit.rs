@@ -0,0 +1,130 @@
+//! Visitor over the per chain results of a [`QueryResponse`].
+//!
+//! Implement only the methods for the query types you handle; the rest do nothing.
+
+use crate::chain::ChainId;
+use crate::structs::{
+    ChainSpecificQuery, ChainSpecificResponse, EthCallByTimestampQueryRequest,
+    EthCallByTimestampQueryResponse, EthCallQueryRequest, EthCallQueryResponse,
+    EthCallWithFinalityQueryRequest, EthCallWithFinalityQueryResponse, QueryResponse,
+    SolanaAccountQueryRequest, SolanaAccountQueryResponse,
+};
+#[cfg(feature = "experimental")]
+use crate::structs::{
+    EthCallWithOverridesQueryRequest, EthCallWithOverridesQueryResponse,
+    NearViewAccountQueryRequest, NearViewAccountQueryResponse, NearViewCallQueryRequest,
+    NearViewCallQueryResponse,
+};
+
+/// Handlers called by [`QueryResponse::walk`] with each per chain request and its
+/// response.
+pub trait ResponseVisitor {
+    fn visit_eth_call(
+        &mut self,
+        _chain: ChainId,
+        _request: &EthCallQueryRequest,
+        _response: &EthCallQueryResponse,
+    ) {
+    }
+
+    fn visit_eth_call_by_timestamp(
+        &mut self,
+        _chain: ChainId,
+        _request: &EthCallByTimestampQueryRequest,
+        _response: &EthCallByTimestampQueryResponse,
+    ) {
+    }
+
+    fn visit_eth_call_with_finality(
+        &mut self,
+        _chain: ChainId,
+        _request: &EthCallWithFinalityQueryRequest,
+        _response: &EthCallWithFinalityQueryResponse,
+    ) {
+    }
+
+    fn visit_solana_account(
+        &mut self,
+        _chain: ChainId,
+        _request: &SolanaAccountQueryRequest,
+        _response: &SolanaAccountQueryResponse,
+    ) {
+    }
+
+    #[cfg(feature = "experimental")]
+    fn visit_eth_call_with_overrides(
+        &mut self,
+        _chain: ChainId,
+        _request: &EthCallWithOverridesQueryRequest,
+        _response: &EthCallWithOverridesQueryResponse,
+    ) {
+    }
+
+    #[cfg(feature = "experimental")]
+    fn visit_near_view_call(
+        &mut self,
+        _chain: ChainId,
+        _request: &NearViewCallQueryRequest,
+        _response: &NearViewCallQueryResponse,
+    ) {
+    }
+
+    #[cfg(feature = "experimental")]
+    fn visit_near_view_account(
+        &mut self,
+        _chain: ChainId,
+        _request: &NearViewAccountQueryRequest,
+        _response: &NearViewAccountQueryResponse,
+    ) {
+    }
+}
+
+impl QueryResponse {
+    /// Calls `visitor` for every per chain response, in order, together with the request
+    /// that produced it. Fails before visiting anything if the responses do not line up
+    /// with the requests.
+    pub fn walk<V: ResponseVisitor>(
+        &self,
+        visitor: &mut V,
+    ) -> std::result::Result<(), std::io::Error> {
+        for (request, response) in self.aligned_pairs()? {
+            let chain = response.chain();
+            match (&request.query, &response.response) {
+                (
+                    ChainSpecificQuery::EthCallQueryRequest(request),
+                    ChainSpecificResponse::EthCallQueryResponse(response),
+                ) => visitor.visit_eth_call(chain, request, response),
+                (
+                    ChainSpecificQuery::EthCallByTimestampQueryRequest(request),
+                    ChainSpecificResponse::EthCallByTimestampQueryResponse(response),
+                ) => visitor.visit_eth_call_by_timestamp(chain, request, response),
+                (
+                    ChainSpecificQuery::EthCallWithFinalityQueryRequest(request),
+                    ChainSpecificResponse::EthCallWithFinalityQueryResponse(response),
+                ) => visitor.visit_eth_call_with_finality(chain, request, response),
+                (
+                    ChainSpecificQuery::SolanaAccountQueryRequest(request),
+                    ChainSpecificResponse::SolanaAccountQueryResponse(response),
+                ) => visitor.visit_solana_account(chain, request, response),
+                #[cfg(feature = "experimental")]
+                (
+                    ChainSpecificQuery::EthCallWithOverridesQueryRequest(request),
+                    ChainSpecificResponse::EthCallWithOverridesQueryResponse(response),
+                ) => visitor.visit_eth_call_with_overrides(chain, request, response),
+                #[cfg(feature = "experimental")]
+                (
+                    ChainSpecificQuery::NearViewCallQueryRequest(request),
+                    ChainSpecificResponse::NearViewCallQueryResponse(response),
+                ) => visitor.visit_near_view_call(chain, request, response),
+                #[cfg(feature = "experimental")]
+                (
+                    ChainSpecificQuery::NearViewAccountQueryRequest(request),
+                    ChainSpecificResponse::NearViewAccountQueryResponse(response),
+                ) => visitor.visit_near_view_account(chain, request, response),
+                // aligned_pairs checked the query types match.
+                _ => unreachable!(),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use wormhole_query_sdk::abi::{
+    encode_eth_call_response, encode_signed_response, submission_calldata,
+};
+use wormhole_query_sdk::structs::*;
+
+fn word(value: u64) -> Vec<u8> {
+    let mut out = vec![0u8; 24];
+    out.extend_from_slice(&value.to_be_bytes());
+    out
+}
+
+/// `data` right padded to a whole number of words.
+fn padded(data: &[u8]) -> Vec<u8> {
+    let mut out = data.to_vec();
+    out.resize(data.len().div_ceil(32) * 32, 0);
+    out
+}
+
+fn words(parts: &[Vec<u8>]) -> Vec<u8> {
+    parts.concat()
+}
+
+#[test]
+fn eth_call_response_matches_abi_encode() {
+    let request = EthCallQueryRequest {
+        block_tag: "latest".to_string(),
+        call_data: vec![EthCallData {
+            to: [1; 20],
+            data: vec![0x12],
+        }],
+    };
+    let response = EthCallQueryResponse {
+        block_number: 100,
+        block_hash: [2; 32],
+        block_time: 10,
+        results: vec![vec![0x34]],
+    };
+    let mut address = vec![0u8; 12];
+    address.extend_from_slice(&[1; 20]);
+    let expected = words(&[
+        word(0x20),
+        // requestBlockId, blockNum, blockTime, blockHash, result
+        word(0xa0),
+        word(100),
+        word(10),
+        vec![2; 32],
+        word(0xe0),
+        word(6),
+        padded(b"latest"),
+        // result[]
+        word(1),
+        word(0x20),
+        address,
+        word(0x60),
+        word(0xa0),
+        word(1),
+        padded(&[0x12]),
+        word(1),
+        padded(&[0x34]),
+    ]);
+    assert_eq!(
+        encode_eth_call_response(&request, &response).unwrap(),
+        expected
+    );
+
+    let mut short = response;
+    short.results.clear();
+    assert!(encode_eth_call_response(&request, &short).is_err());
+}
+
+#[test]
+fn signed_response_matches_abi_encode() {
+    let mut signature = [0u8; 66];
+    signature[..32].copy_from_slice(&[3; 32]);
+    signature[32..64].copy_from_slice(&[4; 32]);
+    signature[64] = 1;
+    signature[65] = 7;
+    let expected = words(&[
+        word(0x40),
+        word(0x80),
+        word(2),
+        padded(&[0xaa, 0xbb]),
+        word(1),
+        vec![3; 32],
+        vec![4; 32],
+        word(28),
+        word(7),
+    ]);
+    assert_eq!(
+        encode_signed_response(&[0xaa, 0xbb], &[signature]),
+        expected
+    );
+
+    let calldata = submission_calldata([0xde, 0xad, 0xbe, 0xef], &[0xaa, 0xbb], &[signature]);
+    assert_eq!(calldata[..4], [0xde, 0xad, 0xbe, 0xef]);
+    assert_eq!(calldata[4..], expected);
+}
@@ -0,0 +1,55 @@
+use wormhole_query_sdk::archive::{ArchiveReader, ArchiveRecord, ArchiveWriter};
+
+fn record(timestamp: u64) -> ArchiveRecord {
+    ArchiveRecord {
+        timestamp,
+        signatures: vec![[timestamp as u8; 66]],
+        response: vec![1, 2, 3],
+    }
+}
+
+/// Two records, each 4 + 8 + 1 + 66 + 4 + 3 + 4 bytes long.
+fn archive() -> Vec<u8> {
+    let mut writer = ArchiveWriter::new(Vec::new());
+    writer.append(&record(1)).unwrap();
+    writer.append(&record(2)).unwrap();
+    writer.into_inner()
+}
+
+const RECORD_LEN: usize = 90;
+
+#[test]
+fn records_round_trip() {
+    let data = archive();
+    assert_eq!(data.len(), 2 * RECORD_LEN);
+    let records: Vec<ArchiveRecord> = ArchiveReader::new(data.as_slice())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(records, [record(1), record(2)]);
+}
+
+#[test]
+fn corrupted_record_fails_its_checksum() {
+    let mut data = archive();
+    // The last response byte of the first record.
+    data[RECORD_LEN - 5] ^= 0xff;
+    let mut reader = ArchiveReader::new(data.as_slice());
+    assert_eq!(
+        reader.next().unwrap().unwrap_err().to_string(),
+        "ChecksumMismatch"
+    );
+    assert_eq!(reader.next().unwrap().unwrap(), record(2));
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn truncated_record_ends_the_archive() {
+    let data = archive();
+    let mut reader = ArchiveReader::new(&data[..data.len() - 2]);
+    assert_eq!(reader.next().unwrap().unwrap(), record(1));
+    assert_eq!(
+        reader.next().unwrap().unwrap_err().to_string(),
+        "TruncatedRecord"
+    );
+    assert!(reader.next().is_none());
+}
@@ -0,0 +1,33 @@
+#![cfg(feature = "tokio")]
+
+use wormhole_query_sdk::structs::*;
+
+// Polygon eth_call request from serialization.rs, with the per chain query length at
+// bytes 9..13.
+const ETH_CALL_REQUEST: &str = "0100000001010005010000004600000009307832386439363330027ceb23fd6bc0add59e62ac25578270cff1b9f6190000000406fdde037ceb23fd6bc0add59e62ac25578270cff1b9f6190000000418160ddd";
+
+fn with_query_len(len: u32) -> Vec<u8> {
+    let mut bytes = hex::decode(ETH_CALL_REQUEST).unwrap();
+    bytes[9..13].copy_from_slice(&len.to_be_bytes());
+    bytes
+}
+
+#[tokio::test]
+async fn sync_and_async_agree_on_per_chain_length() {
+    let bytes = with_query_len(0x46);
+    let sync = QueryRequest::deserialize(&bytes).unwrap();
+    let async_ = QueryRequest::deserialize_from_async_reader(&mut bytes.as_slice())
+        .await
+        .unwrap();
+    assert_eq!(sync, async_);
+
+    for len in [0x45, 0x47] {
+        let bytes = with_query_len(len);
+        assert!(QueryRequest::deserialize(&bytes).is_err());
+        assert!(
+            QueryRequest::deserialize_from_async_reader(&mut bytes.as_slice())
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use wormhole_query_sdk::budget::{
+    estimate_per_chain_response_size, estimate_response_size, ResponseSizeCaps,
+};
+use wormhole_query_sdk::structs::*;
+
+const CAPS: ResponseSizeCaps = ResponseSizeCaps {
+    max_call_result_len: 64,
+    max_account_data_len: 100,
+};
+
+fn eth_call(calls: usize) -> ChainSpecificQuery {
+    ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+        block_tag: "latest".to_string(),
+        call_data: vec![
+            EthCallData {
+                to: [1; 20],
+                data: vec![0x06, 0xfd, 0xde, 0x03],
+            };
+            calls
+        ],
+    })
+}
+
+fn sol_account(accounts: usize, data_slice_length: u64) -> ChainSpecificQuery {
+    ChainSpecificQuery::SolanaAccountQueryRequest(SolanaAccountQueryRequest {
+        commitment: "finalized".to_string(),
+        min_context_slot: 0,
+        data_slice_offset: 0,
+        data_slice_length,
+        accounts: vec![[2; 32]; accounts],
+    })
+}
+
+fn account(data_len: usize) -> SolanaAccountResult {
+    SolanaAccountResult {
+        lamports: 1,
+        rent_epoch: 2,
+        executable: false,
+        owner: [3; 32],
+        data: vec![4; data_len],
+    }
+}
+
+fn request(queries: Vec<(u16, ChainSpecificQuery)>) -> QueryRequest {
+    QueryRequest {
+        version: QueryRequest::REQUEST_VERSION,
+        nonce: 1,
+        requests: queries
+            .into_iter()
+            .map(|(chain_id, query)| PerChainQueryRequest::new(chain_id, query))
+            .collect(),
+    }
+}
+
+#[test]
+fn estimate_matches_largest_response() {
+    let request = request(vec![
+        (2, eth_call(3)),
+        (1, sol_account(2, 10)),
+        (1, sol_account(1, 0)),
+    ]);
+    let largest = QueryResponse::from_parts(
+        request.clone(),
+        vec![
+            ChainSpecificResponse::EthCallQueryResponse(EthCallQueryResponse {
+                block_number: 1,
+                block_hash: [1; 32],
+                block_time: 1,
+                results: vec![vec![0xff; 64]; 3],
+            }),
+            ChainSpecificResponse::SolanaAccountQueryResponse(SolanaAccountQueryResponse {
+                slot_number: 1,
+                block_time: 1,
+                block_hash: [1; 32],
+                results: vec![account(10), account(10)],
+            }),
+            ChainSpecificResponse::SolanaAccountQueryResponse(SolanaAccountQueryResponse {
+                slot_number: 1,
+                block_time: 1,
+                block_hash: [1; 32],
+                results: vec![account(100)],
+            }),
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(
+        estimate_response_size(&request, &CAPS).unwrap(),
+        largest.serialize().unwrap().len()
+    );
+}
+
+#[test]
+fn per_chain_estimate_grows_with_calls() {
+    let one = estimate_per_chain_response_size(&PerChainQueryRequest::new(2, eth_call(1)), &CAPS)
+        .unwrap();
+    let two = estimate_per_chain_response_size(&PerChainQueryRequest::new(2, eth_call(2)), &CAPS)
+        .unwrap();
+    assert_eq!(two - one, 4 + 64);
+}
+
+#[test]
+fn oversized_bounds_are_errors() {
+    let huge_slice = request(vec![(1, sol_account(2, u64::MAX))]);
+    assert_eq!(
+        estimate_response_size(&huge_slice, &CAPS)
+            .unwrap_err()
+            .to_string(),
+        "SizeOverflow"
+    );
+
+    let huge_caps = ResponseSizeCaps {
+        max_call_result_len: usize::MAX,
+        max_account_data_len: usize::MAX,
+    };
+    for query in [eth_call(1), sol_account(1, 0)] {
+        assert_eq!(
+            estimate_per_chain_response_size(&PerChainQueryRequest::new(1, query), &huge_caps)
+                .unwrap_err()
+                .to_string(),
+            "SizeOverflow"
+        );
+    }
+
+    // Each chain fits on its own but not all of them together.
+    let large_caps = ResponseSizeCaps {
+        max_call_result_len: usize::MAX / 4,
+        max_account_data_len: 0,
+    };
+    let request = request(vec![
+        (2, eth_call(1)),
+        (5, eth_call(1)),
+        (6, eth_call(1)),
+        (23, eth_call(1)),
+    ]);
+    for per_chain in &request.requests {
+        estimate_per_chain_response_size(per_chain, &large_caps).unwrap();
+    }
+    assert_eq!(
+        estimate_response_size(&request, &large_caps)
+            .unwrap_err()
+            .to_string(),
+        "SizeOverflow"
+    );
+}
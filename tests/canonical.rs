@@ -0,0 +1,116 @@
+use wormhole_query_sdk::structs::*;
+
+fn request(nonce: u32, block_tag: &str) -> QueryRequest {
+    QueryRequest {
+        version: QueryRequest::REQUEST_VERSION,
+        nonce,
+        requests: vec![PerChainQueryRequest::new(
+            2,
+            ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+                block_tag: block_tag.to_string(),
+                call_data: vec![EthCallData {
+                    to: [1; 20],
+                    data: vec![0x06, 0xfd, 0xde, 0x03],
+                }],
+            }),
+        )],
+    }
+}
+
+#[cfg(feature = "crypto")]
+#[test]
+fn canonical_hash_ignores_only_the_nonce() {
+    let hash = request(1, "latest").canonical_hash().unwrap();
+    assert_eq!(request(2, "latest").canonical_hash().unwrap(), hash);
+    assert_eq!(request(u32::MAX, "latest").canonical_hash().unwrap(), hash);
+
+    assert_ne!(request(1, "safe").canonical_hash().unwrap(), hash);
+    let mut other_chain = request(1, "latest");
+    other_chain.requests[0].chain_id = 5;
+    assert_ne!(other_chain.canonical_hash().unwrap(), hash);
+    let mut other_call = request(1, "latest");
+    if let ChainSpecificQuery::EthCallQueryRequest(query) = &mut other_call.requests[0].query {
+        query.call_data[0].data.push(0);
+    }
+    assert_ne!(other_call.canonical_hash().unwrap(), hash);
+}
+
+fn solana_response(executable: bool) -> QueryResponse {
+    QueryResponse {
+        version: QueryResponse::RESPONSE_VERSION,
+        request_chain_id: 0,
+        request_id: vec![0x11; 65],
+        request: QueryRequest {
+            version: QueryRequest::REQUEST_VERSION,
+            nonce: 1,
+            requests: vec![PerChainQueryRequest::new(
+                1,
+                ChainSpecificQuery::SolanaAccountQueryRequest(SolanaAccountQueryRequest {
+                    commitment: "finalized".to_string(),
+                    min_context_slot: 0,
+                    data_slice_offset: 0,
+                    data_slice_length: 0,
+                    accounts: vec![[1; 32]],
+                }),
+            )],
+        },
+        responses: vec![PerChainQueryResponse::new(
+            1,
+            ChainSpecificResponse::SolanaAccountQueryResponse(SolanaAccountQueryResponse {
+                slot_number: 100,
+                block_time: 10,
+                block_hash: [2; 32],
+                results: vec![SolanaAccountResult {
+                    lamports: 1,
+                    rent_epoch: 2,
+                    executable,
+                    owner: [3; 32],
+                    data: vec![],
+                }],
+            }),
+        )],
+    }
+}
+
+#[test]
+fn canonical_payloads_are_accepted() {
+    let request = request(1, "latest");
+    assert_eq!(
+        QueryRequest::verify_canonical(&request.serialize().unwrap()).unwrap(),
+        request
+    );
+    let response = solana_response(true);
+    assert_eq!(
+        QueryResponse::verify_canonical(&response.serialize().unwrap()).unwrap(),
+        response
+    );
+}
+
+#[test]
+fn non_canonical_payloads_are_rejected() {
+    let bytes = request(1, "latest").serialize().unwrap();
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(QueryRequest::verify_canonical(&trailing).is_err());
+
+    // The per chain query length, at bytes 9..13, one longer than the query.
+    let mut over_long = bytes;
+    let len = u32::from_be_bytes(over_long[9..13].try_into().unwrap());
+    over_long[9..13].copy_from_slice(&(len + 1).to_be_bytes());
+    over_long.push(0);
+    assert!(QueryRequest::verify_canonical(&over_long).is_err());
+
+    // Any non zero byte parses as an executable account, but only one re-serializes to
+    // it. The flag is followed by the owner and an empty data length.
+    let mut bytes = solana_response(true).serialize().unwrap();
+    let executable_at = bytes.len() - 4 - 32 - 1;
+    assert_eq!(bytes[executable_at], 1);
+    bytes[executable_at] = 2;
+    assert!(QueryResponse::deserialize(&bytes).is_ok());
+    assert_eq!(
+        QueryResponse::verify_canonical(&bytes)
+            .unwrap_err()
+            .to_string(),
+        "NonCanonicalEncoding"
+    );
+}
@@ -0,0 +1,112 @@
+#![cfg(feature = "client")]
+
+use std::io::ErrorKind;
+
+use wormhole_query_sdk::client::QueryError;
+
+fn classify(status: u16, body: &str) -> QueryError {
+    QueryError::from_proxy_reply(status, body)
+}
+
+#[test]
+fn statuses_with_a_single_meaning() {
+    assert!(matches!(classify(429, ""), QueryError::RateLimited));
+    assert!(matches!(classify(429, "quorum"), QueryError::RateLimited));
+    assert!(matches!(
+        classify(401, "invalid signature"),
+        QueryError::Unauthorized
+    ));
+    assert!(matches!(
+        classify(504, "quorum not reached"),
+        QueryError::Timeout
+    ));
+}
+
+#[test]
+fn forbidden_is_split_by_message() {
+    assert!(matches!(
+        classify(403, "invalid API key\n"),
+        QueryError::Unauthorized
+    ));
+    match classify(403, "  call not permitted: 0x1234\n") {
+        QueryError::NotAllowed(message) => assert_eq!(message, "call not permitted: 0x1234"),
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn quorum_failures_are_found_under_any_status() {
+    for status in [400, 500, 502] {
+        match classify(status, "Failed to reach Quorum") {
+            QueryError::NoQuorum(message) => assert_eq!(message, "Failed to reach Quorum"),
+            other => panic!("{}: unexpected {:?}", status, other),
+        }
+    }
+}
+
+#[test]
+fn bad_requests_are_split_by_message() {
+    assert!(matches!(
+        classify(400, "failed to verify request Signature"),
+        QueryError::InvalidSignature
+    ));
+    match classify(400, "invalid hex") {
+        QueryError::BadRequest(message) => assert_eq!(message, "invalid hex"),
+        other => panic!("unexpected {:?}", other),
+    }
+    match classify(400, "") {
+        QueryError::BadRequest(message) => assert_eq!(message, ""),
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn other_statuses_are_proxy_errors() {
+    match classify(502, "upstream unavailable") {
+        QueryError::Proxy { status, message } => {
+            assert_eq!(status, 502);
+            assert_eq!(message, "upstream unavailable");
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+    // "signature" only means a rejected signature on a 400.
+    assert!(matches!(
+        classify(500, "signature service down"),
+        QueryError::Proxy { status: 500, .. }
+    ));
+    assert!(classify(502, "").is_retryable());
+    assert!(!classify(418, "").is_retryable());
+}
+
+#[test]
+fn retryable_errors() {
+    assert!(classify(429, "").is_retryable());
+    assert!(classify(504, "").is_retryable());
+    assert!(classify(500, "no quorum").is_retryable());
+    assert!(QueryError::Io(ErrorKind::ConnectionReset.into()).is_retryable());
+    assert!(!classify(401, "").is_retryable());
+    assert!(!classify(403, "not allowed").is_retryable());
+    assert!(!classify(400, "bad signature").is_retryable());
+    assert!(!classify(400, "bad request").is_retryable());
+}
+
+#[test]
+fn io_error_kinds() {
+    let kind = |err: QueryError| std::io::Error::from(err).kind();
+    assert_eq!(kind(classify(429, "")), ErrorKind::Other);
+    assert_eq!(kind(classify(401, "")), ErrorKind::PermissionDenied);
+    assert_eq!(kind(classify(403, "nope")), ErrorKind::PermissionDenied);
+    assert_eq!(
+        kind(classify(400, "bad signature")),
+        ErrorKind::PermissionDenied
+    );
+    assert_eq!(kind(classify(504, "")), ErrorKind::TimedOut);
+    assert_eq!(kind(classify(400, "bad")), ErrorKind::InvalidInput);
+    assert_eq!(kind(classify(500, "no quorum")), ErrorKind::Other);
+    assert_eq!(kind(classify(502, "")), ErrorKind::Other);
+
+    // I/O errors come back out unchanged.
+    let err = std::io::Error::from(QueryError::Io(ErrorKind::ConnectionReset.into()));
+    assert_eq!(err.kind(), ErrorKind::ConnectionReset);
+    assert!(err.get_ref().is_none());
+}
@@ -0,0 +1,87 @@
+use wormhole_query_sdk::compare::{compare_all, compare_responses, Divergence};
+use wormhole_query_sdk::structs::*;
+
+fn response(block_number: u64, results: Vec<Vec<u8>>) -> QueryResponse {
+    let request = QueryRequest {
+        version: QueryRequest::REQUEST_VERSION,
+        nonce: 1,
+        requests: vec![PerChainQueryRequest::new(
+            2,
+            ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+                block_tag: "latest".to_string(),
+                call_data: results
+                    .iter()
+                    .map(|_| EthCallData {
+                        to: [1; 20],
+                        data: vec![],
+                    })
+                    .collect(),
+            }),
+        )],
+    };
+    QueryResponse {
+        version: QueryResponse::RESPONSE_VERSION,
+        request_chain_id: 0,
+        request_id: vec![0; 65],
+        request,
+        responses: vec![PerChainQueryResponse::new(
+            2,
+            ChainSpecificResponse::EthCallQueryResponse(EthCallQueryResponse {
+                block_number,
+                block_hash: [1; 32],
+                block_time: 10,
+                results,
+            }),
+        )],
+    }
+}
+
+#[test]
+fn request_id_is_ignored() {
+    let left = response(100, vec![vec![1], vec![2]]);
+    let mut right = left.clone();
+    right.request_id = vec![7; 65];
+    compare_responses(&left, &right).unwrap();
+}
+
+#[test]
+fn first_divergence_is_reported() {
+    let left = response(100, vec![vec![1], vec![2]]);
+    assert_eq!(
+        compare_responses(&left, &response(101, vec![vec![1], vec![2]])),
+        Err(Divergence::Block { response_index: 0 })
+    );
+    assert_eq!(
+        compare_responses(&left, &response(100, vec![vec![1], vec![3]])),
+        Err(Divergence::Result {
+            response_index: 0,
+            result_index: 1
+        })
+    );
+    assert_eq!(
+        compare_responses(&left, &response(100, vec![vec![1]])),
+        Err(Divergence::Request)
+    );
+
+    let mut right = left.clone();
+    right.responses[0].chain_id = 5;
+    assert_eq!(
+        compare_responses(&left, &right),
+        Err(Divergence::ChainId { response_index: 0 })
+    );
+}
+
+#[test]
+fn compare_all_names_the_diverging_response() {
+    let first = response(100, vec![vec![1]]);
+    let responses = [
+        first.clone(),
+        first.clone(),
+        response(100, vec![vec![2]]),
+        first,
+    ];
+    let (index, divergence) = compare_all(&responses).unwrap_err();
+    assert_eq!(index, 2);
+    assert_eq!(divergence.to_string(), "response 0: result 0 differs");
+    compare_all(&[]).unwrap();
+}
@@ -0,0 +1,66 @@
+use wormhole_query_sdk::structs::*;
+
+fn response(nonce: u32) -> QueryResponse {
+    QueryResponse {
+        version: QueryResponse::RESPONSE_VERSION,
+        request_chain_id: 0,
+        request_id: vec![0; 65],
+        request: QueryRequest {
+            version: QueryRequest::REQUEST_VERSION,
+            nonce,
+            requests: vec![PerChainQueryRequest::new(
+                2,
+                ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+                    block_tag: "latest".to_string(),
+                    call_data: vec![],
+                }),
+            )],
+        },
+        responses: vec![PerChainQueryResponse::new(
+            2,
+            ChainSpecificResponse::EthCallQueryResponse(EthCallQueryResponse {
+                block_number: 100,
+                block_hash: [1; 32],
+                block_time: 10,
+                results: vec![],
+            }),
+        )],
+    }
+}
+
+fn concatenated(count: u32) -> (Vec<u8>, usize) {
+    let mut data = Vec::new();
+    for nonce in 0..count {
+        data.extend(response(nonce).serialize().unwrap());
+    }
+    let len = data.len() / count.max(1) as usize;
+    (data, len)
+}
+
+#[test]
+fn responses_are_split_by_their_lengths() {
+    let (data, _) = concatenated(3);
+    assert_eq!(
+        QueryResponse::deserialize_many(&data).unwrap(),
+        [response(0), response(1), response(2)]
+    );
+    assert_eq!(QueryResponse::deserialize_many(&[]).unwrap(), []);
+}
+
+#[test]
+fn failure_reports_the_offset_of_the_bad_response() {
+    let (mut data, len) = concatenated(3);
+    // The version byte of the second response.
+    data[len] = 2;
+    let (offset, err) = QueryResponse::deserialize_many(&data).unwrap_err();
+    assert_eq!(offset, len);
+    assert_eq!(err.to_string(), "InvalidResponseVersion");
+}
+
+#[test]
+fn truncated_last_response_is_an_error() {
+    let (data, len) = concatenated(2);
+    let (offset, err) = QueryResponse::deserialize_many(&data[..data.len() - 1]).unwrap_err();
+    assert_eq!(offset, len);
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+}
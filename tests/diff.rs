@@ -0,0 +1,83 @@
+use wormhole_query_sdk::diff::{diff_request_with_response, diff_requests};
+use wormhole_query_sdk::structs::*;
+
+fn request(data: Vec<u8>) -> QueryRequest {
+    QueryRequest {
+        version: QueryRequest::REQUEST_VERSION,
+        nonce: 1,
+        requests: vec![PerChainQueryRequest::new(
+            2,
+            ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+                block_tag: "latest".to_string(),
+                call_data: vec![EthCallData { to: [1; 20], data }],
+            }),
+        )],
+    }
+}
+
+#[test]
+fn identical_requests_have_no_difference() {
+    let data = request(vec![1, 2, 3]).serialize().unwrap();
+    assert_eq!(diff_requests(&data, &data), None);
+}
+
+#[test]
+fn differing_calldata_is_named() {
+    let left = request(vec![1, 2, 3]).serialize().unwrap();
+    let right = request(vec![1, 9, 3]).serialize().unwrap();
+    let difference = diff_requests(&left, &right).unwrap();
+    assert_eq!(difference.offset, 49);
+    assert_eq!(difference.field, "requests[0].call_data[0].data");
+    assert_eq!((difference.left, difference.right), (Some(2), Some(9)));
+    assert_eq!(
+        difference.to_string(),
+        "first difference at byte 49 (requests[0].call_data[0].data): 0x02 != 0x09"
+    );
+}
+
+#[test]
+fn truncated_request_is_named_by_the_longer_side() {
+    let full = request(vec![1, 2, 3]).serialize().unwrap();
+    let difference = diff_requests(&full[..20], &full).unwrap();
+    assert_eq!(difference.offset, 20);
+    assert_eq!(difference.field, "requests[0].block_tag");
+    assert_eq!((difference.left, difference.right), (None, Some(b'e')));
+}
+
+#[test]
+fn request_is_compared_with_the_one_embedded_in_a_response() {
+    let signed = request(vec![1, 2, 3]);
+    let response = QueryResponse {
+        version: QueryResponse::RESPONSE_VERSION,
+        request_chain_id: 0,
+        request_id: vec![0; 65],
+        request: signed.clone(),
+        responses: vec![PerChainQueryResponse::new(
+            2,
+            ChainSpecificResponse::EthCallQueryResponse(EthCallQueryResponse {
+                block_number: 1,
+                block_hash: [1; 32],
+                block_time: 1,
+                results: vec![vec![]],
+            }),
+        )],
+    }
+    .serialize()
+    .unwrap();
+
+    let same = signed.serialize().unwrap();
+    assert_eq!(diff_request_with_response(&same, &response).unwrap(), None);
+
+    let mut other = signed;
+    other.nonce = 2;
+    let difference = diff_request_with_response(&other.serialize().unwrap(), &response)
+        .unwrap()
+        .unwrap();
+    assert_eq!(difference.field, "nonce");
+    assert_eq!(
+        diff_request_with_response(&same, &response[..10])
+            .unwrap_err()
+            .to_string(),
+        "TruncatedResponse"
+    );
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "client")]
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use wormhole_query_sdk::chain::ChainId;
+use wormhole_query_sdk::executor::QueryProxyMock;
+use wormhole_query_sdk::structs::*;
+
+fn eth_call(chain_id: u16) -> PerChainQueryRequest {
+    PerChainQueryRequest::new(
+        chain_id,
+        ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+            block_tag: "latest".to_string(),
+            call_data: vec![],
+        }),
+    )
+}
+
+#[test]
+fn failing_chains_still_get_outcomes_and_timings() {
+    let request = QueryRequest {
+        version: QueryRequest::REQUEST_VERSION,
+        nonce: 1,
+        requests: vec![eth_call(2), eth_call(4)],
+    };
+    // Chain 2 fails on injection, chain 4 on its missing RPC url; no RPC is contacted.
+    let mut mock = QueryProxyMock::new(HashMap::new())
+        .with_latency(ChainId::ETHEREUM, Duration::from_millis(20))
+        .fail_next(ChainId::ETHEREUM, 1);
+    let result = mock.query(&request);
+
+    let chains: Vec<ChainId> = result.timings.iter().map(|t| t.chain_id).collect();
+    assert_eq!(chains, [ChainId::ETHEREUM, ChainId::BSC]);
+    assert!(result.timings[0].elapsed >= Duration::from_millis(20));
+
+    let errors: Vec<String> = result
+        .outcomes()
+        .into_iter()
+        .map(|outcome| match outcome.outcome {
+            PerChainOutcome::Error(err) => err.to_string(),
+            other => panic!("unexpected outcome {:?}", other),
+        })
+        .collect();
+    assert_eq!(errors, ["InjectedFailure", "MissingRpcUrl"]);
+    assert_eq!(
+        result.into_response().unwrap_err().to_string(),
+        "InjectedFailure"
+    );
+
+    // The injected failure is used up, so only the missing url remains.
+    let result = mock.query(&request);
+    assert_eq!(
+        result.results[0].as_ref().unwrap_err().to_string(),
+        "MissingRpcUrl"
+    );
+}
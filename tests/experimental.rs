@@ -0,0 +1,67 @@
+use wormhole_query_sdk::structs::*;
+
+// eth_call_with_overrides on Ethereum at "latest", without overrides or calls.
+const OVERRIDES_REQUEST: &str = concat!(
+    "0002",
+    "06",
+    "0000000c",
+    "00000006",
+    "6c6174657374",
+    "00",
+    "00",
+);
+
+// Its response at block 100, with no results.
+const OVERRIDES_RESPONSE: &str = concat!(
+    "0002",
+    "06",
+    "00000031",
+    "0000000000000064",
+    "0101010101010101010101010101010101010101010101010101010101010101",
+    "0000000000000010",
+    "00",
+);
+
+// near_view_call on NEAR at "final", without calls.
+const NEAR_VIEW_CALL_REQUEST: &str =
+    concat!("000f", "07", "0000000a", "00000005", "66696e616c", "00");
+
+#[cfg(not(feature = "experimental"))]
+#[test]
+fn default_parser_rejects_types_the_guardian_does_not_serve() {
+    for request in [OVERRIDES_REQUEST, NEAR_VIEW_CALL_REQUEST] {
+        let request = hex::decode(request).unwrap();
+        assert_eq!(
+            PerChainQueryRequest::deserialize(&request)
+                .unwrap_err()
+                .to_string(),
+            "UnsupportedQueryType"
+        );
+    }
+    let response = hex::decode(OVERRIDES_RESPONSE).unwrap();
+    assert_eq!(
+        PerChainQueryResponse::deserialize(&response)
+            .unwrap_err()
+            .to_string(),
+        "UnsupportedResponseType"
+    );
+}
+
+#[cfg(feature = "experimental")]
+#[test]
+fn experimental_types_round_trip() {
+    let request = hex::decode(OVERRIDES_REQUEST).unwrap();
+    let parsed = PerChainQueryRequest::deserialize(&request).unwrap();
+    assert_eq!(parsed.query.query_type(), 6);
+    assert_eq!(parsed.serialize().unwrap(), request);
+
+    let request = hex::decode(NEAR_VIEW_CALL_REQUEST).unwrap();
+    let parsed = PerChainQueryRequest::deserialize(&request).unwrap();
+    assert_eq!(parsed.query.query_type(), 7);
+    assert_eq!(parsed.serialize().unwrap(), request);
+
+    let response = hex::decode(OVERRIDES_RESPONSE).unwrap();
+    let parsed = PerChainQueryResponse::deserialize(&response).unwrap();
+    assert_eq!(parsed.response.block_number(), 100);
+    assert_eq!(parsed.serialize().unwrap(), response);
+}
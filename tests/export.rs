@@ -0,0 +1,31 @@
+use wormhole_query_sdk::chain::ChainId;
+use wormhole_query_sdk::export::ExportRecord;
+
+fn record(target: &str) -> ExportRecord {
+    ExportRecord {
+        nonce: 1,
+        response_index: 0,
+        chain_id: ChainId::NEAR,
+        query_type: 7,
+        block_number: 2,
+        block_hash: "99".repeat(32),
+        block_time: 3,
+        block_age: None,
+        result_index: 0,
+        target: target.to_string(),
+        result: "00".to_string(),
+    }
+}
+
+#[test]
+fn csv_rows_quote_hostile_account_ids() {
+    let hash = "99".repeat(32);
+    assert_eq!(
+        record("wormhole.near").to_csv_row(),
+        format!("1,0,15,7,2,{},3,,0,wormhole.near,00", hash)
+    );
+    assert_eq!(
+        record("a,\"b\"\nc").to_csv_row(),
+        format!("1,0,15,7,2,{},3,,0,\"a,\"\"b\"\"\nc\",00", hash)
+    );
+}
@@ -0,0 +1,89 @@
+use wormhole_query_sdk::chain::ChainId;
+use wormhole_query_sdk::structs::*;
+
+fn eth_call(block_number: u64) -> (ChainSpecificQuery, ChainSpecificResponse) {
+    (
+        ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+            block_tag: format!("0x{:x}", block_number),
+            call_data: vec![],
+        }),
+        ChainSpecificResponse::EthCallQueryResponse(EthCallQueryResponse {
+            block_number,
+            block_hash: [1; 32],
+            block_time: 10,
+            results: vec![],
+        }),
+    )
+}
+
+fn response(chains: &[(u16, u64)]) -> QueryResponse {
+    let (requests, responses) = chains
+        .iter()
+        .map(|&(chain_id, block_number)| {
+            let (query, response) = eth_call(block_number);
+            (
+                PerChainQueryRequest::new(chain_id, query),
+                PerChainQueryResponse::new(chain_id, response),
+            )
+        })
+        .unzip();
+    QueryResponse {
+        version: QueryResponse::RESPONSE_VERSION,
+        request_chain_id: 0,
+        request_id: vec![0; 65],
+        request: QueryRequest {
+            version: QueryRequest::REQUEST_VERSION,
+            nonce: 1,
+            requests,
+        },
+        responses,
+    }
+}
+
+#[test]
+fn first_response_for_the_chain_is_found() {
+    let response = response(&[(2, 100), (5, 200), (5, 300)]);
+    let bytes = response.serialize().unwrap();
+    assert_eq!(
+        QueryResponse::find_chain(&bytes, ChainId::POLYGON).unwrap(),
+        Some(response.responses[1].clone())
+    );
+    assert_eq!(
+        QueryResponse::find_chain(&bytes, ChainId::ETHEREUM).unwrap(),
+        Some(response.responses[0].clone())
+    );
+}
+
+#[test]
+fn missing_chain_is_none() {
+    let bytes = response(&[(2, 100)]).serialize().unwrap();
+    assert_eq!(
+        QueryResponse::find_chain(&bytes, ChainId::SOLANA).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn length_prefixes_past_the_end_are_rejected() {
+    let response = response(&[(2, 100), (5, 200)]);
+    let bytes = response.serialize().unwrap();
+    // The first per chain response starts right after the request and the response count.
+    let request_len = response.request.serialize().unwrap().len();
+    let first = 1 + 2 + 65 + 4 + request_len + 1;
+    let len_at = first + 2 + 1;
+
+    let mut skipped_too_far = bytes.clone();
+    skipped_too_far[len_at..len_at + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+    let err = QueryResponse::find_chain(&skipped_too_far, ChainId::POLYGON).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+
+    let mut body_too_long = bytes.clone();
+    body_too_long[len_at..len_at + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+    let err = QueryResponse::find_chain(&body_too_long, ChainId::ETHEREUM).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+
+    let mut request_too_long = bytes;
+    request_too_long[1 + 2 + 65..1 + 2 + 65 + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+    let err = QueryResponse::find_chain(&request_too_long, ChainId::ETHEREUM).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+}
@@ -0,0 +1,129 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use wormhole_query_sdk::chain::ChainId;
+use wormhole_query_sdk::structs::*;
+
+const BLOCK_TIME_MICROS: u64 = 1_700_000_000_000_000;
+
+fn eth_call(block_number: u64, block_time: u64) -> ChainSpecificResponse {
+    ChainSpecificResponse::EthCallQueryResponse(EthCallQueryResponse {
+        block_number,
+        block_hash: [1; 32],
+        block_time,
+        results: vec![],
+    })
+}
+
+fn response(responses: Vec<(u16, ChainSpecificResponse)>) -> QueryResponse {
+    QueryResponse {
+        version: QueryResponse::RESPONSE_VERSION,
+        request_chain_id: 0,
+        request_id: vec![0; 65],
+        request: QueryRequest {
+            version: QueryRequest::REQUEST_VERSION,
+            nonce: 1,
+            requests: vec![],
+        },
+        responses: responses
+            .into_iter()
+            .map(|(chain_id, response)| PerChainQueryResponse::new(chain_id, response))
+            .collect(),
+    }
+}
+
+#[test]
+fn block_age_is_checked_to_the_microsecond() {
+    let response = response(vec![
+        (2, eth_call(100, BLOCK_TIME_MICROS)),
+        (5, eth_call(200, BLOCK_TIME_MICROS + 1_000_000)),
+    ]);
+    let max_age = Duration::from_secs(60);
+    let now = UNIX_EPOCH + Duration::from_micros(BLOCK_TIME_MICROS) + max_age;
+    response.check_max_block_age(max_age, now).unwrap();
+    assert_eq!(
+        response
+            .check_max_block_age(max_age, now + Duration::from_micros(1))
+            .unwrap_err()
+            .to_string(),
+        "StaleBlock"
+    );
+}
+
+#[test]
+fn blocks_from_the_future_are_not_stale() {
+    let response = response(vec![(2, eth_call(100, BLOCK_TIME_MICROS))]);
+    let now = UNIX_EPOCH + Duration::from_micros(BLOCK_TIME_MICROS) - Duration::from_secs(5);
+    response.check_max_block_age(Duration::ZERO, now).unwrap();
+}
+
+#[test]
+fn min_block_number_applies_to_every_response_for_the_chain() {
+    let response = response(vec![
+        (2, eth_call(100, BLOCK_TIME_MICROS)),
+        (5, eth_call(50, BLOCK_TIME_MICROS)),
+        (2, eth_call(120, BLOCK_TIME_MICROS)),
+    ]);
+    response
+        .check_min_block_number(ChainId::ETHEREUM, 100)
+        .unwrap();
+    assert_eq!(
+        response
+            .check_min_block_number(ChainId::ETHEREUM, 101)
+            .unwrap_err()
+            .to_string(),
+        "BlockNumberTooLow"
+    );
+    response
+        .check_min_block_number(ChainId::POLYGON, 50)
+        .unwrap();
+}
+
+#[test]
+fn min_block_number_fails_for_a_missing_chain() {
+    let response = response(vec![(2, eth_call(100, BLOCK_TIME_MICROS))]);
+    assert_eq!(
+        response
+            .check_min_block_number(ChainId::SOLANA, 0)
+            .unwrap_err()
+            .to_string(),
+        "ChainNotFound"
+    );
+}
+
+#[test]
+fn continuity_allows_new_and_repeated_blocks() {
+    let previous = response(vec![
+        (2, eth_call(100, BLOCK_TIME_MICROS)),
+        (5, eth_call(200, BLOCK_TIME_MICROS)),
+    ]);
+    let next = response(vec![
+        (2, eth_call(100, BLOCK_TIME_MICROS)),
+        (5, eth_call(201, BLOCK_TIME_MICROS)),
+    ]);
+    next.check_continuity(&previous).unwrap();
+}
+
+#[test]
+fn continuity_detects_regressions_and_reorgs() {
+    let previous = response(vec![(2, eth_call(100, BLOCK_TIME_MICROS))]);
+    let check = |next: QueryResponse| next.check_continuity(&previous).unwrap_err().to_string();
+
+    assert_eq!(
+        check(response(vec![(2, eth_call(99, BLOCK_TIME_MICROS))])),
+        "BlockNumberRegressed"
+    );
+
+    let mut reorged = response(vec![(2, eth_call(100, BLOCK_TIME_MICROS))]);
+    if let ChainSpecificResponse::EthCallQueryResponse(response) =
+        &mut reorged.responses[0].response
+    {
+        response.block_hash = [2; 32];
+    }
+    assert_eq!(check(reorged), "BlockHashMismatch");
+
+    assert_eq!(
+        check(response(vec![(5, eth_call(100, BLOCK_TIME_MICROS))])),
+        "ChainIdMismatch"
+    );
+    assert_eq!(check(response(vec![])), "ResponseCountMismatch");
+}
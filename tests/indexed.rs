@@ -0,0 +1,81 @@
+use wormhole_query_sdk::chain::ChainId;
+use wormhole_query_sdk::structs::*;
+
+fn eth_call(block_tag: &str) -> ChainSpecificQuery {
+    ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+        block_tag: block_tag.to_string(),
+        call_data: vec![],
+    })
+}
+
+fn eth_response(block_number: u64) -> ChainSpecificResponse {
+    ChainSpecificResponse::EthCallQueryResponse(EthCallQueryResponse {
+        block_number,
+        block_hash: [1; 32],
+        block_time: 10,
+        results: vec![],
+    })
+}
+
+fn response() -> QueryResponse {
+    let request = QueryRequest {
+        version: QueryRequest::REQUEST_VERSION,
+        nonce: 1,
+        requests: vec![
+            PerChainQueryRequest::new(5, eth_call("0x1")),
+            PerChainQueryRequest::new(2, eth_call("0x2")),
+            PerChainQueryRequest::new(5, eth_call("0x3")),
+        ],
+    };
+    QueryResponse::from_parts(
+        request,
+        vec![eth_response(1), eth_response(2), eth_response(3)],
+    )
+    .unwrap()
+}
+
+fn block_number(response: &PerChainQueryResponse) -> u64 {
+    match &response.response {
+        ChainSpecificResponse::EthCallQueryResponse(response) => response.block_number,
+        response => panic!("unexpected response {response:?}"),
+    }
+}
+
+#[test]
+fn entries_are_keyed_by_chain_and_position() {
+    let response = response();
+    let indexed = response.indexed().unwrap();
+    assert_eq!(indexed.len(), 3);
+
+    let (request, second) = indexed.get(ChainId::POLYGON, 1).unwrap();
+    assert_eq!(request.query, eth_call("0x3"));
+    assert_eq!(block_number(second), 3);
+    assert!(indexed.get(ChainId::POLYGON, 2).is_none());
+    assert!(indexed.get(ChainId::SOLANA, 0).is_none());
+
+    let polygon: Vec<u64> = indexed
+        .chain(ChainId::POLYGON)
+        .map(|(_, response)| block_number(response))
+        .collect();
+    assert_eq!(polygon, [1, 3]);
+
+    let keys: Vec<(ChainId, usize)> = indexed.iter().map(|(key, _)| key).collect();
+    assert_eq!(
+        keys,
+        [
+            (ChainId::ETHEREUM, 0),
+            (ChainId::POLYGON, 0),
+            (ChainId::POLYGON, 1)
+        ]
+    );
+}
+
+#[test]
+fn missing_responses_are_rejected() {
+    let mut response = response();
+    response.responses.pop();
+    assert_eq!(
+        response.indexed().unwrap_err().to_string(),
+        "RequestResponseMismatch"
+    );
+}
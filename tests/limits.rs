@@ -0,0 +1,94 @@
+use wormhole_query_sdk::structs::*;
+
+fn eth_call_request(calls: usize) -> QueryRequest {
+    QueryRequest {
+        version: QueryRequest::REQUEST_VERSION,
+        nonce: 1,
+        requests: vec![PerChainQueryRequest::new(
+            2,
+            ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+                block_tag: "latest".to_string(),
+                call_data: vec![
+                    EthCallData {
+                        to: [1; 20],
+                        data: vec![0x06, 0xfd, 0xde, 0x03],
+                    };
+                    calls
+                ],
+            }),
+        )],
+    }
+}
+
+#[test]
+fn requests_within_limits_parse() {
+    let request = eth_call_request(3);
+    let bytes = request.serialize().unwrap();
+    let limits = DeserializeLimits {
+        max_len: bytes.len(),
+        max_entries: 4,
+    };
+    assert_eq!(
+        QueryRequest::deserialize_with_limits(&bytes, &limits).unwrap(),
+        request
+    );
+    request.check_limits(&limits).unwrap();
+}
+
+#[test]
+fn long_payloads_are_rejected() {
+    let bytes = eth_call_request(3).serialize().unwrap();
+    let limits = DeserializeLimits {
+        max_len: bytes.len() - 1,
+        ..DeserializeLimits::default()
+    };
+    let err = QueryRequest::deserialize_with_limits(&bytes, &limits).unwrap_err();
+    assert_eq!(err.to_string(), "PayloadTooLong");
+}
+
+#[test]
+fn entries_are_counted_across_lists() {
+    // One per chain query and three calls.
+    let request = eth_call_request(3);
+    let limits = DeserializeLimits {
+        max_entries: 3,
+        ..DeserializeLimits::default()
+    };
+    let err =
+        QueryRequest::deserialize_with_limits(&request.serialize().unwrap(), &limits).unwrap_err();
+    assert_eq!(err.to_string(), "TooManyEntries");
+    assert_eq!(
+        request.check_limits(&limits).unwrap_err().to_string(),
+        "TooManyEntries"
+    );
+
+    // The embedded request counts towards a response's entries.
+    let response = QueryResponse::from_parts(
+        request,
+        vec![ChainSpecificResponse::EthCallQueryResponse(
+            EthCallQueryResponse {
+                block_number: 1,
+                block_hash: [0; 32],
+                block_time: 0,
+                results: vec![vec![0; 32]; 3],
+            },
+        )],
+    )
+    .unwrap();
+    let limits = DeserializeLimits {
+        max_entries: 7,
+        ..DeserializeLimits::default()
+    };
+    let err = QueryResponse::deserialize_with_limits(&response.serialize().unwrap(), &limits)
+        .unwrap_err();
+    assert_eq!(err.to_string(), "TooManyEntries");
+}
+
+#[test]
+fn lengths_past_the_input_fail_before_allocating() {
+    let mut bytes = eth_call_request(1).serialize().unwrap();
+    // The block tag length, after the version, nonce, count, chain, type and query length.
+    bytes[13..17].copy_from_slice(&u32::MAX.to_be_bytes());
+    let err = QueryRequest::deserialize(&bytes).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+}
@@ -0,0 +1,68 @@
+use wormhole_query_sdk::structs::*;
+
+fn response(request_chain_id: u16) -> QueryResponse {
+    QueryResponse {
+        version: QueryResponse::RESPONSE_VERSION,
+        request_chain_id,
+        request_id: vec![0x11; if request_chain_id == 0 { 65 } else { 32 }],
+        request: QueryRequest {
+            version: QueryRequest::REQUEST_VERSION,
+            nonce: 9,
+            requests: vec![PerChainQueryRequest {
+                chain_id: 2,
+                query: ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+                    block_tag: "latest".to_string(),
+                    call_data: vec![EthCallData {
+                        to: [1; 20],
+                        data: vec![0x06, 0xfd, 0xde, 0x03],
+                    }],
+                }),
+            }],
+        },
+        responses: vec![PerChainQueryResponse {
+            chain_id: 2,
+            response: ChainSpecificResponse::EthCallQueryResponse(EthCallQueryResponse {
+                block_number: 100,
+                block_hash: [2; 32],
+                block_time: 10,
+                results: vec![vec![3; 32]],
+            }),
+        }],
+    }
+}
+
+#[test]
+fn lite_round_trips_byte_identically() {
+    for request_chain_id in [0, 2] {
+        let response = response(request_chain_id);
+        let bytes = response.serialize().unwrap();
+        let lite = QueryResponseLite::deserialize(&bytes).unwrap();
+        assert_eq!(lite.request, response.request.serialize().unwrap());
+        assert_eq!(lite.parse_request().unwrap(), response.request);
+        assert_eq!(lite.responses, response.responses);
+        assert_eq!(lite.serialize().unwrap(), bytes);
+    }
+}
+
+#[test]
+fn request_bytes_are_kept_as_parsed() {
+    // A request the full parser rejects still round trips, as it is never parsed.
+    let mut bytes = response(0).serialize().unwrap();
+    let request_len_at = 1 + 2 + 65;
+    bytes[request_len_at + 4] = 0xff;
+    assert!(QueryResponse::deserialize(&bytes).is_err());
+
+    let lite = QueryResponseLite::deserialize(&bytes).unwrap();
+    assert!(lite.parse_request().is_err());
+    assert_eq!(lite.serialize().unwrap(), bytes);
+}
+
+#[test]
+fn request_id_must_match_the_request_chain() {
+    let mut lite = QueryResponseLite::deserialize(&response(0).serialize().unwrap()).unwrap();
+    lite.request_chain_id = 2;
+    assert_eq!(
+        lite.serialize().unwrap_err().to_string(),
+        "InvalidRequestIdLength"
+    );
+}
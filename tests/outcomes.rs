@@ -0,0 +1,84 @@
+use wormhole_query_sdk::structs::*;
+
+fn eth_call(calls: usize) -> ChainSpecificQuery {
+    ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+        block_tag: "latest".to_string(),
+        call_data: (0..calls)
+            .map(|_| EthCallData {
+                to: [1; 20],
+                data: vec![],
+            })
+            .collect(),
+    })
+}
+
+fn eth_response(results: usize) -> ChainSpecificResponse {
+    ChainSpecificResponse::EthCallQueryResponse(EthCallQueryResponse {
+        block_number: 100,
+        block_hash: [1; 32],
+        block_time: 10,
+        results: vec![vec![]; results],
+    })
+}
+
+fn response(requests: Vec<(u16, usize)>, responses: Vec<(u16, usize)>) -> QueryResponse {
+    QueryResponse {
+        version: QueryResponse::RESPONSE_VERSION,
+        request_chain_id: 0,
+        request_id: vec![0; 65],
+        request: QueryRequest {
+            version: QueryRequest::REQUEST_VERSION,
+            nonce: 1,
+            requests: requests
+                .into_iter()
+                .map(|(chain_id, calls)| PerChainQueryRequest::new(chain_id, eth_call(calls)))
+                .collect(),
+        },
+        responses: responses
+            .into_iter()
+            .map(|(chain_id, results)| PerChainQueryResponse::new(chain_id, eth_response(results)))
+            .collect(),
+    }
+}
+
+#[test]
+fn missing_and_failed_chains_are_reported_in_request_order() {
+    let response = response(vec![(2, 1), (5, 2), (6, 1)], vec![(2, 1), (6, 3)]);
+    let outcomes = response.outcomes().unwrap();
+    assert_eq!(outcomes.len(), 3);
+
+    assert_eq!(outcomes[0].request.chain_id, 2);
+    assert_eq!(outcomes[0].outcome.response(), Some(&response.responses[0]));
+    assert_eq!(outcomes[1].request.chain_id, 5);
+    assert!(matches!(outcomes[1].outcome, PerChainOutcome::Missing));
+    assert_eq!(outcomes[2].request.chain_id, 6);
+    match &outcomes[2].outcome {
+        PerChainOutcome::Error(err) => assert_eq!(err.to_string(), "ResultCountMismatch"),
+        outcome => panic!("unexpected outcome {outcome:?}"),
+    }
+
+    assert_eq!(
+        response.check_complete().unwrap_err().to_string(),
+        "MissingResponse"
+    );
+}
+
+#[test]
+fn complete_responses_pass() {
+    let response = response(vec![(2, 1), (5, 2)], vec![(2, 1), (5, 2)]);
+    assert!(response
+        .outcomes()
+        .unwrap()
+        .iter()
+        .all(|outcome| outcome.outcome.response().is_some()));
+    response.check_complete().unwrap();
+}
+
+#[test]
+fn extra_responses_are_rejected() {
+    let response = response(vec![(2, 1)], vec![(2, 1), (5, 1)]);
+    assert_eq!(
+        response.outcomes().unwrap_err().to_string(),
+        "UnexpectedResponse"
+    );
+}
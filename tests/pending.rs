@@ -0,0 +1,161 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use wormhole_query_sdk::chain::ChainId;
+use wormhole_query_sdk::pending::{PendingQuery, QueryTiming};
+use wormhole_query_sdk::structs::*;
+
+const NOW_MICROS: u64 = 1_700_000_000_000_000;
+const HOUR_MICROS: u64 = 3_600_000_000;
+
+fn now() -> SystemTime {
+    UNIX_EPOCH + Duration::from_micros(NOW_MICROS)
+}
+
+/// A pending query created at [`now`] accepting blocks up to a minute old, and a response
+/// to it.
+fn pending_response(
+    query: ChainSpecificQuery,
+    response: ChainSpecificResponse,
+) -> (PendingQuery, QueryResponse) {
+    let request = QueryRequest {
+        version: QueryRequest::REQUEST_VERSION,
+        nonce: 1,
+        requests: vec![PerChainQueryRequest::new(2, query)],
+    };
+    let response = QueryResponse {
+        version: QueryResponse::RESPONSE_VERSION,
+        request_chain_id: 0,
+        request_id: vec![0; 65],
+        request: request.clone(),
+        responses: vec![PerChainQueryResponse::new(2, response)],
+    };
+    let pending = PendingQuery {
+        request,
+        created_at: now(),
+        deadline: now() + Duration::from_secs(60),
+        max_block_age: Some(Duration::from_secs(60)),
+    };
+    (pending, response)
+}
+
+#[test]
+fn by_timestamp_responses_are_exempt_from_block_age() {
+    let target_time = NOW_MICROS - 24 * HOUR_MICROS;
+    let (pending, response) = pending_response(
+        ChainSpecificQuery::EthCallByTimestampQueryRequest(EthCallByTimestampQueryRequest {
+            target_timestamp: target_time,
+            target_block_hint: String::new(),
+            following_block_hint: String::new(),
+            call_data: vec![],
+        }),
+        ChainSpecificResponse::EthCallByTimestampQueryResponse(EthCallByTimestampQueryResponse {
+            target_block_number: 100,
+            target_block_hash: [1; 32],
+            target_block_time: target_time,
+            following_block_number: 101,
+            following_block_hash: [2; 32],
+            following_block_time: target_time + 12_000_000,
+            results: vec![],
+        }),
+    );
+    pending.accept(&response, now()).unwrap();
+}
+
+#[test]
+fn old_eth_call_blocks_are_rejected() {
+    let (pending, response) = pending_response(
+        ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+            block_tag: "latest".to_string(),
+            call_data: vec![],
+        }),
+        ChainSpecificResponse::EthCallQueryResponse(EthCallQueryResponse {
+            block_number: 100,
+            block_hash: [1; 32],
+            block_time: NOW_MICROS - HOUR_MICROS,
+            results: vec![],
+        }),
+    );
+    let err = pending.accept(&response, now()).unwrap_err();
+    assert_eq!(err.to_string(), "BlockTooOld");
+}
+
+#[test]
+fn huge_timeouts_never_expire() {
+    let request = QueryRequest {
+        version: QueryRequest::REQUEST_VERSION,
+        nonce: 1,
+        requests: vec![],
+    };
+    let pending = PendingQuery::new(request, Duration::MAX);
+    assert!(pending.deadline > pending.created_at);
+    assert!(!pending.is_expired(SystemTime::now() + Duration::from_secs(1 << 32)));
+}
+
+#[test]
+fn timing_converts_block_times_from_microseconds() {
+    let (pending, response) = pending_response(
+        ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+            block_tag: "latest".to_string(),
+            call_data: vec![],
+        }),
+        ChainSpecificResponse::EthCallQueryResponse(EthCallQueryResponse {
+            block_number: 100,
+            block_hash: [1; 32],
+            block_time: NOW_MICROS - 1_500_001,
+            results: vec![],
+        }),
+    );
+    let received_at = now() + Duration::from_millis(250);
+    let timing = pending.timing(&response, received_at);
+    assert_eq!(timing.latency, Duration::from_millis(250));
+    assert_eq!(timing.chains.len(), 1);
+    let chain = &timing.chains[0];
+    assert_eq!(chain.chain_id, ChainId::ETHEREUM);
+    assert_eq!(
+        chain.block_time,
+        UNIX_EPOCH + Duration::from_micros(NOW_MICROS - 1_500_001)
+    );
+    assert_eq!(chain.age_at_request, Duration::from_micros(1_500_001));
+    assert_eq!(chain.staleness, Duration::from_micros(1_750_001));
+    assert_eq!(timing.max_staleness(), Some(chain.staleness));
+}
+
+#[test]
+fn clock_skew_clamps_to_zero() {
+    let (_, response) = pending_response(
+        ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+            block_tag: "latest".to_string(),
+            call_data: vec![],
+        }),
+        ChainSpecificResponse::EthCallQueryResponse(EthCallQueryResponse {
+            block_number: 100,
+            block_hash: [1; 32],
+            block_time: NOW_MICROS + 2_000_000,
+            results: vec![],
+        }),
+    );
+    // Received before it was created, from a block after both.
+    let timing = QueryTiming::measure(now(), now() - Duration::from_secs(1), &response);
+    assert_eq!(timing.latency, Duration::ZERO);
+    assert_eq!(timing.chains[0].staleness, Duration::ZERO);
+    assert_eq!(timing.chains[0].age_at_request, Duration::ZERO);
+}
+
+#[test]
+fn largest_block_time_is_in_the_future() {
+    let (_, response) = pending_response(
+        ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+            block_tag: "latest".to_string(),
+            call_data: vec![],
+        }),
+        ChainSpecificResponse::EthCallQueryResponse(EthCallQueryResponse {
+            block_number: 100,
+            block_hash: [1; 32],
+            block_time: u64::MAX,
+            results: vec![],
+        }),
+    );
+    let timing = QueryTiming::measure(now(), now(), &response);
+    assert!(timing.chains[0].block_time > now());
+    assert_eq!(timing.max_staleness(), Some(Duration::ZERO));
+}
@@ -0,0 +1,67 @@
+use wormhole_query_sdk::chain::ChainId;
+use wormhole_query_sdk::plan::{EvmBlock, PlanValue, QueryPlan};
+use wormhole_query_sdk::structs::*;
+
+#[test]
+fn full_per_chain_requests_are_split() {
+    let mut plan = QueryPlan::new(1);
+    for i in 0..300u16 {
+        plan = plan.eth_call(
+            format!("call {}", i),
+            ChainId::ETHEREUM,
+            EvmBlock::At("latest".to_string()),
+            [1; 20],
+            i.to_be_bytes().to_vec(),
+        );
+    }
+    let compiled = plan.compile().unwrap();
+
+    let call_counts: Vec<usize> = compiled
+        .request
+        .requests
+        .iter()
+        .map(|request| match &request.query {
+            ChainSpecificQuery::EthCallQueryRequest(query) => query.call_data.len(),
+            other => panic!("unexpected query {:?}", other),
+        })
+        .collect();
+    assert_eq!(call_counts, [255, 45]);
+    compiled.request.serialize().unwrap();
+
+    // Each call returns its own calldata, so every label resolves to its index.
+    let responses = compiled
+        .request
+        .requests
+        .iter()
+        .map(|request| match &request.query {
+            ChainSpecificQuery::EthCallQueryRequest(query) => PerChainQueryResponse::new(
+                request.chain_id,
+                ChainSpecificResponse::EthCallQueryResponse(EthCallQueryResponse {
+                    block_number: 1,
+                    block_hash: [0; 32],
+                    block_time: 0,
+                    results: query
+                        .call_data
+                        .iter()
+                        .map(|call| call.data.clone())
+                        .collect(),
+                }),
+            ),
+            other => panic!("unexpected query {:?}", other),
+        })
+        .collect();
+    let response = QueryResponse {
+        version: QueryResponse::RESPONSE_VERSION,
+        request_chain_id: 0,
+        request_id: vec![0; 65],
+        request: compiled.request.clone(),
+        responses,
+    };
+    let results = compiled.resolve(&response).unwrap();
+    for i in [0u16, 254, 255, 299] {
+        assert_eq!(
+            results.get(&format!("call {}", i)),
+            Some(&PlanValue::Call(i.to_be_bytes().to_vec()))
+        );
+    }
+}
@@ -0,0 +1,236 @@
+#![cfg(feature = "policy")]
+
+use wormhole_query_sdk::chain::ChainId;
+use wormhole_query_sdk::policy::Policy;
+use wormhole_query_sdk::structs::*;
+
+const CONTRACT: [u8; 20] = [0xaa; 20];
+
+const PERMISSIONS: &str = r#"{
+  "Permissions": [
+    {
+      "userName": "Test User",
+      "apiKey": "my_secret_key",
+      "allowedCalls": [
+        {
+          "ethCall": {
+            "note:": "Name of WETH on Ethereum",
+            "chain": 2,
+            "contractAddress": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "call": "0x06fdde03"
+          }
+        },
+        {
+          "ethCallWithFinality": {
+            "chain": 2,
+            "contractAddress": "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "call": "0x00000001"
+          }
+        },
+        {
+          "solAccount": {
+            "chain": 1,
+            "account": "11111111111111111111111111111111"
+          }
+        },
+        {
+          "solAccount": {
+            "chain": 1,
+            "account": "0x0707070707070707070707070707070707070707070707070707070707070707"
+          }
+        }
+      ]
+    },
+    {
+      "apiKey": "anything_goes",
+      "allowAnything": true
+    }
+  ]
+}"#;
+
+fn policy() -> Policy {
+    Policy::from_json(PERMISSIONS, "my_secret_key").unwrap()
+}
+
+fn request(chain_id: u16, query: ChainSpecificQuery) -> QueryRequest {
+    QueryRequest {
+        version: QueryRequest::REQUEST_VERSION,
+        nonce: 1,
+        requests: vec![PerChainQueryRequest::new(chain_id, query)],
+    }
+}
+
+fn eth_call(to: [u8; 20], data: Vec<u8>) -> ChainSpecificQuery {
+    ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+        block_tag: "latest".to_string(),
+        call_data: vec![
+            EthCallData {
+                to: CONTRACT,
+                data: vec![0x06, 0xfd, 0xde, 0x03],
+            },
+            EthCallData { to, data },
+        ],
+    })
+}
+
+fn sol_account(accounts: Vec<[u8; 32]>) -> ChainSpecificQuery {
+    ChainSpecificQuery::SolanaAccountQueryRequest(SolanaAccountQueryRequest {
+        commitment: "finalized".to_string(),
+        min_context_slot: 0,
+        data_slice_offset: 0,
+        data_slice_length: 0,
+        accounts,
+    })
+}
+
+#[test]
+fn calls_match_on_contract_and_selector() {
+    let policy = policy();
+    assert_eq!(policy.user_name, "Test User");
+
+    // Only the first four bytes of the calldata are the selector.
+    policy
+        .check(&request(
+            2,
+            eth_call(CONTRACT, vec![0x06, 0xfd, 0xde, 0x03, 0x01]),
+        ))
+        .unwrap();
+
+    let violation = policy
+        .check(&request(
+            2,
+            eth_call(CONTRACT, vec![0x18, 0x16, 0x0d, 0xdd]),
+        ))
+        .unwrap_err();
+    assert_eq!(violation.request_index, 0);
+    assert_eq!(violation.item_index, Some(1));
+    assert_eq!(
+        violation.target.as_deref(),
+        Some("0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa:0x18160ddd")
+    );
+
+    let violation = policy
+        .check(&request(
+            2,
+            eth_call([0xbb; 20], vec![0x06, 0xfd, 0xde, 0x03]),
+        ))
+        .unwrap_err();
+    assert_eq!(violation.item_index, Some(1));
+
+    // The same call on another chain.
+    let violation = policy
+        .check(&request(5, eth_call(CONTRACT, vec![])))
+        .unwrap_err();
+    assert_eq!(
+        (violation.chain_id, violation.item_index),
+        (ChainId::POLYGON, Some(0))
+    );
+}
+
+#[test]
+fn short_calldata_is_zero_padded_into_a_selector() {
+    let query =
+        ChainSpecificQuery::EthCallWithFinalityQueryRequest(EthCallWithFinalityQueryRequest {
+            block_tag: "0x10".to_string(),
+            finality: "finalized".to_string(),
+            call_data: vec![EthCallData {
+                to: CONTRACT,
+                data: vec![0x00, 0x00, 0x00, 0x01],
+            }],
+        });
+    policy().check(&request(2, query)).unwrap();
+
+    let query =
+        ChainSpecificQuery::EthCallWithFinalityQueryRequest(EthCallWithFinalityQueryRequest {
+            block_tag: "0x10".to_string(),
+            finality: "finalized".to_string(),
+            call_data: vec![EthCallData {
+                to: CONTRACT,
+                data: vec![],
+            }],
+        });
+    let violation = policy().check(&request(2, query)).unwrap_err();
+    assert_eq!(
+        violation.target.as_deref(),
+        Some("0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa:0x00000000")
+    );
+}
+
+#[test]
+fn calls_are_allowed_per_query_type() {
+    // Allowed for eth_call_with_finality only.
+    let violation = policy()
+        .check(&request(
+            2,
+            eth_call(CONTRACT, vec![0x00, 0x00, 0x00, 0x01]),
+        ))
+        .unwrap_err();
+    assert_eq!((violation.query_type, violation.item_index), (1, Some(1)));
+}
+
+#[cfg(feature = "experimental")]
+#[test]
+fn query_types_without_permissions_are_rejected() {
+    let query = ChainSpecificQuery::NearViewCallQueryRequest(NearViewCallQueryRequest {
+        block_id: "final".to_string(),
+        calls: vec![],
+    });
+    let violation = policy().check(&request(15, query)).unwrap_err();
+    assert_eq!(
+        (violation.item_index, violation.target.as_deref()),
+        (None, None)
+    );
+    assert_eq!(
+        violation.to_string(),
+        "request 0 on chain Near: query type 7 not allowed"
+    );
+}
+
+#[test]
+fn accounts_match_in_base58_and_hex() {
+    let policy = policy();
+    policy
+        .check(&request(1, sol_account(vec![[0; 32], [7; 32]])))
+        .unwrap();
+
+    let violation = policy
+        .check(&request(1, sol_account(vec![[7; 32], [8; 32]])))
+        .unwrap_err();
+    assert_eq!(violation.item_index, Some(1));
+    assert_eq!(
+        violation.target,
+        Some(format!("0x{}", hex::encode([8; 32])))
+    );
+    assert_eq!(
+        violation.to_string(),
+        format!(
+            "request 0 on chain Solana: item 1 (0x{}) not allowed for query type 4",
+            hex::encode([8; 32])
+        )
+    );
+}
+
+#[test]
+fn allow_anything_skips_checks() {
+    let policy = Policy::from_json(PERMISSIONS, "anything_goes").unwrap();
+    policy
+        .check(&request(1, sol_account(vec![[8; 32]])))
+        .unwrap();
+}
+
+#[test]
+fn bad_permissions_are_rejected() {
+    assert_eq!(
+        Policy::from_json(PERMISSIONS, "unknown")
+            .unwrap_err()
+            .to_string(),
+        "UnknownApiKey"
+    );
+    let bad_selector = PERMISSIONS.replace("0x06fdde03", "0x06fdde");
+    assert_eq!(
+        Policy::from_json(&bad_selector, "my_secret_key")
+            .unwrap_err()
+            .to_string(),
+        "InvalidPermissions"
+    );
+}
@@ -0,0 +1,230 @@
+#![cfg(all(feature = "client", feature = "devnet", feature = "tokio"))]
+
+use std::future::poll_fn;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use wormhole_query_sdk::client::{QueryProxyClient, RecurringQuery};
+use wormhole_query_sdk::devnet;
+use wormhole_query_sdk::structs::*;
+
+/// Serves `/v1/query` on a local port, answering every request with a devnet signed
+/// response. Returns the proxy url.
+fn spawn_proxy() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut rdr = BufReader::new(stream.try_clone().unwrap());
+            while let Some(body) = read_http_body(&mut rdr) {
+                let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                let request = QueryRequest::from_hex(body["bytes"].as_str().unwrap()).unwrap();
+                let (bytes, signatures) = devnet::sign_query_response(&answer(request)).unwrap();
+                let reply = serde_json::json!({
+                    "bytes": hex::encode(bytes),
+                    "signatures": signatures.iter().map(hex::encode).collect::<Vec<_>>(),
+                })
+                .to_string();
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                    reply.len(),
+                    reply
+                )
+                .unwrap();
+            }
+        }
+    });
+    url
+}
+
+fn read_http_body(rdr: &mut impl BufRead) -> Option<Vec<u8>> {
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if rdr.read_line(&mut line).ok()? == 0 {
+            return None;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap();
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    rdr.read_exact(&mut body).ok()?;
+    Some(body)
+}
+
+fn answer(request: QueryRequest) -> QueryResponse {
+    let responses = request
+        .requests
+        .iter()
+        .map(|_| {
+            ChainSpecificResponse::EthCallQueryResponse(EthCallQueryResponse {
+                block_number: u64::from(request.nonce),
+                block_hash: [1; 32],
+                block_time: 1_700_000_000_000_000,
+                results: vec![vec![0; 32]],
+            })
+        })
+        .collect();
+    QueryResponse::from_parts(request, responses).unwrap()
+}
+
+/// A trigger standing in for a new block subscription.
+struct Blocks(std::vec::IntoIter<u64>);
+
+impl Stream for Blocks {
+    type Item = u64;
+
+    fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<u64>> {
+        Poll::Ready(self.get_mut().0.next())
+    }
+}
+
+async fn next<S: Stream + Unpin>(stream: &mut S) -> Option<S::Item> {
+    poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx)).await
+}
+
+/// One `totalSupply()` call on Ethereum.
+fn template(nonce: u32) -> QueryRequest {
+    QueryRequest {
+        version: QueryRequest::REQUEST_VERSION,
+        nonce,
+        requests: vec![PerChainQueryRequest::new(
+            2,
+            ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+                block_tag: "latest".to_string(),
+                call_data: vec![EthCallData {
+                    to: [1; 20],
+                    data: vec![0x18, 0x16, 0x0d, 0xdd],
+                }],
+            }),
+        )],
+    }
+}
+
+#[tokio::test]
+async fn one_round_per_block_with_increasing_nonces() {
+    let mut rounds = RecurringQuery::per_block(
+        QueryProxyClient::new(spawn_proxy(), "key"),
+        template(5),
+        devnet::devnet_guardian_set(),
+        Blocks(vec![100, 101, 102].into_iter()),
+    );
+
+    let mut nonces = Vec::new();
+    while let Some(verified) = next(&mut rounds).await {
+        nonces.push(verified.unwrap().response().request.nonce);
+    }
+    assert_eq!(nonces, [5, 6, 7]);
+}
+
+#[cfg(feature = "websocket")]
+mod websocket {
+    use super::*;
+    use futures_util::{SinkExt, StreamExt};
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::Message;
+    use wormhole_query_sdk::client::NewHeads;
+
+    /// Accepts one WebSocket connection, answers its `eth_subscribe` with `reply`, then
+    /// sends `notifications` and closes. Returns the node url.
+    async fn spawn_node(reply: serde_json::Value, notifications: Vec<serde_json::Value>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let request = socket.next().await.unwrap().unwrap();
+            let request: serde_json::Value =
+                serde_json::from_str(request.to_text().unwrap()).unwrap();
+            assert_eq!(request["method"], "eth_subscribe");
+            assert_eq!(request["params"], serde_json::json!(["newHeads"]));
+            socket.send(Message::text(reply.to_string())).await.unwrap();
+            for notification in notifications {
+                socket.send(Message::Ping(vec![].into())).await.unwrap();
+                socket
+                    .send(Message::text(notification.to_string()))
+                    .await
+                    .unwrap();
+            }
+            socket.close(None).await.unwrap();
+            while socket.next().await.is_some() {}
+        });
+        url
+    }
+
+    fn head(subscription: &str, number: &str) -> serde_json::Value {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "eth_subscription",
+            "params": {
+                "subscription": subscription,
+                "result": { "number": number, "hash": "0x01" },
+            },
+        })
+    }
+
+    fn subscribed() -> serde_json::Value {
+        serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": "0xabc" })
+    }
+
+    #[tokio::test]
+    async fn new_heads_yields_block_numbers_until_the_node_closes() {
+        let url = spawn_node(
+            subscribed(),
+            vec![
+                head("0xabc", "0x64"),
+                // Another subscription on the same connection.
+                head("0xdef", "0x1000"),
+                head("0xabc", "0x65"),
+            ],
+        )
+        .await;
+        let heads = NewHeads::subscribe(&url).await.unwrap();
+        assert_eq!(heads.collect::<Vec<u64>>().await, [100, 101]);
+    }
+
+    #[tokio::test]
+    async fn rejected_subscriptions_are_errors() {
+        let reply = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "error": { "code": -32601, "message": "the method eth_subscribe does not exist" },
+        });
+        let url = spawn_node(reply, vec![]).await;
+        let err = NewHeads::subscribe(&url).await.unwrap_err();
+        assert_eq!(err.to_string(), "SubscriptionRejected");
+    }
+
+    #[tokio::test]
+    async fn one_round_per_new_head() {
+        let url = spawn_node(
+            subscribed(),
+            vec![head("0xabc", "0x64"), head("0xabc", "0x65")],
+        )
+        .await;
+        let rounds = RecurringQuery::per_block(
+            QueryProxyClient::new(spawn_proxy(), "key"),
+            template(9),
+            devnet::devnet_guardian_set(),
+            NewHeads::subscribe(&url).await.unwrap(),
+        );
+
+        let nonces: Vec<u32> = rounds
+            .map(|verified| verified.unwrap().response().request.nonce)
+            .collect()
+            .await;
+        assert_eq!(nonces, [9, 10]);
+    }
+}
@@ -0,0 +1,146 @@
+use wormhole_query_sdk::structs::*;
+
+fn eth_call(results: Vec<Vec<u8>>) -> QueryResponse {
+    let request = QueryRequest {
+        version: QueryRequest::REQUEST_VERSION,
+        nonce: 1,
+        requests: vec![PerChainQueryRequest::new(
+            2,
+            ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+                block_tag: "latest".to_string(),
+                call_data: results
+                    .iter()
+                    .map(|_| EthCallData {
+                        to: [1; 20],
+                        data: vec![],
+                    })
+                    .collect(),
+            }),
+        )],
+    };
+    QueryResponse::from_parts(
+        request,
+        vec![ChainSpecificResponse::EthCallQueryResponse(
+            EthCallQueryResponse {
+                block_number: 100,
+                block_hash: [1; 32],
+                block_time: 10,
+                results,
+            },
+        )],
+    )
+    .unwrap()
+}
+
+fn shape_error(response: &QueryResponse, abi_words: bool) -> String {
+    response
+        .check_result_shapes(abi_words)
+        .unwrap_err()
+        .to_string()
+}
+
+#[test]
+fn abi_words_requires_whole_words() {
+    let words = eth_call(vec![vec![0; 32], vec![0; 64], vec![]]);
+    words.check_result_shapes(true).unwrap();
+
+    let unaligned = eth_call(vec![vec![0; 32], vec![0; 33]]);
+    unaligned.check_result_shapes(false).unwrap();
+    assert_eq!(shape_error(&unaligned, true), "UnalignedResult");
+}
+
+#[test]
+fn responses_must_line_up_with_requests() {
+    let mut other_chain = eth_call(vec![vec![0; 32]]);
+    other_chain.responses[0].chain_id = 5;
+    assert_eq!(shape_error(&other_chain, false), "RequestResponseMismatch");
+
+    let mut missing = eth_call(vec![vec![0; 32]]);
+    missing.responses.clear();
+    assert_eq!(shape_error(&missing, false), "RequestResponseMismatch");
+
+    let mut extra_result = eth_call(vec![vec![0; 32]]);
+    if let ChainSpecificResponse::EthCallQueryResponse(response) =
+        &mut extra_result.responses[0].response
+    {
+        response.results.push(vec![]);
+    }
+    assert_eq!(shape_error(&extra_result, false), "ResultCountMismatch");
+}
+
+#[test]
+fn following_block_must_follow_the_target() {
+    let request = QueryRequest {
+        version: QueryRequest::REQUEST_VERSION,
+        nonce: 1,
+        requests: vec![PerChainQueryRequest::new(
+            2,
+            ChainSpecificQuery::EthCallByTimestampQueryRequest(EthCallByTimestampQueryRequest {
+                target_timestamp: 10,
+                target_block_hint: String::new(),
+                following_block_hint: String::new(),
+                call_data: vec![],
+            }),
+        )],
+    };
+    let response = |following_block_number| {
+        QueryResponse::from_parts(
+            request.clone(),
+            vec![ChainSpecificResponse::EthCallByTimestampQueryResponse(
+                EthCallByTimestampQueryResponse {
+                    target_block_number: 100,
+                    target_block_hash: [1; 32],
+                    target_block_time: 10,
+                    following_block_number,
+                    following_block_hash: [2; 32],
+                    following_block_time: 20,
+                    results: vec![],
+                },
+            )],
+        )
+        .unwrap()
+    };
+    response(101).check_result_shapes(true).unwrap();
+    assert_eq!(shape_error(&response(102), true), "InvalidFollowingBlock");
+}
+
+#[test]
+fn account_data_must_fit_the_slice() {
+    let request = QueryRequest {
+        version: QueryRequest::REQUEST_VERSION,
+        nonce: 1,
+        requests: vec![PerChainQueryRequest::new(
+            1,
+            ChainSpecificQuery::SolanaAccountQueryRequest(SolanaAccountQueryRequest {
+                commitment: "finalized".to_string(),
+                min_context_slot: 0,
+                data_slice_offset: 0,
+                data_slice_length: 8,
+                accounts: vec![[1; 32]],
+            }),
+        )],
+    };
+    let response = |data_len| {
+        QueryResponse::from_parts(
+            request.clone(),
+            vec![ChainSpecificResponse::SolanaAccountQueryResponse(
+                SolanaAccountQueryResponse {
+                    slot_number: 100,
+                    block_time: 10,
+                    block_hash: [1; 32],
+                    results: vec![SolanaAccountResult {
+                        lamports: 1,
+                        rent_epoch: 2,
+                        executable: false,
+                        owner: [3; 32],
+                        data: vec![0; data_len],
+                    }],
+                },
+            )],
+        )
+        .unwrap()
+    };
+    // Account data is not an ABI word, so abi_words does not apply to it.
+    response(5).check_result_shapes(true).unwrap();
+    assert_eq!(shape_error(&response(9), false), "AccountDataTooLong");
+}
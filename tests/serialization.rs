@@ -0,0 +1,163 @@
+use wormhole_query_sdk::structs::*;
+
+// Expected encodings follow the layout written by the TS SDK
+// (@wormhole-foundation/wormhole-query-sdk), including its per chain length prefixes.
+
+const WMATIC: [u8; 20] = [
+    0x7c, 0xeb, 0x23, 0xfd, 0x6b, 0xc0, 0xad, 0xd5, 0x9e, 0x62, 0xac, 0x25, 0x57, 0x82, 0x70, 0xcf,
+    0xf1, 0xb9, 0xf6, 0x19,
+];
+
+// Polygon eth_call of name() and totalSupply() on WMATIC at block 0x28d9630, nonce 1.
+const ETH_CALL_REQUEST: &str = "0100000001010005010000004600000009307832386439363330027ceb23fd6bc0add59e62ac25578270cff1b9f6190000000406fdde037ceb23fd6bc0add59e62ac25578270cff1b9f6190000000418160ddd";
+
+fn eth_call_request() -> QueryRequest {
+    QueryRequest {
+        version: QueryRequest::REQUEST_VERSION,
+        nonce: 1,
+        requests: vec![PerChainQueryRequest::new(
+            5,
+            ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+                block_tag: "0x28d9630".to_string(),
+                call_data: vec![
+                    EthCallData {
+                        to: WMATIC,
+                        data: vec![0x06, 0xfd, 0xde, 0x03],
+                    },
+                    EthCallData {
+                        to: WMATIC,
+                        data: vec![0x18, 0x16, 0x0d, 0xdd],
+                    },
+                ],
+            }),
+        )],
+    }
+}
+
+#[test]
+fn eth_call_request_matches_ts_sdk() {
+    let request = eth_call_request();
+    assert_eq!(request.to_hex().unwrap(), ETH_CALL_REQUEST);
+    assert_eq!(QueryRequest::from_hex(ETH_CALL_REQUEST).unwrap(), request);
+}
+
+#[test]
+fn solana_account_request_matches_ts_sdk() {
+    let mut first = [0u8; 32];
+    for (i, byte) in first.iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+    let request = QueryRequest {
+        version: QueryRequest::REQUEST_VERSION,
+        nonce: 42,
+        requests: vec![PerChainQueryRequest::new(
+            1,
+            ChainSpecificQuery::SolanaAccountQueryRequest(SolanaAccountQueryRequest {
+                commitment: "finalized".to_string(),
+                min_context_slot: 8069,
+                data_slice_offset: 10,
+                data_slice_length: 20,
+                accounts: vec![first, [0xff; 32]],
+            }),
+        )],
+    };
+
+    let expected = "010000002a01000104000000660000000966696e616c697a65640000000000001f85000000000000000a000000000000001402000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff";
+    assert_eq!(request.to_hex().unwrap(), expected);
+    assert_eq!(QueryRequest::from_hex(expected).unwrap(), request);
+}
+
+#[test]
+fn eth_call_response_matches_ts_sdk() {
+    let name_result = hex::decode("0000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000000d57726170706564204d6174696300000000000000000000000000000000000000").unwrap();
+    let total_supply_result =
+        hex::decode("0000000000000000000000000000000000000000007ae5649beabeddf889364a").unwrap();
+
+    let response = QueryResponse {
+        version: QueryResponse::RESPONSE_VERSION,
+        request_chain_id: 0,
+        request_id: vec![0x11; 65],
+        request: eth_call_request(),
+        responses: vec![PerChainQueryResponse::new(
+            5,
+            ChainSpecificResponse::EthCallQueryResponse(EthCallQueryResponse {
+                block_number: 42831408,
+                block_hash: [0x99; 32],
+                block_time: 1700000000000000,
+                results: vec![name_result, total_supply_result],
+            }),
+        )],
+    };
+
+    let expected = format!(
+        "010000{}00000053{}01000501000000b9{}{}{}02{}{}",
+        "11".repeat(65),
+        ETH_CALL_REQUEST,
+        "00000000028d8e30",
+        "99".repeat(32),
+        "00060a24181e4000",
+        "000000600000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000000d57726170706564204d6174696300000000000000000000000000000000000000",
+        "000000200000000000000000000000000000000000000000007ae5649beabeddf889364a",
+    );
+    assert_eq!(response.to_hex().unwrap(), expected);
+    assert_eq!(QueryResponse::from_hex(&expected).unwrap(), response);
+}
+
+#[test]
+fn per_chain_lengths_are_computed() {
+    let per_chain = &eth_call_request().requests[0];
+    let bytes = per_chain.serialize().unwrap();
+    let body_len = u32::from_be_bytes(bytes[3..7].try_into().unwrap()) as usize;
+    assert_eq!(body_len, bytes.len() - 7);
+    assert_eq!(
+        PerChainQueryRequest::deserialize(&bytes).unwrap(),
+        *per_chain
+    );
+}
+
+#[cfg(feature = "experimental")]
+#[test]
+fn near_view_account_round_trips() {
+    let request = QueryRequest {
+        version: QueryRequest::REQUEST_VERSION,
+        nonce: 7,
+        requests: vec![PerChainQueryRequest::new(
+            15,
+            ChainSpecificQuery::NearViewAccountQueryRequest(NearViewAccountQueryRequest {
+                block_id: "final".to_string(),
+                account_ids: vec!["wormhole.near".to_string(), "token.near".to_string()],
+            }),
+        )],
+    };
+    let result = NearAccountResult {
+        amount: 10u128.pow(24) + 1,
+        locked: 0,
+        code_hash: [3; 32],
+        storage_usage: 182,
+    };
+    let response = QueryResponse {
+        version: QueryResponse::RESPONSE_VERSION,
+        request_chain_id: 0,
+        request_id: vec![0; 65],
+        request: request.clone(),
+        responses: vec![PerChainQueryResponse::new(
+            15,
+            ChainSpecificResponse::NearViewAccountQueryResponse(NearViewAccountQueryResponse {
+                block_height: 100,
+                block_hash: [1; 32],
+                block_time: 1_700_000_000_000_000,
+                results: vec![result.clone(), result],
+            }),
+        )],
+    };
+
+    let bytes = response.serialize().unwrap();
+    let decoded = QueryResponse::deserialize(&bytes).unwrap();
+    assert_eq!(decoded, response);
+    assert_eq!(decoded.request.requests[0].query.query_type(), 8);
+    decoded.check_result_shapes(true).unwrap();
+    assert_eq!(
+        QueryRequest::deserialize(&request.serialize().unwrap()).unwrap(),
+        request
+    );
+}
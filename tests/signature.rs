@@ -0,0 +1,138 @@
+#![cfg(feature = "devnet")]
+
+use std::time::{Duration, UNIX_EPOCH};
+
+use wormhole_query_sdk::devnet::{self, DEVNET_GUARDIAN_PRIVATE_KEY};
+use wormhole_query_sdk::signature::{
+    query_response_digest, recover_address, sign_response, verify_signatures, GuardianSet,
+    GuardianSetRegistry, VerifiedResponse,
+};
+use wormhole_query_sdk::structs::*;
+
+fn response_bytes() -> Vec<u8> {
+    let request = QueryRequest {
+        version: QueryRequest::REQUEST_VERSION,
+        nonce: 1,
+        requests: vec![PerChainQueryRequest {
+            chain_id: 2,
+            query: ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+                block_tag: "latest".to_string(),
+                call_data: vec![EthCallData {
+                    to: [1; 20],
+                    data: vec![0x18, 0x16, 0x0d, 0xdd],
+                }],
+            }),
+        }],
+    };
+    QueryResponse {
+        version: QueryResponse::RESPONSE_VERSION,
+        request_chain_id: 0,
+        request_id: vec![0; 65],
+        request,
+        responses: vec![PerChainQueryResponse {
+            chain_id: 2,
+            response: ChainSpecificResponse::EthCallQueryResponse(EthCallQueryResponse {
+                block_number: 100,
+                block_hash: [2; 32],
+                block_time: 1_700_000_000_000_000,
+                results: vec![vec![0; 32]],
+            }),
+        }],
+    }
+    .serialize()
+    .unwrap()
+}
+
+/// The devnet guardian at index 0 followed by three more, with a quorum of three.
+fn four_guardians() -> (GuardianSet, [[u8; 32]; 4]) {
+    let keys = [DEVNET_GUARDIAN_PRIVATE_KEY, [1; 32], [2; 32], [3; 32]];
+    let digest = query_response_digest(b"");
+    let addresses = keys
+        .iter()
+        .map(|key| recover_address(&digest, &sign_response(b"", key, 0).unwrap()[..65]).unwrap())
+        .collect();
+    let guardian_set = GuardianSet {
+        index: 0,
+        keys: addresses,
+    };
+    assert_eq!(guardian_set.quorum(), 3);
+    (guardian_set, keys)
+}
+
+fn sign_by(response: &[u8], keys: &[[u8; 32]], indices: &[u8]) -> Vec<[u8; 66]> {
+    indices
+        .iter()
+        .map(|&index| sign_response(response, &keys[usize::from(index)], index).unwrap())
+        .collect()
+}
+
+#[test]
+fn signed_responses_verify() {
+    let bytes = response_bytes();
+    let signatures = devnet::sign_response(&bytes).unwrap();
+    let guardian_set = devnet::devnet_guardian_set();
+    verify_signatures(&bytes, &signatures, &guardian_set).unwrap();
+    let verified = VerifiedResponse::verify(bytes.clone(), signatures, &guardian_set).unwrap();
+    assert_eq!(verified.bytes(), bytes);
+}
+
+#[test]
+fn quorum_is_required() {
+    let bytes = response_bytes();
+    let (guardian_set, keys) = four_guardians();
+
+    let err =
+        verify_signatures(&bytes, &sign_by(&bytes, &keys, &[0, 2]), &guardian_set).unwrap_err();
+    assert_eq!(err.to_string(), "NoQuorum");
+
+    verify_signatures(&bytes, &sign_by(&bytes, &keys, &[0, 2, 3]), &guardian_set).unwrap();
+    verify_signatures(
+        &bytes,
+        &sign_by(&bytes, &keys, &[0, 1, 2, 3]),
+        &guardian_set,
+    )
+    .unwrap();
+}
+
+#[test]
+fn duplicate_guardian_indices_are_rejected() {
+    let bytes = response_bytes();
+    let (guardian_set, keys) = four_guardians();
+    // Three signatures, but only two guardians.
+    let err =
+        verify_signatures(&bytes, &sign_by(&bytes, &keys, &[0, 1, 1]), &guardian_set).unwrap_err();
+    assert_eq!(err.to_string(), "SignatureIndicesNotAscending");
+}
+
+#[test]
+fn tampered_responses_are_rejected() {
+    let mut bytes = response_bytes();
+    let signatures = devnet::sign_response(&bytes).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 1;
+    let err = verify_signatures(&bytes, &signatures, &devnet::devnet_guardian_set()).unwrap_err();
+    assert_eq!(err.to_string(), "GuardianSignatureMismatch");
+}
+
+#[test]
+fn guardian_sets_expire_at_their_expiration_time() {
+    let bytes = response_bytes();
+    let signatures = devnet::sign_response(&bytes).unwrap();
+    let expiration = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let mut registry = GuardianSetRegistry::new(GuardianSet {
+        index: 1,
+        keys: vec![[9; 20]],
+    });
+    registry.insert(devnet::devnet_guardian_set(), Some(expiration));
+
+    registry
+        .verify(&bytes, &signatures, 0, expiration - Duration::from_secs(1))
+        .unwrap();
+    let err = registry
+        .verify(&bytes, &signatures, 0, expiration)
+        .unwrap_err();
+    assert_eq!(err.to_string(), "GuardianSetExpired");
+    let err = VerifiedResponse::verify_with_registry(bytes, signatures, &registry, 0, expiration)
+        .unwrap_err();
+    assert_eq!(err.to_string(), "GuardianSetExpired");
+}
@@ -0,0 +1,87 @@
+#![cfg(feature = "solana")]
+
+use wormhole_query_sdk::solana::*;
+
+const PROGRAM: [u8; 32] = [1; 32];
+const PAYER: [u8; 32] = [2; 32];
+const SIGNATURES: [u8; 32] = [3; 32];
+
+fn signatures(count: usize) -> Vec<[u8; 66]> {
+    (0..count).map(|i| [i as u8; 66]).collect()
+}
+
+#[test]
+fn post_signatures_data_is_borsh_encoded() {
+    let instruction = post_signatures(PROGRAM, PAYER, SIGNATURES, &signatures(2), 5);
+    assert_eq!(instruction.program_id, PROGRAM);
+    assert_eq!(
+        instruction.accounts,
+        [
+            AccountMeta::writable(PAYER, true),
+            AccountMeta::writable(SIGNATURES, true),
+            AccountMeta::readonly(SYSTEM_PROGRAM_ID, false),
+        ]
+    );
+    let data = &instruction.data[8..];
+    assert_eq!(data.len(), 4 + 2 * 66 + 1);
+    assert_eq!(data[..4], 2u32.to_le_bytes());
+    assert_eq!(data[4..70], [0; 66]);
+    assert_eq!(data[70..136], [1; 66]);
+    assert_eq!(data[136], 5);
+}
+
+#[test]
+fn signatures_are_split_across_instructions() {
+    let instructions =
+        post_signatures_chunked(PROGRAM, PAYER, SIGNATURES, &signatures(13)).unwrap();
+    let counts: Vec<(u32, u8)> = instructions
+        .iter()
+        .map(|instruction| {
+            let data = &instruction.data[8..];
+            (
+                u32::from_le_bytes(data[..4].try_into().unwrap()),
+                *data.last().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(counts, [(7, 13), (6, 13)]);
+    // Every chunk is the same instruction with different signatures.
+    assert_eq!(instructions[0].data[..8], instructions[1].data[..8]);
+    assert_eq!(instructions[1].data[12..78], [7; 66]);
+
+    assert_eq!(
+        post_signatures_chunked(PROGRAM, PAYER, SIGNATURES, &signatures(256))
+            .unwrap_err()
+            .to_string(),
+        "TooManySignatures"
+    );
+}
+
+#[test]
+fn verify_query_passes_response_and_guardian_set_index() {
+    let guardian_set = [4; 32];
+    let refund = [5; 32];
+    let instruction = verify_query(PROGRAM, guardian_set, 3, SIGNATURES, refund, &[0xaa, 0xbb]);
+    assert_eq!(
+        instruction.accounts,
+        [
+            AccountMeta::readonly(guardian_set, false),
+            AccountMeta::writable(SIGNATURES, false),
+            AccountMeta::writable(refund, false),
+        ]
+    );
+    assert_eq!(instruction.data[8..], [2, 0, 0, 0, 0xaa, 0xbb, 3, 0, 0, 0]);
+
+    let close = close_signatures(PROGRAM, SIGNATURES, refund);
+    assert_eq!(close.data.len(), 8);
+    assert_ne!(close.data, instruction.data[..8]);
+    assert_eq!(close.accounts[1], AccountMeta::writable(refund, true));
+}
+
+#[test]
+fn guardian_set_seeds_use_big_endian_index() {
+    assert_eq!(
+        guardian_set_seeds(0x0102),
+        [b"GuardianSet".to_vec(), vec![0, 0, 1, 2]]
+    );
+}
@@ -0,0 +1,68 @@
+use wormhole_query_sdk::structs::*;
+
+fn request(data_slice_offset: u64, data_slice_length: u64) -> SolanaAccountQueryRequest {
+    SolanaAccountQueryRequest {
+        commitment: "finalized".to_string(),
+        min_context_slot: 0,
+        data_slice_offset,
+        data_slice_length,
+        accounts: vec![[1; 32], [2; 32]],
+    }
+}
+
+fn response(data_lens: [usize; 2]) -> SolanaAccountQueryResponse {
+    SolanaAccountQueryResponse {
+        slot_number: 100,
+        block_time: 10,
+        block_hash: [3; 32],
+        results: data_lens
+            .into_iter()
+            .map(|data_len| SolanaAccountResult {
+                lamports: 1,
+                rent_epoch: 2,
+                executable: false,
+                owner: [4; 32],
+                data: vec![5; data_len],
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn data_range_starts_at_the_slice_offset() {
+    let response = response([20, 8]);
+    assert_eq!(response.results[0].data_range(&request(10, 20)), 10..30);
+    assert_eq!(response.results[1].data_range(&request(10, 20)), 10..18);
+    // Without a slice the offset is ignored and the whole account is returned.
+    assert_eq!(response.results[0].data_range(&request(10, 0)), 0..20);
+}
+
+#[test]
+fn data_range_saturates_at_the_largest_offset() {
+    let response = response([20, 0]);
+    let request = request(u64::MAX - 5, 20);
+    assert_eq!(
+        response.results[0].data_range(&request),
+        u64::MAX - 5..u64::MAX
+    );
+    assert_eq!(
+        response.results[1].data_range(&request),
+        u64::MAX - 5..u64::MAX - 5
+    );
+}
+
+#[test]
+fn data_longer_than_the_slice_is_rejected() {
+    response([20, 8]).check_data_slice(&request(0, 20)).unwrap();
+    assert_eq!(
+        response([20, 21])
+            .check_data_slice(&request(0, 20))
+            .unwrap_err()
+            .to_string(),
+        "AccountDataTooLong"
+    );
+    // Without a slice any amount of data is allowed.
+    response([20, 1000])
+        .check_data_slice(&request(0, 0))
+        .unwrap();
+}
@@ -0,0 +1,171 @@
+#![cfg(feature = "stats")]
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use wormhole_query_sdk::chain::ChainId;
+use wormhole_query_sdk::stats::ResponseStats;
+use wormhole_query_sdk::structs::*;
+
+/// Block time of every response, in microseconds.
+const BLOCK_TIME_MICROS: u64 = 1_700_000_000_000_000;
+
+fn response(per_chain: Vec<(u16, ChainSpecificResponse)>) -> QueryResponse {
+    QueryResponse {
+        version: QueryResponse::RESPONSE_VERSION,
+        request_chain_id: 0,
+        request_id: vec![0; 65],
+        request: QueryRequest {
+            version: QueryRequest::REQUEST_VERSION,
+            nonce: 1,
+            requests: vec![],
+        },
+        responses: per_chain
+            .into_iter()
+            .map(|(chain_id, response)| PerChainQueryResponse { chain_id, response })
+            .collect(),
+    }
+}
+
+fn eth_call(results: Vec<Vec<u8>>) -> ChainSpecificResponse {
+    ChainSpecificResponse::EthCallQueryResponse(EthCallQueryResponse {
+        block_number: 100,
+        block_hash: [1; 32],
+        block_time: BLOCK_TIME_MICROS,
+        results,
+    })
+}
+
+fn sol_account(data_len: usize) -> ChainSpecificResponse {
+    ChainSpecificResponse::SolanaAccountQueryResponse(SolanaAccountQueryResponse {
+        slot_number: 100,
+        block_time: BLOCK_TIME_MICROS,
+        block_hash: [1; 32],
+        results: vec![SolanaAccountResult {
+            lamports: 1,
+            rent_epoch: 2,
+            executable: false,
+            owner: [3; 32],
+            data: vec![0; data_len],
+        }],
+    })
+}
+
+fn after_block(lag: Duration) -> SystemTime {
+    UNIX_EPOCH + Duration::from_micros(BLOCK_TIME_MICROS) + lag
+}
+
+#[test]
+fn ingest_counts_responses_and_result_sizes() {
+    let mut stats = ResponseStats::new();
+    stats.ingest(
+        &response(vec![
+            (2, eth_call(vec![vec![0; 32], vec![0; 100]])),
+            (1, sol_account(5000)),
+        ]),
+        after_block(Duration::ZERO),
+    );
+    stats.ingest(
+        &response(vec![(2, eth_call(vec![vec![0; 32]]))]),
+        after_block(Duration::ZERO),
+    );
+
+    let ethereum = &stats.chains[&ChainId::ETHEREUM];
+    assert_eq!((ethereum.responses, ethereum.results), (2, 3));
+    assert_eq!(ethereum.result_bytes.count, 3);
+    assert_eq!(ethereum.result_bytes.sum, 164.0);
+    let buckets: Vec<(f64, u64)> = ethereum.result_bytes.buckets().collect();
+    assert_eq!(
+        buckets,
+        [
+            (32.0, 2),
+            (64.0, 2),
+            (256.0, 3),
+            (1024.0, 3),
+            (4096.0, 3),
+            (16384.0, 3),
+            (65536.0, 3)
+        ]
+    );
+
+    let solana = &stats.chains[&ChainId::SOLANA];
+    assert_eq!((solana.responses, solana.results), (1, 1));
+    assert_eq!(solana.result_bytes.buckets().nth(4), Some((4096.0, 0)));
+    assert_eq!(solana.result_bytes.buckets().nth(5), Some((16384.0, 1)));
+}
+
+#[test]
+fn block_lag_is_measured_in_seconds() {
+    let mut stats = ResponseStats::new();
+    stats.ingest(
+        &response(vec![(2, eth_call(vec![]))]),
+        after_block(Duration::from_millis(1500)),
+    );
+    stats.ingest(
+        &response(vec![(2, eth_call(vec![]))]),
+        after_block(Duration::from_secs(600)),
+    );
+    // A block newer than the request counts as no lag.
+    stats.ingest(
+        &response(vec![(2, eth_call(vec![]))]),
+        after_block(Duration::ZERO) - Duration::from_secs(5),
+    );
+
+    let lag = &stats.chains[&ChainId::ETHEREUM].block_lag_seconds;
+    assert_eq!((lag.count, lag.sum), (3, 601.5));
+    let buckets: Vec<u64> = lag.buckets().map(|(_, count)| count).collect();
+    assert_eq!(buckets, [1, 2, 2, 2, 2, 2, 2, 3]);
+}
+
+#[test]
+fn gather_renders_prometheus_text() {
+    let mut stats = ResponseStats::new();
+    stats.ingest(
+        &response(vec![
+            (5, eth_call(vec![vec![0; 32]])),
+            (2, eth_call(vec![vec![0; 64], vec![0; 64]])),
+        ]),
+        after_block(Duration::from_secs(3)),
+    );
+    let text = stats.gather();
+
+    for line in [
+        "# TYPE ccq_responses_total counter",
+        "ccq_responses_total{chain=\"2\"} 1",
+        "ccq_responses_total{chain=\"5\"} 1",
+        "ccq_results_total{chain=\"2\"} 2",
+        "# TYPE ccq_result_bytes histogram",
+        "ccq_result_bytes_bucket{chain=\"2\",le=\"32\"} 0",
+        "ccq_result_bytes_bucket{chain=\"2\",le=\"64\"} 2",
+        "ccq_result_bytes_bucket{chain=\"2\",le=\"+Inf\"} 2",
+        "ccq_result_bytes_sum{chain=\"2\"} 128",
+        "ccq_result_bytes_count{chain=\"2\"} 2",
+        "ccq_block_lag_seconds_bucket{chain=\"5\",le=\"2\"} 0",
+        "ccq_block_lag_seconds_bucket{chain=\"5\",le=\"5\"} 1",
+        "ccq_block_lag_seconds_sum{chain=\"5\"} 3",
+    ] {
+        assert!(text.lines().any(|l| l == line), "missing {:?}", line);
+    }
+
+    // Chains are rendered in id order, and every sample line is `name{labels} value`.
+    let first_sample = |chain: &str| {
+        text.find(&format!("ccq_responses_total{{chain=\"{}\"}}", chain))
+            .unwrap()
+    };
+    assert!(first_sample("2") < first_sample("5"));
+    for line in text.lines().filter(|l| !l.starts_with('#')) {
+        let (series, value) = line.rsplit_once(' ').unwrap();
+        assert!(
+            series.starts_with("ccq_") && series.ends_with('}'),
+            "{}",
+            line
+        );
+        value.parse::<f64>().unwrap();
+    }
+    for line in text.lines().filter(|l| l.starts_with('#')) {
+        assert!(
+            line.starts_with("# HELP ccq_") || line.starts_with("# TYPE ccq_"),
+            "{}",
+            line
+        );
+    }
+}
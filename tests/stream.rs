@@ -0,0 +1,83 @@
+use wormhole_query_sdk::stream::{write_framed, ResponseStream};
+use wormhole_query_sdk::structs::*;
+
+fn response(nonce: u32) -> QueryResponse {
+    QueryResponse {
+        version: QueryResponse::RESPONSE_VERSION,
+        request_chain_id: 0,
+        request_id: vec![0; 65],
+        request: QueryRequest {
+            version: QueryRequest::REQUEST_VERSION,
+            nonce,
+            requests: vec![PerChainQueryRequest {
+                chain_id: 2,
+                query: ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+                    block_tag: "latest".to_string(),
+                    call_data: vec![],
+                }),
+            }],
+        },
+        responses: vec![PerChainQueryResponse {
+            chain_id: 2,
+            response: ChainSpecificResponse::EthCallQueryResponse(EthCallQueryResponse {
+                block_number: 100,
+                block_hash: [1; 32],
+                block_time: 10,
+                results: vec![],
+            }),
+        }],
+    }
+}
+
+/// Two responses with an unparsable record between them.
+fn framed() -> Vec<u8> {
+    let mut data = Vec::new();
+    write_framed(&mut data, &response(1).serialize().unwrap()).unwrap();
+    write_framed(&mut data, &[0xff, 0xff]).unwrap();
+    write_framed(&mut data, &response(2).serialize().unwrap()).unwrap();
+    data
+}
+
+#[test]
+fn bad_records_are_skipped() {
+    let data = framed();
+    let mut stream = ResponseStream::new(data.as_slice());
+    assert_eq!(stream.next().unwrap().unwrap(), response(1));
+    assert!(stream.next().unwrap().is_err());
+    assert_eq!(stream.next().unwrap().unwrap(), response(2));
+    assert!(stream.next().is_none());
+}
+
+#[test]
+fn truncated_frame_ends_the_stream() {
+    let data = framed();
+    let mut stream = ResponseStream::new(&data[..data.len() - 1]);
+    assert_eq!(stream.next().unwrap().unwrap(), response(1));
+    assert!(stream.next().unwrap().is_err());
+    assert_eq!(
+        stream.next().unwrap().unwrap_err().to_string(),
+        "TruncatedRecord"
+    );
+    assert!(stream.next().is_none());
+
+    let mut stream = ResponseStream::new(&data[..2]);
+    assert_eq!(
+        stream.next_record().unwrap().unwrap_err().to_string(),
+        "TruncatedRecord"
+    );
+    assert!(stream.next_record().is_none());
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn async_reads_match_sync_reads() {
+    let data = framed();
+    let mut stream = ResponseStream::new(data.as_slice());
+    assert_eq!(stream.next_async().await.unwrap().unwrap(), response(1));
+    assert_eq!(
+        stream.next_record_async().await.unwrap().unwrap(),
+        [0xff, 0xff]
+    );
+    assert_eq!(stream.next_async().await.unwrap().unwrap(), response(2));
+    assert!(stream.next_async().await.is_none());
+}
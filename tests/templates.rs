@@ -0,0 +1,88 @@
+use wormhole_query_sdk::chain::ChainId;
+use wormhole_query_sdk::templates::*;
+
+const CORE_BRIDGE: [u8; 20] = [0x98; 20];
+const TOKEN_BRIDGE: [u8; 20] = [0x3e; 20];
+
+fn word(low: &[u8]) -> Vec<u8> {
+    let mut word = vec![0u8; 32 - low.len()];
+    word.extend_from_slice(low);
+    word
+}
+
+#[test]
+fn calls_encode_selector_and_arguments() {
+    let call = next_sequence(CORE_BRIDGE, [0x11; 20]);
+    assert_eq!(call.to, CORE_BRIDGE);
+    assert_eq!(call.data[..4], [0x4c, 0xf8, 0x42, 0xb5]);
+    assert_eq!(call.data[4..], word(&[0x11; 20]));
+
+    let call = wrapped_asset(TOKEN_BRIDGE, ChainId(0x1234), [0x22; 32]);
+    assert_eq!(call.to, TOKEN_BRIDGE);
+    assert_eq!(call.data.len(), 4 + 64);
+    assert_eq!(call.data[4..36], word(&[0x12, 0x34]));
+    assert_eq!(call.data[36..], [0x22; 32]);
+
+    assert_eq!(current_guardian_set_index(CORE_BRIDGE).data.len(), 4);
+}
+
+#[cfg(feature = "crypto")]
+#[test]
+fn selectors_match_signatures() {
+    use wormhole_query_sdk::signature::keccak256;
+
+    let calls = [
+        ("nextSequence(address)", next_sequence(CORE_BRIDGE, [0; 20])),
+        (
+            "getCurrentGuardianSetIndex()",
+            current_guardian_set_index(CORE_BRIDGE),
+        ),
+        ("messageFee()", message_fee(CORE_BRIDGE)),
+        (
+            "wrappedAsset(uint16,bytes32)",
+            wrapped_asset(TOKEN_BRIDGE, ChainId::SOLANA, [0; 32]),
+        ),
+        (
+            "isWrappedAsset(address)",
+            is_wrapped_asset(TOKEN_BRIDGE, [0; 20]),
+        ),
+        (
+            "bridgeContracts(uint16)",
+            bridge_contracts(TOKEN_BRIDGE, ChainId::SOLANA),
+        ),
+    ];
+    for (signature, call) in calls {
+        assert_eq!(
+            call.data[..4],
+            keccak256(signature.as_bytes())[..4],
+            "{}",
+            signature
+        );
+    }
+}
+
+#[test]
+fn results_decode_from_words() {
+    assert_eq!(decode_u64(&word(&[1, 2])).unwrap(), 0x0102);
+    assert_eq!(decode_u32(&word(&[4])).unwrap(), 4);
+    assert_eq!(decode_address(&word(&[0x33; 20])).unwrap(), [0x33; 20]);
+    assert!(decode_bool(&word(&[1])).unwrap());
+    assert!(!decode_bool(&word(&[])).unwrap());
+    assert_eq!(decode_bytes32(&[0x44; 32]).unwrap(), [0x44; 32]);
+}
+
+#[test]
+fn malformed_results_are_rejected() {
+    assert_eq!(
+        decode_u64(&[0; 31]).unwrap_err().to_string(),
+        "InvalidResultLength"
+    );
+    assert_eq!(
+        decode_u32(&word(&[1, 0, 0, 0, 0])).unwrap_err().to_string(),
+        "InvalidResultValue"
+    );
+    assert_eq!(
+        decode_bool(&word(&[2])).unwrap_err().to_string(),
+        "InvalidResultValue"
+    );
+}
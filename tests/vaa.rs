@@ -0,0 +1,174 @@
+use wormhole_query_sdk::chain::ChainId;
+use wormhole_query_sdk::structs::*;
+use wormhole_query_sdk::vaa::OnChainRequest;
+
+// A VAA emitted on Ethereum whose payload is an eth_call request for WETH's totalSupply()
+// at block 0x1234, signed by the devnet guardian as a Tilt devnet would.
+const VAA: &str = concat!(
+    // version, guardian set index, one signature
+    "01",
+    "00000000",
+    "01",
+    // guardian index, r, s, v
+    "00",
+    "ded3fc48b6dab9c30131f73ed766e4f1233822cb7d5138b0a70ef64c904a6245",
+    "483b5c186cfaef6a087f5c5a9d28df0f9aafdea40f98502eaa7442ddc4fae205",
+    "01",
+    // timestamp, nonce, emitter chain, emitter address, sequence, consistency level
+    "6553f100",
+    "00000000",
+    "0002",
+    "0000000000000000000000000000000000000000000000000000000000c0ffee",
+    "0000000000000007",
+    "c8",
+    // query request payload
+    "010000002a010002010000002700000006307831323334",
+    "01c02aaa39b223fe8d0a0e5c4f27ead9083c756cc20000000418160ddd",
+);
+
+/// keccak256(keccak256(body)), the digest the devnet guardian signed.
+#[cfg(feature = "crypto")]
+const VAA_HASH: &str = "62d777a847d910d3e7787a4f0b5e03ee974761ea9e0cda10f6e9c5c88af690ea";
+
+/// Version, guardian set index, signature count and one signature.
+const BODY_START: usize = 6 + 66;
+
+fn vaa() -> Vec<u8> {
+    hex::decode(VAA).unwrap()
+}
+
+#[test]
+fn from_vaa_skips_header_and_signatures() {
+    let vaa = vaa();
+    let request = OnChainRequest::from_vaa(&vaa).unwrap();
+
+    assert_eq!(request.emitter_chain, ChainId::ETHEREUM);
+    assert_eq!(request.emitter_address[29..], [0xc0, 0xff, 0xee]);
+    assert_eq!(request.sequence, 7);
+    assert_eq!(request.body, vaa[BODY_START..]);
+    assert_eq!(request.request.nonce, 0x2a);
+    assert_eq!(request.request.requests.len(), 1);
+    assert_eq!(request.request.requests[0].chain(), ChainId::ETHEREUM);
+    match &request.request.requests[0].query {
+        ChainSpecificQuery::EthCallQueryRequest(query) => {
+            assert_eq!(query.block_tag, "0x1234");
+            assert_eq!(query.call_data[0].data, [0x18, 0x16, 0x0d, 0xdd]);
+        }
+        other => panic!("unexpected query {:?}", other),
+    }
+
+    assert_eq!(
+        OnChainRequest::from_body(&vaa[BODY_START..]).unwrap(),
+        request
+    );
+}
+
+#[test]
+fn truncated_vaas_are_rejected() {
+    let vaa = vaa();
+
+    // Cut inside the signatures, or claiming more signatures than are present.
+    for truncated in [&vaa[..BODY_START - 1], &vaa[..6]] {
+        let err = OnChainRequest::from_vaa(truncated).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+    let mut extra_signatures = vaa.clone();
+    extra_signatures[5] = 200;
+    let err = OnChainRequest::from_vaa(&extra_signatures).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+
+    // Cut inside the body header, or inside the request payload.
+    for len in [BODY_START + 10, vaa.len() - 1] {
+        OnChainRequest::from_vaa(&vaa[..len]).unwrap_err();
+    }
+
+    let mut version_two = vaa.clone();
+    version_two[0] = 2;
+    assert_eq!(
+        OnChainRequest::from_vaa(&version_two)
+            .unwrap_err()
+            .to_string(),
+        "InvalidVaaVersion"
+    );
+}
+
+#[cfg(feature = "crypto")]
+mod crypto {
+    use super::*;
+    use wormhole_query_sdk::signature::recover_address;
+
+    /// `0xbeFA429d57cD18b7F8A4d91A2da9AB4AF05d0FBe`
+    const DEVNET_GUARDIAN: &str = "befa429d57cd18b7f8a4d91a2da9ab4af05d0fbe";
+
+    fn on_chain_request() -> OnChainRequest {
+        OnChainRequest::from_vaa(&vaa()).unwrap()
+    }
+
+    fn eth_call_response() -> ChainSpecificResponse {
+        ChainSpecificResponse::EthCallQueryResponse(EthCallQueryResponse {
+            block_number: 0x1234,
+            block_hash: [1; 32],
+            block_time: 1_700_000_000_000_000,
+            results: vec![vec![2; 32]],
+        })
+    }
+
+    #[test]
+    fn request_id_is_the_digest_guardians_sign() {
+        let vaa = vaa();
+        let request_id = on_chain_request().request_id();
+        assert_eq!(hex::encode(request_id), VAA_HASH);
+
+        // The VAA's own signature recovers to the devnet guardian over this digest.
+        let signature = &vaa[7..BODY_START];
+        assert_eq!(
+            hex::encode(recover_address(&request_id, signature).unwrap()),
+            DEVNET_GUARDIAN
+        );
+    }
+
+    #[test]
+    fn responses_carry_the_emitter_chain_and_a_32_byte_request_id() {
+        let request = on_chain_request();
+        let response = request.response(vec![eth_call_response()]).unwrap();
+        assert_eq!(response.request_chain_id, 2);
+        assert_eq!(response.request_id, hex::decode(VAA_HASH).unwrap());
+
+        // A non zero request chain id selects the 32 byte request id when parsing.
+        let bytes = response.serialize().unwrap();
+        assert_eq!(bytes[3..35], response.request_id[..]);
+        let parsed = QueryResponse::deserialize(&bytes).unwrap();
+        assert_eq!(parsed, response);
+        parsed.check_on_chain_request(&request).unwrap();
+    }
+
+    #[test]
+    fn responses_to_other_requests_are_rejected() {
+        let request = on_chain_request();
+        let response = request.response(vec![eth_call_response()]).unwrap();
+
+        let mut other_chain = response.clone();
+        other_chain.request_chain_id = 5;
+        let mut other_id = response.clone();
+        other_id.request_id[0] ^= 1;
+        for response in [other_chain, other_id] {
+            assert_eq!(
+                response
+                    .check_on_chain_request(&request)
+                    .unwrap_err()
+                    .to_string(),
+                "RequestIdMismatch"
+            );
+        }
+
+        let mut other_request = response;
+        other_request.request.nonce += 1;
+        assert_eq!(
+            other_request
+                .check_on_chain_request(&request)
+                .unwrap_err()
+                .to_string(),
+            "RequestMismatch"
+        );
+    }
+}
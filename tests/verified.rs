@@ -0,0 +1,112 @@
+use wormhole_query_sdk::chain::ChainId;
+use wormhole_query_sdk::structs::*;
+
+fn response() -> QueryResponse {
+    let call = |to: u8| EthCallData {
+        to: [to; 20],
+        data: vec![to],
+    };
+    let request = QueryRequest {
+        version: QueryRequest::REQUEST_VERSION,
+        nonce: 1,
+        requests: vec![
+            PerChainQueryRequest::new(
+                2,
+                ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+                    block_tag: "latest".to_string(),
+                    call_data: vec![call(1), call(2)],
+                }),
+            ),
+            PerChainQueryRequest::new(
+                1,
+                ChainSpecificQuery::SolanaAccountQueryRequest(SolanaAccountQueryRequest {
+                    commitment: "finalized".to_string(),
+                    min_context_slot: 0,
+                    data_slice_offset: 0,
+                    data_slice_length: 0,
+                    accounts: vec![],
+                }),
+            ),
+            PerChainQueryRequest::new(
+                5,
+                ChainSpecificQuery::EthCallWithFinalityQueryRequest(
+                    EthCallWithFinalityQueryRequest {
+                        block_tag: "0x10".to_string(),
+                        finality: "finalized".to_string(),
+                        call_data: vec![call(3)],
+                    },
+                ),
+            ),
+        ],
+    };
+    QueryResponse::from_parts(
+        request,
+        vec![
+            ChainSpecificResponse::EthCallQueryResponse(EthCallQueryResponse {
+                block_number: 100,
+                block_hash: [1; 32],
+                block_time: 10,
+                results: vec![vec![0xa1], vec![0xa2]],
+            }),
+            ChainSpecificResponse::SolanaAccountQueryResponse(SolanaAccountQueryResponse {
+                slot_number: 7,
+                block_time: 20,
+                block_hash: [2; 32],
+                results: vec![],
+            }),
+            ChainSpecificResponse::EthCallWithFinalityQueryResponse(
+                EthCallWithFinalityQueryResponse {
+                    block_number: 16,
+                    block_hash: [3; 32],
+                    block_time: 30,
+                    results: vec![vec![0xa3]],
+                },
+            ),
+        ],
+    )
+    .unwrap()
+}
+
+#[test]
+fn calls_are_flattened_across_query_types() {
+    let calls = response().verified_calls().unwrap();
+    let flat: Vec<(ChainId, u8, u64, Vec<u8>)> = calls
+        .iter()
+        .map(|call| {
+            (
+                call.chain,
+                call.to[0],
+                call.block.number,
+                call.result.clone(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        flat,
+        [
+            (ChainId::ETHEREUM, 1, 100, vec![0xa1]),
+            (ChainId::ETHEREUM, 2, 100, vec![0xa2]),
+            (ChainId::POLYGON, 3, 16, vec![0xa3]),
+        ]
+    );
+    assert_eq!(calls[1].calldata, [2]);
+}
+
+#[test]
+fn misaligned_responses_are_rejected() {
+    let mut response = response();
+    response.responses.swap(0, 2);
+    for err in [
+        response.verified_calls().unwrap_err(),
+        response.indexed().unwrap_err(),
+    ] {
+        assert_eq!(err.to_string(), "RequestResponseMismatch");
+    }
+
+    let mut response = self::response();
+    response.responses.pop();
+    assert_eq!(
+        response.verified_calls().unwrap_err().to_string(),
+        "RequestResponseMismatch"
+    );
+}
@@ -0,0 +1,90 @@
+use wormhole_query_sdk::chain::ChainId;
+use wormhole_query_sdk::structs::*;
+use wormhole_query_sdk::visit::ResponseVisitor;
+
+#[derive(Default)]
+struct Calls {
+    eth_calls: Vec<(ChainId, usize)>,
+}
+
+impl ResponseVisitor for Calls {
+    fn visit_eth_call(
+        &mut self,
+        chain: ChainId,
+        _request: &EthCallQueryRequest,
+        response: &EthCallQueryResponse,
+    ) {
+        self.eth_calls.push((chain, response.results.len()));
+    }
+}
+
+fn eth_call(chain_id: u16, results: usize) -> (PerChainQueryRequest, PerChainQueryResponse) {
+    (
+        PerChainQueryRequest::new(
+            chain_id,
+            ChainSpecificQuery::EthCallQueryRequest(EthCallQueryRequest {
+                block_tag: "latest".to_string(),
+                call_data: vec![
+                    EthCallData {
+                        to: [1; 20],
+                        data: vec![],
+                    };
+                    results
+                ],
+            }),
+        ),
+        PerChainQueryResponse::new(
+            chain_id,
+            ChainSpecificResponse::EthCallQueryResponse(EthCallQueryResponse {
+                block_number: 100,
+                block_hash: [1; 32],
+                block_time: 10,
+                results: vec![vec![0; 32]; results],
+            }),
+        ),
+    )
+}
+
+fn query_response(
+    requests: Vec<PerChainQueryRequest>,
+    responses: Vec<PerChainQueryResponse>,
+) -> QueryResponse {
+    QueryResponse {
+        version: QueryResponse::RESPONSE_VERSION,
+        request_chain_id: 0,
+        request_id: vec![0; 65],
+        request: QueryRequest {
+            version: QueryRequest::REQUEST_VERSION,
+            nonce: 1,
+            requests,
+        },
+        responses,
+    }
+}
+
+#[test]
+fn walk_visits_each_chain_in_order() {
+    let (requests, responses) = [eth_call(2, 1), eth_call(5, 2)].into_iter().unzip();
+    let response = query_response(requests, responses);
+
+    let mut calls = Calls::default();
+    response.walk(&mut calls).unwrap();
+    assert_eq!(
+        calls.eth_calls,
+        vec![(ChainId::ETHEREUM, 1), (ChainId::POLYGON, 2)]
+    );
+}
+
+#[test]
+fn walk_rejects_misaligned_responses() {
+    let (request, response) = eth_call(2, 1);
+    let mut response = query_response(vec![request], vec![response]);
+    response.responses[0].chain_id = 5;
+
+    let mut calls = Calls::default();
+    assert_eq!(
+        response.walk(&mut calls).unwrap_err().to_string(),
+        "RequestResponseMismatch"
+    );
+    assert!(calls.eth_calls.is_empty());
+}